@@ -0,0 +1,36 @@
+//! Archive command - moves finished task groups out of the active graph.
+
+use std::time::Duration;
+
+use owo_colors::OwoColorize;
+
+use crate::error_fmt::AppError;
+use crate::jj;
+use crate::MontContext;
+
+/// Move finished task groups into `.tasks/archive`, optionally only those
+/// completed or cancelled at least `older_than` ago.
+pub fn archive(ctx: &MontContext, older_than: Option<Duration>) -> Result<(), AppError> {
+    let ids = ctx.archive(older_than)?;
+
+    if ids.is_empty() {
+        println!("Nothing to archive");
+        return Ok(());
+    }
+
+    for id in &ids {
+        println!("{} {}", "archived:".dimmed(), id.bright_yellow());
+    }
+
+    // Auto-commit if jj is enabled
+    if ctx.config().jj.enabled {
+        let message = format!("Archive {} task{}", ids.len(), if ids.len() == 1 { "" } else { "s" });
+        match jj::commit(&message, &[ctx.tasks_dir()]) {
+            Ok(result) if result.committed => println!("{}", "committed".bright_green()),
+            Ok(_) => {} // Nothing to commit (e.g., .tasks is gitignored)
+            Err(e) => eprintln!("{}: failed to auto-commit: {}", "warning".yellow(), e),
+        }
+    }
+
+    Ok(())
+}
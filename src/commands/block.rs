@@ -0,0 +1,74 @@
+//! Block command - mark a task as unable to proceed, or clear the block.
+
+use chrono::Utc;
+use owo_colors::OwoColorize;
+
+use crate::error_fmt::AppError;
+use crate::{Blocker, MontContext, Status};
+
+/// Mark a task as blocked with a reason and optional external reference.
+///
+/// Blocked tasks are excluded from `mont ready` until unblocked. Blocking an
+/// in-progress task closes its work session.
+pub fn block(
+    ctx: &MontContext,
+    id: &str,
+    reason: &str,
+    reference: Option<&str>,
+) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let task = graph.get(id).ok_or_else(|| AppError::TaskNotFound {
+        task_id: id.to_string(),
+        tasks_dir: ctx.tasks_dir().display().to_string(),
+    })?;
+
+    if task.is_complete() {
+        return Err(AppError::TaskAlreadyComplete(id.to_string()));
+    }
+    if task.is_cancelled() {
+        return Err(AppError::TaskCancelled(id.to_string()));
+    }
+    if task.is_gate() {
+        return Err(AppError::InvalidArgs(format!("gate '{}' cannot be blocked", id)));
+    }
+    if reason.trim().is_empty() {
+        return Err(AppError::InvalidArgs("--reason cannot be empty".to_string()));
+    }
+
+    let mut updated_task = task.clone();
+    updated_task.status = Some(Status::Blocked);
+    updated_task.blocker = Some(Blocker {
+        reason: reason.trim().to_string(),
+        reference: reference.map(str::to_string),
+    });
+    updated_task.end_session(Utc::now());
+    drop(graph);
+
+    ctx.update(id, updated_task)?;
+
+    println!("Blocked task '{}': {}", id.red(), reason.trim());
+    Ok(())
+}
+
+/// Clear the blocked status from a task, making it ready for work again.
+pub fn unblock(ctx: &MontContext, id: &str) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let task = graph.get(id).ok_or_else(|| AppError::TaskNotFound {
+        task_id: id.to_string(),
+        tasks_dir: ctx.tasks_dir().display().to_string(),
+    })?;
+
+    if !task.is_blocked() {
+        return Err(AppError::TaskNotBlocked(id.to_string()));
+    }
+
+    let mut updated_task = task.clone();
+    updated_task.status = None;
+    updated_task.blocker = None;
+    drop(graph);
+
+    ctx.update(id, updated_task)?;
+
+    println!("Unblocked task '{}'", id.green());
+    Ok(())
+}
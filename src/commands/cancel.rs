@@ -0,0 +1,49 @@
+//! Cancel command - mark a task as won't-do while keeping it as a record.
+
+use chrono::Utc;
+use owo_colors::OwoColorize;
+
+use crate::commands::shared::complete_finished_epics;
+use crate::error_fmt::AppError;
+use crate::{Cancellation, MontContext, Status};
+
+/// Mark a task as cancelled, with an optional reason.
+///
+/// The task file is kept so abandoned ideas remain visible in retrospectives.
+/// Cancelling an in-progress task closes its work session.
+pub fn cancel(ctx: &MontContext, id: &str, reason: Option<&str>) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let task = graph.get(id).ok_or_else(|| AppError::TaskNotFound {
+        task_id: id.to_string(),
+        tasks_dir: ctx.tasks_dir().display().to_string(),
+    })?;
+
+    if task.is_complete() {
+        return Err(AppError::TaskAlreadyComplete(id.to_string()));
+    }
+    if task.is_cancelled() {
+        return Err(AppError::TaskCancelled(id.to_string()));
+    }
+    if task.is_gate() {
+        return Err(AppError::InvalidArgs(format!("gate '{}' cannot be cancelled", id)));
+    }
+
+    let now = Utc::now();
+    let mut updated_task = task.clone();
+    updated_task.status = Some(Status::Cancelled);
+    updated_task.blocker = None;
+    updated_task.cancelled = Some(Cancellation {
+        at: now,
+        reason: reason.map(str::trim).filter(|r| !r.is_empty()).map(str::to_string),
+    });
+    updated_task.end_session(now);
+    drop(graph);
+
+    ctx.update(id, updated_task)?;
+
+    println!("Cancelled task '{}'", id.bright_black());
+    for epic_id in complete_finished_epics(ctx)? {
+        println!("Epic '{}' complete: all of its remaining tasks are done", epic_id.green());
+    }
+    Ok(())
+}
@@ -1,10 +1,70 @@
 //! Check command - validates the task graph.
 
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::Utc;
+use owo_colors::OwoColorize;
+
+use crate::context::{lint, GraphReadError, LintEnv, LintFinding, LoadError, Severity};
 use crate::error_fmt::AppError;
-use crate::MontContext;
+use crate::{jj, MontContext};
+
+use super::shared::canonical_id;
+
+/// Exit code when the graph has validation errors, or lint errors.
+const EXIT_INVALID: i32 = 1;
+/// Exit code when task files couldn't be read or parsed.
+const EXIT_UNREADABLE: i32 = 2;
+
+/// One line of a check report.
+struct Problem {
+    severity: Severity,
+    message: String,
+}
+
+/// Validate the task graph and lint its tasks, optionally checking a specific task.
+///
+/// Loads the graph itself so that every problem can be reported, grouped by
+/// the file it should be fixed in, rather than just the load error. Lint
+/// warnings only fail the check when `strict` is set.
+pub fn check(tasks_dir: &Path, id: Option<&str>, strict: bool) -> Result<(), AppError> {
+    let errors = match MontContext::load(tasks_dir.to_path_buf()) {
+        Ok(ctx) => return check_valid(&ctx, id, strict),
+        Err(LoadError::Graph(errors)) => errors,
+        Err(e) => return Err(e.into()),
+    };
+
+    let file = id.map(|id| task_file(tasks_dir, id));
+    let problems: BTreeMap<String, Vec<Problem>> = problems_by_file(tasks_dir, &errors)
+        .into_iter()
+        .filter(|(path, _)| file.as_ref().is_none_or(|file| file == path))
+        .collect();
+
+    if problems.is_empty() {
+        // Only reachable with an id: the task is fine, the rest of the graph isn't
+        if let Some(id) = id {
+            println!(
+                "ok: task '{}' is valid ({} problem(s) elsewhere; run `mont check`)",
+                id,
+                errors.error_count()
+            );
+        }
+        return Ok(());
+    }
+
+    let code = if errors.io_errors.is_empty() && errors.parse_errors.is_empty() {
+        EXIT_INVALID
+    } else {
+        EXIT_UNREADABLE
+    };
+    Err(AppError::CheckFailed {
+        report: format_report(&problems, true),
+        code,
+    })
+}
 
-/// Validate the task graph, optionally checking a specific task.
-pub fn check(ctx: &MontContext, id: Option<&str>) -> Result<(), AppError> {
+fn check_valid(ctx: &MontContext, id: Option<&str>, strict: bool) -> Result<(), AppError> {
     let graph = ctx.graph();
 
     if graph.is_empty() {
@@ -12,20 +72,144 @@ pub fn check(ctx: &MontContext, id: Option<&str>) -> Result<(), AppError> {
         return Ok(());
     }
 
+    let id = id.map(|id| canonical_id(&graph, id.to_string()));
+    if let Some(task_id) = &id
+        && !graph.contains(task_id)
+    {
+        return Err(AppError::TaskNotFound {
+            task_id: task_id.clone(),
+            tasks_dir: ctx.tasks_dir().display().to_string(),
+        });
+    }
+
+    let config = ctx.config();
+    let env = LintEnv {
+        now: Utc::now(),
+        // Only ask jj when the rule that needs it is on
+        has_code_changes: (config.jj.enabled && config.lint.idle_in_progress != Severity::Off)
+            .then(|| jj::has_code_changes().ok())
+            .flatten(),
+    };
+    let findings: Vec<LintFinding> = lint(&graph, &config.lint, &config.default_gates, &env)
+        .into_iter()
+        .filter(|f| id.as_ref().is_none_or(|id| *id == f.task_id))
+        .collect();
+
+    let mut problems: BTreeMap<String, Vec<Problem>> = BTreeMap::new();
+    for finding in &findings {
+        problems
+            .entry(task_file(ctx.tasks_dir(), &finding.task_id))
+            .or_default()
+            .push(Problem {
+                severity: finding.severity,
+                message: format!("{} [{}]", finding.message, finding.rule.name()),
+            });
+    }
+
+    let failed = (strict && !findings.is_empty()) || findings.iter().any(|f| f.severity == Severity::Error);
+    if failed {
+        return Err(AppError::CheckFailed {
+            report: format_report(&problems, true),
+            code: EXIT_INVALID,
+        });
+    }
+    if !problems.is_empty() {
+        print!("{}", format_report(&problems, false));
+    }
+
     match id {
-        Some(task_id) => {
-            if !graph.contains(task_id) {
-                return Err(AppError::TaskNotFound {
-                    task_id: task_id.to_string(),
-                    tasks_dir: ctx.tasks_dir().display().to_string(),
-                });
-            }
-            println!("ok: task '{}' is valid", task_id);
-        }
-        None => {
-            println!("ok: {} tasks validated", graph.len());
-        }
+        Some(task_id) => println!("ok: task '{}' is valid", task_id),
+        None => println!("ok: {} tasks validated", graph.len()),
     }
 
     Ok(())
 }
+
+fn task_file(tasks_dir: &Path, id: &str) -> String {
+    tasks_dir.join(format!("{}.md", id)).display().to_string()
+}
+
+/// Every problem in `errors`, keyed by the file it was found in.
+fn problems_by_file(tasks_dir: &Path, errors: &GraphReadError) -> BTreeMap<String, Vec<Problem>> {
+    let mut problems: BTreeMap<String, Vec<Problem>> = BTreeMap::new();
+    let mut add = |path: String, message: String| {
+        problems.entry(path).or_default().push(Problem {
+            severity: Severity::Error,
+            message,
+        });
+    };
+
+    for (path, e) in &errors.io_errors {
+        add(path.display().to_string(), e.to_string());
+    }
+    for (path, e) in &errors.parse_errors {
+        add(path.display().to_string(), e.to_string());
+    }
+    for e in &errors.validation_errors {
+        add(task_file(tasks_dir, e.task_id()), e.to_string());
+    }
+
+    problems
+}
+
+/// Format problems under their files, followed by a summary line headed
+/// `error` if the check failed and `warning` otherwise.
+fn format_report(problems: &BTreeMap<String, Vec<Problem>>, failed: bool) -> String {
+    let mut out = String::new();
+    let (mut errors, mut warnings) = (0, 0);
+
+    for (path, file_problems) in problems {
+        out.push_str(&format!("{}\n", path.cyan()));
+        for problem in file_problems {
+            let bullet = if problem.severity == Severity::Error {
+                errors += 1;
+                "•".red().to_string()
+            } else {
+                warnings += 1;
+                "•".yellow().to_string()
+            };
+            out.push_str(&format!("  {} {}\n", bullet, problem.message));
+        }
+    }
+
+    let label = if failed {
+        "error".red().bold().to_string()
+    } else {
+        "warning".yellow().bold().to_string()
+    };
+    out.push_str(&format!(
+        "\n{}: {} error(s), {} warning(s) in {} file(s)\n",
+        label,
+        errors,
+        warnings,
+        problems.len()
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{CycleEdge, EdgeKind, ParseError, ValidationError};
+
+    #[test]
+    fn test_problems_grouped_by_file() {
+        let mut errors = GraphReadError::new();
+        errors.add_parse_error(".tasks/broken.md".into(), ParseError::EmptyId);
+        errors.add_validation_error(ValidationError::InvalidAfter {
+            task_id: "api".to_string(),
+            after_id: "missing".to_string(),
+        });
+        errors.add_validation_error(ValidationError::CycleDetected {
+            cycle: vec![
+                CycleEdge { from: "api".to_string(), to: "db".to_string(), kind: EdgeKind::After },
+                CycleEdge { from: "db".to_string(), to: "api".to_string(), kind: EdgeKind::After },
+            ],
+        });
+
+        let problems = problems_by_file(Path::new(".tasks"), &errors);
+        let files: Vec<&str> = problems.keys().map(String::as_str).collect();
+        assert_eq!(files, vec![".tasks/api.md", ".tasks/broken.md"]);
+        assert_eq!(problems[".tasks/api.md"].len(), 2);
+    }
+}
@@ -0,0 +1,73 @@
+//! Critical path command - the longest chain of open work.
+
+use owo_colors::OwoColorize;
+
+use super::shared::canonical_id;
+use crate::context::graph::{critical_path as find_critical_path, is_available};
+use crate::error_fmt::AppError;
+use crate::render::{format_task_line, task_marker};
+use crate::MontContext;
+
+/// Show the longest chain of open tasks leading to `target`, or the longest
+/// chain in the whole graph.
+///
+/// The first tasks on the chain are the ones to start now: finishing them
+/// sooner is the only way to finish the target sooner.
+pub fn critical_path(ctx: &MontContext, target: Option<&str>) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let config = ctx.config();
+
+    let target = target.map(|target| canonical_id(&graph, target.to_string()));
+    let target = target.as_deref();
+    if let Some(target) = target
+        && !graph.contains(target)
+    {
+        return Err(AppError::TaskNotFound {
+            task_id: target.to_string(),
+            tasks_dir: ctx.tasks_dir().display().to_string(),
+        });
+    }
+
+    let path = find_critical_path(&graph, target);
+    if path.tasks.is_empty() {
+        match target {
+            Some(target) => println!("No open work left for {}", target.bold()),
+            None => println!("No open tasks"),
+        }
+        return Ok(());
+    }
+
+    let mut summary = format!("{} task(s)", path.tasks.len());
+    if !path.estimate.is_zero() {
+        summary.push_str(&format!(", {} estimated", path.estimate));
+    }
+    if path.unestimated > 0 {
+        summary.push_str(&format!(", {} without estimate", path.unestimated));
+    }
+    match target {
+        Some(target) => println!("Critical path to {} ({}):", target.bold(), summary),
+        None => println!("Critical path ({}):", summary),
+    }
+
+    for task in path.tasks.iter().filter_map(|id| graph.get(id)) {
+        let line = format_task_line(task, &graph, &config.default_gates);
+        match task.estimate {
+            Some(estimate) => println!("  {} {} {}", task_marker(task, &graph), line, estimate.to_string().dimmed()),
+            None => println!("  {} {}", task_marker(task, &graph), line),
+        }
+    }
+
+    let ready: Vec<&str> = path
+        .tasks
+        .iter()
+        .filter_map(|id| graph.get(id))
+        .filter(|t| !t.is_in_progress() && !t.is_blocked() && !t.is_epic() && is_available(t, &*graph))
+        .map(|t| t.id.as_str())
+        .collect();
+    if !ready.is_empty() {
+        println!();
+        println!("Start with: {}", ready.join(", ").bright_green());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,359 @@
+//! Diff command - compares the task graph at two jj revisions.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use owo_colors::OwoColorize;
+
+use crate::context::{Snapshot, ARCHIVE_DIR};
+use crate::error_fmt::AppError;
+use crate::multieditor::compute_diff;
+use crate::{jj, GateStatus, Status, Task};
+
+/// What happened to one task between two revisions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// Moved into `.tasks/archive`
+    Archived,
+    Renamed { from: String },
+    Changed,
+}
+
+/// One task's changes, with a line per changed field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskChange {
+    /// The task's id in the newer revision (or the older one if it's gone)
+    pub id: String,
+    pub kind: ChangeKind,
+    pub details: Vec<String>,
+}
+
+/// Compare `.tasks` at revisions `from` and `to`, reading the task files that
+/// differ between them with `jj file show`.
+///
+/// Renamed tasks are matched by their uid, or by the old id in their aliases,
+/// so a rename shows up as one change rather than a removal and an addition.
+pub fn diff(tasks_dir: &Path, from: &str, to: &str) -> Result<(), AppError> {
+    let changed: HashSet<PathBuf> = jj::changed_files(from, to, tasks_dir)
+        .map_err(|e| AppError::JJError(e.to_string()))?
+        .into_iter()
+        .collect();
+    let old = read_snapshot(tasks_dir, from, &changed)?;
+    let new = read_snapshot(tasks_dir, to, &changed)?;
+
+    let changes = changes(&old, &new);
+    if changes.is_empty() {
+        println!("No task changes between {} and {}", from.cyan(), to.cyan());
+        return Ok(());
+    }
+
+    let count = |matches: fn(&ChangeKind) -> bool| changes.iter().filter(|c| matches(&c.kind)).count();
+    println!(
+        "{} to {}: {} added, {} removed, {} renamed, {} changed",
+        from.cyan(),
+        to.cyan(),
+        count(|k| matches!(k, ChangeKind::Added)),
+        count(|k| matches!(k, ChangeKind::Removed | ChangeKind::Archived)),
+        count(|k| matches!(k, ChangeKind::Renamed { .. })),
+        count(|k| matches!(k, ChangeKind::Changed)),
+    );
+    println!();
+
+    for change in &changes {
+        let header = match &change.kind {
+            ChangeKind::Added => format!("{} {}", "+".green(), change.id.green().bold()),
+            ChangeKind::Removed => format!("{} {}", "-".red(), change.id.red().bold()),
+            ChangeKind::Archived => format!("{} {} {}", "-".bright_black(), change.id.bold(), "archived".bright_black()),
+            ChangeKind::Renamed { from } => format!("{} {} → {}", "~".yellow(), from, change.id.yellow().bold()),
+            ChangeKind::Changed => format!("{} {}", "~".yellow(), change.id.bold()),
+        };
+        println!("{}", header);
+        for detail in &change.details {
+            println!("    {}", detail);
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the tasks as they were at `rev`, reading only the task files in
+/// `changed` and the config. Tasks in other files are the same at both
+/// revisions, so they can't show up in the diff.
+fn read_snapshot(tasks_dir: &Path, rev: &str, changed: &HashSet<PathBuf>) -> Result<Snapshot, AppError> {
+    let jj_error = |e: jj::JJError| AppError::JJError(e.to_string());
+    let config_path = tasks_dir.join("config.yml");
+    let archive_dir = tasks_dir.join(ARCHIVE_DIR);
+
+    let mut files = Vec::new();
+    for path in jj::file_list(rev, tasks_dir).map_err(jj_error)? {
+        if path == config_path || (changed.contains(&path) && path.extension().is_some_and(|ext| ext == "md")) {
+            let content = jj::file_show(rev, &path).map_err(jj_error)?;
+            files.push((path, content));
+        } else if path.starts_with(&archive_dir) {
+            // Archived tasks are known by their path alone
+            files.push((path, String::new()));
+        }
+    }
+
+    Ok(Snapshot::read(tasks_dir, files)?)
+}
+
+/// Every task that was added, removed, renamed or changed from `old` to
+/// `new`, in id order.
+pub fn changes(old: &Snapshot, new: &Snapshot) -> Vec<TaskChange> {
+    let renames = renames(&old.tasks, &new.tasks);
+
+    // compute_diff treats a task with `new_id` set as a rename of `id`
+    let edited: Vec<Task> = new
+        .tasks
+        .iter()
+        .map(|task| {
+            let mut task = task.clone();
+            if let Some((old_id, _)) = renames.iter().find(|(_, new_id)| **new_id == task.id) {
+                task.new_id = Some(task.id.clone());
+                task.id = old_id.clone();
+            }
+            task
+        })
+        .collect();
+    let diff = compute_diff(&old.tasks, &edited);
+
+    // References are compared by the id they point to in the new revision
+    let old_keys = keys(&old.tasks);
+    let new_keys = keys(&new.tasks);
+    let old_ref = |key: &str| {
+        let id = old_keys.get(key).map_or(key, String::as_str);
+        renames.get(id).cloned().unwrap_or_else(|| id.to_string())
+    };
+    let new_ref = |key: &str| new_keys.get(key).cloned().unwrap_or_else(|| key.to_string());
+
+    let old_by_id: HashMap<&str, &Task> = old.tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mut changes = Vec::new();
+
+    for task in diff.inserts {
+        changes.push(TaskChange { id: task.id, kind: ChangeKind::Added, details: vec![] });
+    }
+    for id in diff.deletes {
+        let kind = if new.archived.contains(&id) { ChangeKind::Archived } else { ChangeKind::Removed };
+        changes.push(TaskChange { id, kind, details: vec![] });
+    }
+    for (old_id, task) in diff.updates {
+        let Some(before) = old_by_id.get(old_id.as_str()) else {
+            continue;
+        };
+        let details = details(before, &task, &old_ref, &new_ref);
+        let kind = if old_id != task.id {
+            ChangeKind::Renamed { from: old_id }
+        } else if details.is_empty() {
+            continue;
+        } else {
+            ChangeKind::Changed
+        };
+        changes.push(TaskChange { id: task.id, kind, details });
+    }
+
+    changes.sort_by(|a, b| a.id.cmp(&b.id));
+    changes
+}
+
+/// Old id -> new id for tasks whose id changed, matched by uid or alias.
+fn renames(old: &[Task], new: &[Task]) -> HashMap<String, String> {
+    let old_ids: HashSet<&str> = old.iter().map(|t| t.id.as_str()).collect();
+    let new_ids: HashSet<&str> = new.iter().map(|t| t.id.as_str()).collect();
+    let by_uid: HashMap<&str, &str> = old
+        .iter()
+        .filter_map(|t| Some((t.uid.as_deref()?, t.id.as_str())))
+        .collect();
+
+    let mut renames = HashMap::new();
+    for task in new.iter().filter(|t| !old_ids.contains(t.id.as_str())) {
+        let from = task
+            .uid
+            .as_deref()
+            .and_then(|uid| by_uid.get(uid).copied())
+            .or_else(|| task.aliases.iter().map(String::as_str).find(|a| old_ids.contains(a)));
+        if let Some(from) = from
+            && !new_ids.contains(from)
+        {
+            renames.insert(from.to_string(), task.id.clone());
+        }
+    }
+    renames
+}
+
+/// Uids and aliases -> task id, for resolving references.
+fn keys(tasks: &[Task]) -> HashMap<String, String> {
+    let mut keys = HashMap::new();
+    for task in tasks {
+        for alias in &task.aliases {
+            keys.insert(alias.clone(), task.id.clone());
+        }
+        if let Some(uid) = &task.uid {
+            keys.insert(uid.clone(), task.id.clone());
+        }
+    }
+    for task in tasks {
+        keys.insert(task.id.clone(), task.id.clone());
+    }
+    keys
+}
+
+/// A line for each field that changed: status, dependency edges and gates in
+/// detail, anything else by name.
+fn details(old: &Task, new: &Task, old_ref: &dyn Fn(&str) -> String, new_ref: &dyn Fn(&str) -> String) -> Vec<String> {
+    let mut details = Vec::new();
+
+    if old.status != new.status {
+        details.push(format!("status: {} → {}", status_name(old.status), status_name(new.status)));
+    }
+
+    for (field, old_edges, new_edges) in [("after", &old.after, &new.after), ("before", &old.before, &new.before)] {
+        let old_edges: BTreeSet<String> = old_edges.iter().map(|k| old_ref(k)).collect();
+        let new_edges: BTreeSet<String> = new_edges.iter().map(|k| new_ref(k)).collect();
+        if let Some(line) = set_change(field, &old_edges, &new_edges) {
+            details.push(line);
+        }
+    }
+
+    let old_gates: HashMap<String, GateStatus> = old.gates.iter().map(|g| (old_ref(&g.id), g.status)).collect();
+    let new_gates: HashMap<String, GateStatus> = new.gates.iter().map(|g| (new_ref(&g.id), g.status)).collect();
+    let old_ids: BTreeSet<String> = old_gates.keys().cloned().collect();
+    let new_ids: BTreeSet<String> = new_gates.keys().cloned().collect();
+    if let Some(line) = set_change("gates", &old_ids, &new_ids) {
+        details.push(line);
+    }
+    for id in old_ids.intersection(&new_ids) {
+        if old_gates[id] != new_gates[id] {
+            details.push(format!(
+                "gate {}: {} → {}",
+                id,
+                gate_status_name(old_gates[id]),
+                gate_status_name(new_gates[id])
+            ));
+        }
+    }
+
+    let others: Vec<&str> = [
+        ("title", old.title != new.title),
+        ("description", old.description != new.description),
+        ("type", old.task_type != new.task_type),
+        ("estimate", old.estimate != new.estimate),
+        ("sessions", old.sessions != new.sessions),
+        ("due", old.due != new.due),
+        ("assignee", old.assignee != new.assignee),
+        ("blocker", old.blocker != new.blocker),
+        ("milestone", old.milestone != new.milestone),
+        ("tags", old.tags != new.tags),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(field, _)| field)
+    .collect();
+    if !others.is_empty() {
+        details.push(format!("also changed: {}", others.join(", ")));
+    }
+
+    details
+}
+
+/// `field: +added -removed`, or `None` if the sets are equal.
+fn set_change(field: &str, old: &BTreeSet<String>, new: &BTreeSet<String>) -> Option<String> {
+    let added = new.difference(old).map(|id| format!("+{}", id).green().to_string());
+    let removed = old.difference(new).map(|id| format!("-{}", id).red().to_string());
+    let parts: Vec<String> = added.chain(removed).collect();
+    (!parts.is_empty()).then(|| format!("{}: {}", field, parts.join(" ")))
+}
+
+fn status_name(status: Option<Status>) -> &'static str {
+    match status {
+        None => "pending",
+        Some(Status::InProgress) => "inprogress",
+        Some(Status::Stopped) => "stopped",
+        Some(Status::Blocked) => "blocked",
+        Some(Status::Complete) => "complete",
+        Some(Status::Cancelled) => "cancelled",
+    }
+}
+
+fn gate_status_name(status: GateStatus) -> &'static str {
+    match status {
+        GateStatus::Pending => "pending",
+        GateStatus::Passed => "passed",
+        GateStatus::Failed => "failed",
+        GateStatus::Skipped => "skipped",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn snapshot(tasks: &[&str]) -> Snapshot {
+        Snapshot {
+            tasks: tasks.iter().map(|content| parse(content).unwrap()).collect(),
+            archived: HashSet::new(),
+        }
+    }
+
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_changes_between_revisions() {
+        let old = snapshot(&[
+            "---\nid: auth\nuid: 01J0000000000000000000AUTH\n---\n",
+            "---\nid: login\nafter: [auth]\ngates:\n  - review\n---\n",
+            "---\nid: review\ntype: gate\n---\n",
+            "---\nid: gone\n---\n",
+            "---\nid: done\nstatus: complete\n---\n",
+            "---\nid: same\n---\n",
+        ]);
+        let mut new = snapshot(&[
+            // Renamed; its uid ties it to the old task
+            "---\nid: auth-backend\nuid: 01J0000000000000000000AUTH\naliases: [auth]\n---\n",
+            // Still refers to the old id, which resolves to the renamed task
+            "---\nid: login\ntitle: Login\nstatus: inprogress\nafter: [auth, db]\ngates:\n  - review: passed\n---\n",
+            "---\nid: review\ntype: gate\n---\n",
+            "---\nid: db\n---\n",
+            "---\nid: same\n---\n",
+        ]);
+        new.archived.insert("done".to_string());
+
+        let changes = changes(&old, &new);
+        let summary: Vec<(&str, &ChangeKind)> = changes.iter().map(|c| (c.id.as_str(), &c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("auth-backend", &ChangeKind::Renamed { from: "auth".to_string() }),
+                ("db", &ChangeKind::Added),
+                ("done", &ChangeKind::Archived),
+                ("gone", &ChangeKind::Removed),
+                ("login", &ChangeKind::Changed),
+            ]
+        );
+
+        let login: Vec<String> = changes[4].details.iter().map(|d| strip_ansi(d)).collect();
+        assert_eq!(
+            login,
+            vec![
+                "status: pending → inprogress",
+                "after: +db",
+                "gate review: pending → passed",
+                "also changed: title",
+            ]
+        );
+    }
+}
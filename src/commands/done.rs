@@ -1,9 +1,11 @@
 //! Done command - mark a task as complete and commit.
 
+use chrono::Utc;
 use owo_colors::OwoColorize;
 
+use crate::commands::shared::complete_finished_epics;
 use crate::error_fmt::AppError;
-use crate::{jj, GateStatus, MontContext, Status};
+use crate::{jj, GateStatus, MontContext};
 
 /// Complete a task.
 ///
@@ -60,14 +62,17 @@ pub fn done(ctx: &MontContext, id: Option<&str>, message: Option<&str>) -> Resul
         });
     }
 
-    // Mark task as complete
+    // Mark task as complete and close the work session
     let mut updated_task = task.clone();
-    updated_task.status = Some(Status::Complete);
+    updated_task.complete(Utc::now());
     drop(graph);
 
     ctx.update(&task_id, updated_task)?;
 
     println!("Marked '{}' as complete", task_id.green());
+    for epic_id in complete_finished_epics(ctx)? {
+        println!("Epic '{}' complete: all of its tasks are done", epic_id.green());
+    }
     println!();
 
     // Run jj commit (skip if jj is disabled)
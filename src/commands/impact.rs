@@ -0,0 +1,57 @@
+//! Impact command - what finishing a task would unblock.
+
+use owo_colors::OwoColorize;
+
+use super::shared::canonical_id;
+use crate::context::graph::impact as find_impact;
+use crate::error_fmt::AppError;
+use crate::render::{format_task_line, task_marker};
+use crate::MontContext;
+
+/// Show the tasks that become ready once `id` completes, and every open task
+/// waiting on it, directly or further down the graph.
+pub fn impact(ctx: &MontContext, id: &str) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let config = ctx.config();
+
+    let id = &canonical_id(&graph, id.to_string());
+    let Some(task) = graph.get(id) else {
+        return Err(AppError::TaskNotFound {
+            task_id: id.to_string(),
+            tasks_dir: ctx.tasks_dir().display().to_string(),
+        });
+    };
+    if task.is_closed() {
+        println!("{} is already closed, so it isn't holding anything up", id.bold());
+        return Ok(());
+    }
+
+    let impact = find_impact(&graph, id);
+    if impact.blocked.is_empty() {
+        println!("Nothing is waiting on {}", id.bold());
+        return Ok(());
+    }
+
+    let print_tasks = |ids: &[String]| {
+        for task in ids.iter().filter_map(|id| graph.get(id)) {
+            println!("  {} {}", task_marker(task, &graph), format_task_line(task, &graph, &config.default_gates));
+        }
+    };
+
+    if impact.unblocked.is_empty() {
+        println!("Completing {} unblocks nothing yet; its dependents wait on other work too", id.bold());
+    } else {
+        println!("Completing {} makes {} task(s) ready:", id.bold(), impact.unblocked.len());
+        print_tasks(&impact.unblocked);
+    }
+
+    println!();
+    println!(
+        "{} open task(s) wait on it, {} level(s) deep:",
+        impact.blocked.len().bright_yellow(),
+        impact.depth
+    );
+    print_tasks(&impact.blocked);
+
+    Ok(())
+}
@@ -7,6 +7,7 @@ use std::process::Command;
 use owo_colors::OwoColorize;
 
 use crate::error_fmt::{AppError, IoResultExt};
+use crate::FORMAT_VERSION;
 
 /// Tracking preference for .tasks directory.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -294,7 +295,10 @@ fn apply_preference(
     // Create default config.yml if it doesn't exist
     let config_path = Path::new(".tasks/config.yml");
     if !config_path.exists() {
-        let default_config = "# Mont configuration\n# See https://github.com/Sawyer-Powell/mont for options\n\njj:\n  enabled: true\n\ndefault_gates: []\n";
+        let default_config = format!(
+            "# Mont configuration\n# See https://github.com/Sawyer-Powell/mont for options\n\nversion: {}\n\njj:\n  enabled: true\n\ndefault_gates: []\n",
+            FORMAT_VERSION
+        );
         std::fs::write(config_path, default_config)
             .with_context("failed to create config.yml")?;
         println!("  {} Created .tasks/config.yml", "✓".green());
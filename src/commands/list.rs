@@ -1,18 +1,66 @@
 //! List command - displays all tasks in the task graph.
 
-use crate::render;
+use std::collections::HashSet;
+
+use crate::context::Query;
+use crate::error_fmt::AppError;
+use crate::render::{self, RenderOptions};
 use crate::MontContext;
 
-/// List all tasks in the task graph.
-pub fn list(ctx: &MontContext, show_completed: bool) {
-    let graph = ctx.graph();
+/// Arguments for the list command.
+#[derive(Default)]
+pub struct ListArgs {
+    /// Show completed tasks (hidden by default)
+    pub show_completed: bool,
+    /// Show cancelled tasks (hidden by default)
+    pub show_cancelled: bool,
+    /// Also list tasks from `.tasks/archive` (implies `show_completed`)
+    pub include_archived: bool,
+    /// Only list tasks assigned to this user
+    pub assignee: Option<String>,
+    /// Only list tasks within this namespace
+    pub namespace: Option<String>,
+    /// Highlight the tasks on the longest chain of open work
+    pub critical_path: bool,
+    /// Only list tasks matching this query. Closed tasks it matches are
+    /// listed too, so `status:done` works without `show_completed`.
+    pub query: Option<Query>,
+}
+
+/// List all tasks in the task graph, filtered and highlighted as `args` asks.
+pub fn list(ctx: &MontContext, args: ListArgs) -> Result<(), AppError> {
+    let mut graph = ctx.graph().clone();
     let config = ctx.config();
 
+    if args.include_archived {
+        for task in ctx.archived_tasks()? {
+            graph.insert(task);
+        }
+    }
+
     if graph.is_empty() {
         println!("No tasks found");
-        return;
+        return Ok(());
     }
 
-    let output = render::render_task_graph(&graph, &config.default_gates, show_completed);
+    let queried = args.query.is_some();
+    let options = RenderOptions {
+        show_completed: args.show_completed || args.include_archived || queried,
+        show_cancelled: args.show_cancelled || queried,
+        assignee: args.assignee,
+        namespace: args.namespace,
+        critical: if args.critical_path {
+            crate::context::graph::critical_path(&graph, None).tasks.into_iter().collect()
+        } else {
+            HashSet::new()
+        },
+        query: args.query,
+    };
+    let output = render::render_task_graph(&graph, &config.default_gates, &options);
+    if output.is_empty() && queried {
+        println!("No matching tasks");
+        return Ok(());
+    }
     print!("{}", output);
+    Ok(())
 }
@@ -2,9 +2,13 @@
 
 use minijinja::{context, Environment};
 
+use crate::context::graph::{available_tasks, impact};
 use crate::error_fmt::AppError;
 use crate::{jj, GateStatus, MontContext, Task};
 
+/// How many high-leverage tasks the no-task prompt suggests.
+const MAX_HIGH_LEVERAGE: usize = 3;
+
 // Embed templates at compile time (numbered by state machine order)
 const TEMPLATE_NO_TASK: &str = include_str!("../prompts/00_no-task-in-progress.md");
 const TEMPLATE_NO_CODE_CHANGES: &str = include_str!("../prompts/01_no-code-changes.md");
@@ -12,6 +16,8 @@ const TEMPLATE_HAS_CODE_CHANGES: &str = include_str!("../prompts/02_has-code-cha
 const TEMPLATE_SOME_GATES_UNLOCKED: &str = include_str!("../prompts/03_some-gates-unlocked.md");
 const TEMPLATE_ALL_GATES_UNLOCKED: &str = include_str!("../prompts/04_all-gates-unlocked.md");
 const TEMPLATE_JOT_IN_PROGRESS: &str = include_str!("../prompts/05_jot-in-progress.md");
+// Partial included by the in-progress templates
+const TEMPLATE_BLOCKER_GUIDANCE: &str = include_str!("../prompts/blocker-guidance.md");
 
 /// State of the task graph from the LLM's perspective.
 #[derive(Debug)]
@@ -19,6 +25,9 @@ pub enum TaskGraphState {
     /// No task is currently in progress.
     NoTaskInProgress {
         has_uncommitted_changes: bool,
+        blocked: Vec<BlockedInfo>,
+        /// Ready tasks that unblock the most work, best first.
+        high_leverage: Vec<LeverageInfo>,
     },
     /// A task is in progress with the given sub-state.
     TaskInProgress {
@@ -50,6 +59,26 @@ pub enum InProgressState {
     AllGatesUnlocked,
 }
 
+/// Information about a blocked task for templating.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BlockedInfo {
+    pub id: String,
+    pub title: Option<String>,
+    pub reason: String,
+    pub reference: Option<String>,
+}
+
+/// A ready task and the work waiting on it, for templating.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LeverageInfo {
+    pub id: String,
+    pub title: Option<String>,
+    /// Tasks that become ready when it completes
+    pub unblocks: usize,
+    /// Open tasks waiting on it, directly or transitively
+    pub blocks: usize,
+}
+
 /// Information about a gate for templating.
 #[derive(Debug, Clone)]
 pub struct GateInfo {
@@ -72,8 +101,25 @@ pub fn detect_state(ctx: &MontContext) -> Result<TaskGraphState, AppError> {
         } else {
             false // Assume no uncommitted changes when jj is disabled
         };
+        let mut blocked: Vec<BlockedInfo> = graph
+            .values()
+            .filter(|t| t.is_blocked())
+            .map(|t| BlockedInfo {
+                id: t.id.clone(),
+                title: t.title.clone(),
+                reason: t
+                    .blocker
+                    .as_ref()
+                    .map(|b| b.reason.clone())
+                    .unwrap_or_else(|| "no reason recorded".to_string()),
+                reference: t.blocker.as_ref().and_then(|b| b.reference.clone()),
+            })
+            .collect();
+        blocked.sort_by(|a, b| a.id.cmp(&b.id));
         return Ok(TaskGraphState::NoTaskInProgress {
             has_uncommitted_changes: has_changes,
+            blocked,
+            high_leverage: high_leverage_tasks(&graph),
         });
     }
 
@@ -91,6 +137,33 @@ pub fn detect_state(ctx: &MontContext) -> Result<TaskGraphState, AppError> {
     Ok(TaskGraphState::TaskInProgress { task: Box::new(task), state })
 }
 
+/// Ready tasks ranked by how much work finishing them unblocks.
+///
+/// Tasks nothing waits on are left out.
+fn high_leverage_tasks(graph: &crate::TaskGraph) -> Vec<LeverageInfo> {
+    let mut ranked: Vec<(LeverageInfo, usize)> = available_tasks(graph)
+        .into_iter()
+        .filter(|t| !t.is_jot())
+        .map(|t| (t, impact(graph, &t.id)))
+        .filter(|(_, impact)| !impact.blocked.is_empty())
+        .map(|(t, impact)| {
+            let info = LeverageInfo {
+                id: t.id.clone(),
+                title: t.title.clone(),
+                unblocks: impact.unblocked.len(),
+                blocks: impact.blocked.len(),
+            };
+            (info, impact.depth)
+        })
+        .collect();
+    ranked.sort_by(|(a, a_depth), (b, b_depth)| {
+        (b.unblocks, b.blocks, b_depth)
+            .cmp(&(a.unblocks, a.blocks, a_depth))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    ranked.into_iter().take(MAX_HIGH_LEVERAGE).map(|(info, _)| info).collect()
+}
+
 /// Detect the state of an in-progress task.
 fn detect_in_progress_state(ctx: &MontContext, task: &Task) -> Result<InProgressState, AppError> {
     let graph = ctx.graph();
@@ -167,12 +240,14 @@ pub fn generate_prompt(_ctx: &MontContext, state: &TaskGraphState) -> Result<Str
         .map_err(|e| AppError::TemplateError(e.to_string()))?;
     env.add_template("jot-in-progress", TEMPLATE_JOT_IN_PROGRESS)
         .map_err(|e| AppError::TemplateError(e.to_string()))?;
+    env.add_template("blocker-guidance", TEMPLATE_BLOCKER_GUIDANCE)
+        .map_err(|e| AppError::TemplateError(e.to_string()))?;
 
     match state {
-        TaskGraphState::NoTaskInProgress { has_uncommitted_changes } => {
+        TaskGraphState::NoTaskInProgress { has_uncommitted_changes, blocked, high_leverage } => {
             let tmpl = env.get_template("no-task")
                 .map_err(|e| AppError::TemplateError(e.to_string()))?;
-            tmpl.render(context! { has_uncommitted_changes })
+            tmpl.render(context! { has_uncommitted_changes, blocked_tasks => blocked, high_leverage })
                 .map_err(|e| AppError::TemplateError(e.to_string()))
         }
         TaskGraphState::TaskInProgress { task, state } => {
@@ -280,7 +355,8 @@ CRITICAL WORKFLOW RULES:
 1. ALWAYS run `mont prompt` before starting any work - it tells you exactly what to do
 2. If `mont prompt` shows a JOT (not a task), you MUST distill it into tasks first using `mont distill <jot-id> --stdin`. NEVER implement jots directly - follow the distillation instructions in the prompt.
 3. Only implement TASKS, never jots. Tasks have gates (validation checkpoints) that ensure quality.
-4. After completing work on a task, run `mont prompt` again - it guides you through gates and completion."#;
+4. After completing work on a task, run `mont prompt` again - it guides you through gates and completion.
+5. If you cannot proceed because of something outside the task, do not keep retrying. Run `mont block <task-id> --reason "..."` and report the blocker to the user."#;
 
 /// Pre-validate before showing the picker for `mont claude`.
 ///
@@ -384,7 +460,7 @@ pub fn claude(ctx: &MontContext, task_id: &str) -> Result<(), AppError> {
 
     // Start the task if it's not already in progress
     if should_start {
-        crate::commands::start(ctx, task_id)?;
+        crate::commands::start(ctx, task_id, false)?;
     }
 
     // Generate prompt based on current state
@@ -412,3 +488,72 @@ fn spawn_claude(prompt: &str) -> Result<(), AppError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_context() -> (tempfile::TempDir, MontContext) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ctx = MontContext::load(temp_dir.path().to_path_buf()).unwrap();
+        (temp_dir, ctx)
+    }
+
+    #[test]
+    fn test_no_task_prompt_lists_blocked_tasks() {
+        let (_dir, ctx) = temp_context();
+        let state = TaskGraphState::NoTaskInProgress {
+            has_uncommitted_changes: false,
+            blocked: vec![BlockedInfo {
+                id: "deploy".to_string(),
+                title: None,
+                reason: "waiting on credentials".to_string(),
+                reference: Some("OPS-12".to_string()),
+            }],
+            high_leverage: vec![],
+        };
+        let prompt = generate_prompt(&ctx, &state).unwrap();
+        assert!(prompt.contains("`deploy`: waiting on credentials [OPS-12]"));
+        assert!(prompt.contains("mont unblock"));
+    }
+
+    #[test]
+    fn test_no_task_prompt_suggests_high_leverage_tasks() {
+        let (_dir, ctx) = temp_context();
+        let graph: crate::TaskGraph = [
+            "---\nid: small\n---\n",
+            "---\nid: auth\ntitle: Auth backend\n---\n",
+            "---\nid: login\nafter: [auth]\n---\n",
+            "---\nid: checkout\nafter: [login]\n---\n",
+            "---\nid: docs\nafter: [small]\n---\n",
+        ]
+        .into_iter()
+        .map(|content| crate::parse(content).unwrap())
+        .collect();
+
+        let high_leverage = high_leverage_tasks(&graph);
+        let ids: Vec<&str> = high_leverage.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["auth", "small"]);
+
+        let state = TaskGraphState::NoTaskInProgress {
+            has_uncommitted_changes: false,
+            blocked: vec![],
+            high_leverage,
+        };
+        let prompt = generate_prompt(&ctx, &state).unwrap();
+        assert!(prompt.contains("`auth` (Auth backend): makes 1 task(s) ready, 2 task(s) wait on it"));
+        assert!(prompt.contains("mont impact"));
+    }
+
+    #[test]
+    fn test_in_progress_prompt_explains_how_to_block() {
+        let (_dir, ctx) = temp_context();
+        let task = crate::parse("---\nid: feature\n---\n").unwrap();
+        let state = TaskGraphState::TaskInProgress {
+            task: Box::new(task),
+            state: InProgressState::NoCodeChanges,
+        };
+        let prompt = generate_prompt(&ctx, &state).unwrap();
+        assert!(prompt.contains("mont block feature --reason"));
+    }
+}
@@ -0,0 +1,48 @@
+//! Migrate command - upgrades a `.tasks` directory to the current format.
+
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::context::{self, GlobalConfig, FORMAT_VERSION};
+use crate::error_fmt::AppError;
+use crate::jj;
+
+/// Upgrade `.tasks` step by step to `FORMAT_VERSION`.
+///
+/// Runs before the context is loaded, since `MontContext::load` refuses
+/// directories in an older format.
+pub fn migrate(tasks_dir: &Path, dry_run: bool) -> Result<(), AppError> {
+    let report = context::migrate(tasks_dir, dry_run)?;
+
+    if report.from == report.to {
+        println!("Already at format version {}", FORMAT_VERSION);
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would migrate" } else { "Migrating" };
+    println!("{} .tasks from format {} to {}", verb, report.from, report.to);
+    for step in &report.steps {
+        println!("  {} {}", "✓".green(), step);
+    }
+    for path in &report.changed {
+        println!("  updated: {}", path.display().to_string().cyan());
+    }
+
+    if dry_run {
+        println!("{}", "dry run: no files were changed".dimmed());
+        return Ok(());
+    }
+
+    let config = GlobalConfig::load(&tasks_dir.join("config.yml")).unwrap_or_default();
+    if config.jj.enabled {
+        let message = format!("Migrate .tasks to format {}", report.to);
+        match jj::commit(&message, &[tasks_dir]) {
+            Ok(result) if result.committed => println!("{}", "committed".bright_green()),
+            Ok(_) => {}
+            Err(e) => eprintln!("{}: failed to auto-commit: {}", "warning".yellow(), e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+//! Milestone command - release planning view over a milestone's tasks.
+
+use std::collections::HashSet;
+
+use chrono::Local;
+use owo_colors::OwoColorize;
+
+use crate::context::graph::{available_tasks, effective_due_dates};
+use crate::error_fmt::AppError;
+use crate::render::{format_due, task_marker_for_state, TaskDisplayView, MAX_TITLE_LEN};
+use crate::{Milestone, MontContext, Task, TaskGraph};
+
+/// A dependency of a milestone task that is planned elsewhere.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutsideDependency {
+    /// Milestone task that is waiting
+    pub task_id: String,
+    /// Open task it waits on, which isn't in the milestone
+    pub dependency_id: String,
+    /// Milestone the dependency belongs to, if any
+    pub dependency_milestone: Option<String>,
+}
+
+/// Everything `mont milestone <name>` reports about one milestone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MilestoneReport<'a> {
+    /// Tasks in the milestone's dependency closure, in topological order
+    pub tasks: Vec<&'a Task>,
+    /// IDs of tasks explicitly tagged with the milestone
+    pub members: HashSet<&'a str>,
+    pub complete: usize,
+    pub ready: usize,
+    pub blocked: usize,
+    pub outside: Vec<OutsideDependency>,
+}
+
+impl<'a> MilestoneReport<'a> {
+    /// Build the report for milestone `name`.
+    ///
+    /// The closure is every tagged task and everything it depends on (via
+    /// `TaskGraph::upstream`), excluding gates. Work that merely depends on a
+    /// tagged task, e.g. something planned for a later milestone, isn't part
+    /// of it. Open dependencies of tagged tasks that aren't tagged themselves
+    /// are reported as outside.
+    pub fn build(graph: &'a TaskGraph, name: &str) -> Self {
+        let members: HashSet<&str> = graph
+            .values()
+            .filter(|t| t.milestone.as_deref() == Some(name))
+            .map(|t| t.id.as_str())
+            .collect();
+
+        let seeds: Vec<&str> = members.iter().copied().collect();
+        let closure: HashSet<String> = graph.upstream(&seeds).into_iter().collect();
+        let tasks: Vec<&Task> = graph
+            .topological_order()
+            .into_iter()
+            .filter(|id| closure.contains(*id))
+            .filter_map(|id| graph.get(id))
+            .filter(|t| !t.is_gate())
+            .collect();
+
+        let ready_ids: HashSet<&str> = available_tasks(graph).iter().map(|t| t.id.as_str()).collect();
+        let complete = tasks.iter().filter(|t| t.is_complete()).count();
+        let ready = tasks.iter().filter(|t| ready_ids.contains(t.id.as_str())).count();
+        let blocked = tasks.iter().filter(|t| t.is_blocked()).count();
+
+        let mut outside = Vec::new();
+        let mut member_tasks: Vec<&Task> = members.iter().filter_map(|id| graph.get(id)).collect();
+        member_tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        for task in member_tasks {
+            // A task waits on its `after` dependencies and on its subtasks
+            let subtasks = graph.values().filter(|t| t.before.contains(&task.id));
+            let mut deps: Vec<&Task> = task
+                .after
+                .iter()
+                .filter_map(|id| graph.get(id))
+                .chain(subtasks)
+                .filter(|d| !d.is_gate() && !d.is_closed() && !members.contains(d.id.as_str()))
+                .collect();
+            deps.sort_by(|a, b| a.id.cmp(&b.id));
+            outside.extend(deps.into_iter().map(|d| OutsideDependency {
+                task_id: task.id.clone(),
+                dependency_id: d.id.clone(),
+                dependency_milestone: d.milestone.clone(),
+            }));
+        }
+
+        Self { tasks, members, complete, ready, blocked, outside }
+    }
+}
+
+/// Show a milestone's tasks and progress, or a summary of all milestones.
+pub fn milestone(ctx: &MontContext, name: Option<&str>) -> Result<(), AppError> {
+    let config = ctx.config();
+
+    let Some(name) = name else {
+        print_milestone_summary(ctx, &config.milestones);
+        return Ok(());
+    };
+
+    let milestone = config
+        .milestone(name)
+        .ok_or_else(|| AppError::UnknownMilestone(name.to_string()))?;
+
+    let graph = ctx.graph();
+    let report = MilestoneReport::build(&graph, name);
+    let today = Local::now().date_naive();
+
+    println!(
+        "{} {} {}",
+        "Milestone".bold(),
+        milestone.name.blue().bold(),
+        format_due(milestone.date, today)
+    );
+
+    if report.tasks.is_empty() {
+        println!();
+        println!("No tasks in this milestone");
+        return Ok(());
+    }
+
+    println!(
+        "  {} complete, {} ready, {} blocked, {} total",
+        report.complete.to_string().bright_green(),
+        report.ready.to_string().cyan(),
+        report.blocked.to_string().red(),
+        report.tasks.len()
+    );
+    println!();
+
+    let due_dates = effective_due_dates(&graph);
+    let max_id_len = report.tasks.iter().map(|t| t.id.len()).max().unwrap_or(0);
+    for task in &report.tasks {
+        let view = TaskDisplayView::from_task(task, &graph, &config.default_gates)
+            .with_due(due_dates.get(&task.id).copied(), today);
+        let line = view.format_line_padded(max_id_len, MAX_TITLE_LEN);
+        if report.members.contains(task.id.as_str()) {
+            println!("{} {}", task_marker_for_state(view.state), line);
+        } else {
+            let note = match &task.milestone {
+                Some(other) => format!("(in {})", other),
+                None => "(not in milestone)".to_string(),
+            };
+            println!("{} {} {}", task_marker_for_state(view.state), line, note.dimmed());
+        }
+    }
+
+    if !report.outside.is_empty() {
+        println!();
+        for dep in &report.outside {
+            let location = match &dep.dependency_milestone {
+                Some(other) => format!("planned for '{}'", other),
+                None => "not in any milestone".to_string(),
+            };
+            println!(
+                "{}: '{}' depends on '{}', which is {}",
+                "warning".yellow().bold(),
+                dep.task_id.cyan(),
+                dep.dependency_id.cyan(),
+                location
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Print one line per configured milestone with its date and completion.
+fn print_milestone_summary(ctx: &MontContext, milestones: &[Milestone]) {
+    if milestones.is_empty() {
+        println!("No milestones defined in config.yml");
+        return;
+    }
+
+    let graph = ctx.graph();
+    let today = Local::now().date_naive();
+    let max_name_len = milestones.iter().map(|m| m.name.len()).max().unwrap_or(0);
+
+    for milestone in milestones {
+        let report = MilestoneReport::build(&graph, &milestone.name);
+        println!(
+            "{}  {:>3}/{:<3} complete  {}",
+            format!("{:max_name_len$}", milestone.name).blue().bold(),
+            report.complete,
+            report.tasks.len(),
+            format_due(milestone.date, today)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Status;
+
+    fn make_task(id: &str, milestone: Option<&str>, after: &[&str]) -> Task {
+        Task {
+            id: id.to_string(),
+            after: after.iter().map(|s| s.to_string()).collect(),
+            milestone: milestone.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_report_counts_and_outside_dependencies() {
+        let mut shipped = make_task("shipped", Some("v1"), &[]);
+        shipped.status = Some(Status::Complete);
+        let graph: TaskGraph = vec![
+            shipped,
+            make_task("api", Some("v1"), &["shipped", "schema"]),
+            make_task("schema", Some("v2"), &[]),
+            make_task("unrelated", None, &[]),
+        ]
+        .into_iter()
+        .collect();
+
+        let report = MilestoneReport::build(&graph, "v1");
+        let ids: Vec<&str> = report.tasks.iter().map(|t| t.id.as_str()).collect();
+
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&"unrelated"));
+        assert_eq!((report.complete, report.ready, report.blocked), (1, 1, 0));
+        assert_eq!(
+            report.outside,
+            vec![OutsideDependency {
+                task_id: "api".to_string(),
+                dependency_id: "schema".to_string(),
+                dependency_milestone: Some("v2".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_report_excludes_downstream_work() {
+        let graph: TaskGraph = vec![
+            make_task("core", Some("v1"), &[]),
+            make_task("follow-up", Some("v2"), &["core"]),
+        ]
+        .into_iter()
+        .chain(std::iter::once(Task {
+            id: "setup".to_string(),
+            before: vec!["core".to_string()],
+            ..Default::default()
+        }))
+        .collect();
+
+        let report = MilestoneReport::build(&graph, "v1");
+        let mut ids: Vec<&str> = report.tasks.iter().map(|t| t.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["core", "setup"]);
+
+        let report = MilestoneReport::build(&graph, "v2");
+        let mut ids: Vec<&str> = report.tasks.iter().map(|t| t.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["core", "follow-up", "setup"]);
+    }
+}
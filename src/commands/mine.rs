@@ -0,0 +1,54 @@
+//! Mine command - shows open tasks assigned to the current user.
+
+use chrono::Local;
+
+use crate::context::graph::effective_due_dates;
+use crate::error_fmt::AppError;
+use crate::render::{task_marker_for_state, DisplayState, TaskDisplayView};
+use crate::MontContext;
+
+/// Max title length for mine output.
+const MINE_MAX_TITLE_LEN: usize = 120;
+
+/// Show open tasks assigned to the current user.
+///
+/// In-progress tasks come first, then ready tasks, then tasks still
+/// waiting on dependencies.
+pub fn mine(ctx: &MontContext) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let config = ctx.config();
+    let user = config.current_user().ok_or(AppError::UnknownUser)?;
+
+    let due_dates = effective_due_dates(&graph);
+    let today = Local::now().date_naive();
+
+    let mut views: Vec<TaskDisplayView> = graph
+        .values()
+        .filter(|t| t.is_assigned_to(&user) && !t.is_closed() && !t.is_gate())
+        .map(|t| {
+            TaskDisplayView::from_task(t, &graph, &config.default_gates)
+                .with_due(due_dates.get(&t.id).copied(), today)
+        })
+        .collect();
+
+    if views.is_empty() {
+        println!("No tasks assigned to {}", user);
+        return Ok(());
+    }
+
+    let rank = |state: DisplayState| match state {
+        DisplayState::InProgress => 0,
+        DisplayState::Available => 1,
+        DisplayState::Jot => 2,
+        _ => 3,
+    };
+    views.sort_by(|a, b| rank(a.state).cmp(&rank(b.state)).then_with(|| a.id.cmp(&b.id)));
+
+    let max_id_len = views.iter().map(|v| v.id.len()).max().unwrap_or(0);
+    for view in &views {
+        let marker = task_marker_for_state(view.state);
+        println!("{} {}", marker, view.format_line_padded(max_id_len, MINE_MAX_TITLE_LEN));
+    }
+
+    Ok(())
+}
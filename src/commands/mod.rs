@@ -3,31 +3,57 @@
 //! Each command is implemented in its own submodule and uses MontContext
 //! for all task graph operations.
 
+mod archive;
+mod block;
+mod cancel;
 mod check;
+mod critical_path;
 mod delete;
+mod diff;
 mod done;
+mod impact;
 mod init;
 mod list;
+mod migrate;
+mod milestone;
 pub mod llm;
+mod mine;
+mod plan;
 mod ready;
+mod reopen;
 pub mod shared;
 mod show;
 mod start;
+mod stats;
 mod status;
 mod stop;
 pub mod task_cmd;
 pub mod unlock;
+mod why;
 
+pub use archive::archive;
+pub use block::{block, unblock};
+pub use cancel::cancel;
 pub use check::check;
+pub use critical_path::critical_path;
 pub use delete::delete;
+pub use diff::diff;
 pub use done::done;
+pub use impact::impact;
 pub use init::init;
-pub use list::list;
+pub use list::{list, ListArgs};
+pub use migrate::migrate;
+pub use milestone::milestone;
 pub use llm::{claude, claude_ignore, claude_pre_validate, prompt};
+pub use mine::mine;
+pub use plan::plan;
 pub use ready::ready;
+pub use reopen::reopen;
 pub use show::show;
 pub use start::start;
+pub use stats::stats;
 pub use status::status;
 pub use stop::stop;
 pub use task_cmd::{distill, jot, task};
 pub use unlock::unlock;
+pub use why::why;
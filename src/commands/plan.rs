@@ -0,0 +1,71 @@
+//! Plan command - simulates running the open work across parallel workers.
+
+use owo_colors::OwoColorize;
+
+use crate::context::{plan as simulate, Effort};
+use crate::error_fmt::AppError;
+use crate::render::{format_task_line, task_marker};
+use crate::MontContext;
+
+/// Show the waves of tasks `workers` workers could run at once, and when
+/// they'd finish. Without `workers`, there's no limit, which shows how much
+/// concurrency the graph allows at all.
+pub fn plan(ctx: &MontContext, workers: Option<usize>, default_estimate: Effort) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let config = ctx.config();
+
+    let plan = simulate(&graph, workers.unwrap_or(usize::MAX), default_estimate);
+    if plan.waves.is_empty() {
+        println!("Nothing is ready to schedule");
+    }
+
+    for (i, wave) in plan.waves.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", format!("Wave {} at {}:", i + 1, wave.start).bold());
+        for task in wave.tasks.iter().filter_map(|id| graph.get(id)) {
+            let line = format_task_line(task, &graph, &config.default_gates);
+            match task.estimate {
+                Some(estimate) => println!("  {} {} {}", task_marker(task, &graph), line, estimate.to_string().dimmed()),
+                None => println!("  {} {}", task_marker(task, &graph), line),
+            }
+        }
+    }
+
+    if !plan.waves.is_empty() {
+        let workers = match workers {
+            Some(workers) => format!("{} worker(s)", workers),
+            None => "unlimited workers".to_string(),
+        };
+        let capacity = plan.finish.minutes() * plan.peak as u64;
+        println!();
+        println!(
+            "With {}: {} of work done after {}, at most {} task(s) at once{}",
+            workers,
+            plan.work,
+            plan.finish.to_string().bright_green(),
+            plan.peak,
+            (plan.work.minutes() * 100)
+                .checked_div(capacity)
+                .map_or(String::new(), |busy| format!(" ({}% busy)", busy))
+        );
+        if plan.unestimated > 0 {
+            println!(
+                "{} task(s) without an estimate counted as {} each",
+                plan.unestimated, default_estimate
+            );
+        }
+    }
+
+    if !plan.unschedulable.is_empty() {
+        println!();
+        println!(
+            "{} {}",
+            "Never becomes ready (blocked, or waiting on blocked work):".yellow(),
+            plan.unschedulable.join(", ")
+        );
+    }
+
+    Ok(())
+}
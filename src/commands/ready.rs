@@ -1,6 +1,9 @@
 //! Ready command - shows tasks ready to work on.
 
-use crate::context::graph::available_tasks;
+use chrono::{Local, NaiveDate};
+
+use crate::context::graph::{available_tasks, effective_due_dates};
+use crate::context::Query;
 use crate::render::{task_marker_for_state, DisplayState, TaskDisplayView};
 use crate::MontContext;
 
@@ -8,7 +11,12 @@ use crate::MontContext;
 const READY_MAX_TITLE_LEN: usize = 120;
 
 /// Show tasks that are ready to work on (all dependencies complete).
-pub fn ready(ctx: &MontContext) {
+///
+/// Overdue and due-soon tasks are highlighted using their effective due date.
+/// With `due_before`, only tasks whose effective due date is on or before
+/// that date are shown. With `assignee`, only tasks assigned to that user,
+/// and with `query`, only tasks matching it.
+pub fn ready(ctx: &MontContext, due_before: Option<NaiveDate>, assignee: Option<&str>, query: Option<&Query>) {
     let graph = ctx.graph();
     let config = ctx.config();
 
@@ -17,7 +25,18 @@ pub fn ready(ctx: &MontContext) {
         return;
     }
 
-    let ready: Vec<_> = available_tasks(&graph);
+    let due_dates = effective_due_dates(&graph);
+    let today = Local::now().date_naive();
+
+    let ready: Vec<_> = available_tasks(&graph)
+        .into_iter()
+        .filter(|t| match due_before {
+            Some(limit) => due_dates.get(&t.id).is_some_and(|due| *due <= limit),
+            None => true,
+        })
+        .filter(|t| assignee.is_none_or(|a| t.is_assigned_to(a)))
+        .filter(|t| query.is_none_or(|q| q.matches(t, &*graph)))
+        .collect();
 
     if ready.is_empty() {
         println!("No ready tasks");
@@ -27,7 +46,10 @@ pub fn ready(ctx: &MontContext) {
     // Build display views for all ready tasks
     let views: Vec<_> = ready
         .into_iter()
-        .map(|t| TaskDisplayView::from_task(t, &graph, &config.default_gates))
+        .map(|t| {
+            TaskDisplayView::from_task(t, &graph, &config.default_gates)
+                .with_due(due_dates.get(&t.id).copied(), today)
+        })
         .collect();
 
     // Split into in-progress, regular tasks, and jots
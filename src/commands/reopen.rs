@@ -0,0 +1,137 @@
+//! Reopen command - move a completed or cancelled task back to pending.
+
+use chrono::Utc;
+use owo_colors::OwoColorize;
+
+use std::collections::HashSet;
+
+use crate::context::graph::is_available;
+use crate::error_fmt::AppError;
+use crate::{MontContext, Task, TaskGraph};
+
+/// Reopen a closed task, recording the reason on the task.
+///
+/// Gates are reset to pending so the task goes through verification again.
+/// Completed epics containing the task are reopened as well.
+/// Dependents that were relying on the task being done are reported, since
+/// they are no longer available (or are now in progress on an open dependency).
+pub fn reopen(ctx: &MontContext, id: &str, reason: &str) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let task = graph.get(id).ok_or_else(|| AppError::TaskNotFound {
+        task_id: id.to_string(),
+        tasks_dir: ctx.tasks_dir().display().to_string(),
+    })?;
+
+    if !task.is_closed() {
+        return Err(AppError::TaskNotClosed(id.to_string()));
+    }
+    if reason.trim().is_empty() {
+        return Err(AppError::InvalidArgs("--reason cannot be empty".to_string()));
+    }
+
+    // Dependents that could proceed before the reopen: tasks after it, and
+    // the tasks it's a subtask of
+    let dependent_ids: Vec<String> = graph
+        .dependents(&task.id)
+        .chain(task.before.iter().filter_map(|parent| graph.get(parent)))
+        .filter(|t| !t.is_deleted() && !t.is_closed() && !t.is_gate())
+        .filter(|t| t.is_in_progress() || is_available(t, &*graph))
+        .map(|t| t.id.clone())
+        .collect();
+
+    // Completed epics that contain this task are no longer finished
+    let epics = enclosing_complete_epics(task, &graph);
+
+    let now = Utc::now();
+    let mut updated_task = task.clone();
+    updated_task.reopen(reason.trim(), now);
+    drop(graph);
+
+    // The task and its epics are reopened together, or not at all
+    let mut txn = ctx.begin();
+    txn.update(id, updated_task);
+    for mut epic in epics.iter().cloned() {
+        epic.reopen(&format!("'{}' reopened: {}", id, reason.trim()), now);
+        txn.update(epic.id.clone(), epic);
+    }
+    ctx.commit(txn)?;
+
+    println!("Reopened task '{}': {}", id.yellow(), reason.trim());
+    for epic in &epics {
+        println!("Reopened epic '{}'", epic.id.yellow());
+    }
+
+    let graph = ctx.graph();
+    let mut affected: Vec<&str> = dependent_ids
+        .iter()
+        .filter_map(|dep_id| graph.get(dep_id))
+        .filter(|t| t.is_in_progress() || !is_available(t, &*graph))
+        .map(|t| t.id.as_str())
+        .collect();
+    affected.sort();
+
+    if !affected.is_empty() {
+        println!();
+        println!("{}", "Dependents now waiting on this task:".bold());
+        for dep_id in affected {
+            let note = if graph.get(dep_id).is_some_and(|t| t.is_in_progress()) {
+                " (in progress)".yellow().to_string()
+            } else {
+                String::new()
+            };
+            println!("  {}{}", dep_id.cyan(), note);
+        }
+    }
+
+    Ok(())
+}
+
+/// Complete epics that `task` counts towards (see `epic_descendants`): the
+/// ones reached by following `before` up from it, in id order.
+fn enclosing_complete_epics(task: &Task, graph: &TaskGraph) -> Vec<Task> {
+    if task.is_gate() || task.is_jot() {
+        return Vec::new();
+    }
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut stack: Vec<&str> = task.before.iter().map(String::as_str).collect();
+    let mut epics = Vec::new();
+    while let Some(id) = stack.pop() {
+        if id == task.id || !seen.insert(id) {
+            continue;
+        }
+        let Some(parent) = graph.get(id).filter(|t| !t.is_deleted()) else {
+            continue;
+        };
+        if parent.is_epic() && parent.is_complete() {
+            epics.push(parent.clone());
+        }
+        stack.extend(parent.before.iter().map(String::as_str));
+    }
+    epics.sort_by(|a, b| a.id.cmp(&b.id));
+    epics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_enclosing_complete_epics() {
+        let graph: TaskGraph = [
+            "---\nid: outer\ntype: epic\nstatus: complete\n---\n",
+            "---\nid: inner\ntype: epic\nstatus: complete\nbefore: [outer]\n---\n",
+            "---\nid: open-epic\ntype: epic\n---\n",
+            "---\nid: step\nstatus: complete\nbefore: [inner, open-epic]\n---\n",
+            "---\nid: task\nstatus: complete\nbefore: [step]\n---\n",
+        ]
+        .into_iter()
+        .map(|content| parse(content).unwrap())
+        .collect();
+
+        let epics = enclosing_complete_epics(graph.get("task").unwrap(), &graph);
+        let ids: Vec<&str> = epics.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["inner", "outer"]);
+    }
+}
@@ -4,9 +4,12 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
-use crate::context::graph::is_available;
+use chrono::Utc;
+
+use crate::context::graph::{finished_epics, is_available};
+use crate::context::{is_query, Query};
 use crate::error_fmt::{AppError, IoResultExt, ParseResultExt};
-use crate::{parse, Task, TaskGraph};
+use crate::{parse, MontContext, Task, TaskGraph};
 
 /// Filter options for interactive task picker.
 #[derive(Clone, Copy)]
@@ -17,10 +20,14 @@ pub enum TaskFilter {
     InProgress,
     /// All tasks including complete
     All,
-    /// Only ready tasks (not complete, not gates, all dependencies complete)
+    /// Only ready tasks (not complete, not gates or epics, not blocked, all dependencies complete)
     Ready,
     /// Only jots (non-complete)
     Jots,
+    /// Only blocked tasks
+    Blocked,
+    /// Only complete or cancelled tasks
+    Closed,
 }
 
 /// Pick a task interactively using fzf.
@@ -30,6 +37,13 @@ pub enum TaskFilter {
 /// - User cancelled the picker
 /// - No matching tasks exist
 pub fn pick_task(graph: &TaskGraph, filter: TaskFilter) -> Result<String, AppError> {
+    pick_task_matching(graph, filter, None)
+}
+
+/// Pick a task interactively, offering only tasks that also match `query`.
+///
+/// With a query, a single match is selected without opening the picker.
+pub fn pick_task_matching(graph: &TaskGraph, filter: TaskFilter, query: Option<&Query>) -> Result<String, AppError> {
     // Check if fzf is installed
     if Command::new("fzf")
         .arg("--version")
@@ -45,12 +59,21 @@ pub fn pick_task(graph: &TaskGraph, filter: TaskFilter) -> Result<String, AppErr
     let mut tasks: Vec<_> = graph
         .values()
         .filter(|t| match filter {
-            TaskFilter::Active => !t.is_complete(),
+            TaskFilter::Active => !t.is_closed(),
             TaskFilter::InProgress => t.is_in_progress(),
             TaskFilter::All => true,
-            TaskFilter::Ready => !t.is_complete() && !t.is_gate() && is_available(t, graph),
-            TaskFilter::Jots => !t.is_complete() && t.is_jot(),
+            TaskFilter::Ready => {
+                !t.is_closed()
+                    && !t.is_gate()
+                    && !t.is_epic()
+                    && !t.is_blocked()
+                    && is_available(t, graph)
+            }
+            TaskFilter::Jots => !t.is_closed() && t.is_jot(),
+            TaskFilter::Blocked => t.is_blocked(),
+            TaskFilter::Closed => t.is_closed(),
         })
+        .filter(|t| query.is_none_or(|q| q.matches(t, graph)))
         .collect();
 
     if tasks.is_empty() {
@@ -70,6 +93,7 @@ pub fn pick_task(graph: &TaskGraph, filter: TaskFilter) -> Result<String, AppErr
                 crate::TaskType::Task => "[task]",
                 crate::TaskType::Jot => "[jot] ",
                 crate::TaskType::Gate => "[gate]",
+                crate::TaskType::Epic => "[epic]",
             };
             let title = t.title.as_deref().unwrap_or("");
             format!("{}  {:max_id_len$}  {}", type_tag, t.id, title)
@@ -88,6 +112,7 @@ pub fn pick_task(graph: &TaskGraph, filter: TaskFilter) -> Result<String, AppErr
             "--layout", "reverse",
             "--border",
         ])
+        .args(query.map(|_| "--select-1"))
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::inherit())
@@ -136,18 +161,61 @@ pub fn pick_task(graph: &TaskGraph, filter: TaskFilter) -> Result<String, AppErr
     Ok(id)
 }
 
-/// Resolve a list of IDs, expanding `?` placeholders via interactive picker.
+/// Mark epics complete once all their children have finished.
+///
+/// Repeats until no more epics finish, so completing the last task of a nested
+/// epic also completes the enclosing one. Returns the completed epic IDs.
+pub fn complete_finished_epics(ctx: &MontContext) -> Result<Vec<String>, AppError> {
+    let mut completed = Vec::new();
+
+    loop {
+        let ids = finished_epics(&ctx.graph());
+        if ids.is_empty() {
+            return Ok(completed);
+        }
+        for id in ids {
+            let Some(mut epic) = ctx.graph().get(&id).cloned() else {
+                continue;
+            };
+            epic.complete(Utc::now());
+            ctx.update(&id, epic)?;
+            completed.push(id);
+        }
+    }
+}
+
+/// Resolve a task argument to a task id.
+///
+/// `?` opens the picker and `?<query>` opens it over the tasks matching the
+/// query. Anything else is treated as an id, uid or alias.
+pub fn resolve_id(graph: &TaskGraph, id: String, filter: TaskFilter) -> Result<String, AppError> {
+    match id.strip_prefix('?') {
+        Some("") => pick_task(graph, filter),
+        Some(query) => pick_task_matching(graph, filter, Some(&Query::parse(query)?)),
+        None => Ok(canonical_id(graph, id)),
+    }
+}
+
+/// Whether any argument needs `resolve_ids` to expand it.
+pub fn needs_resolving(graph: &TaskGraph, ids: &[String]) -> bool {
+    ids.iter().any(|id| id.starts_with('?') || is_query(id, graph))
+}
+
+/// Resolve a list of IDs, expanding picker placeholders and queries.
 ///
 /// Input can be:
-/// - A slice of IDs where some may be `?`
-/// - Each `?` triggers an fzf picker
+/// - A slice of IDs where some may be `?` or `?<query>`
+/// - Each `?` triggers an fzf picker, over the query's matches if given
+/// - Queries (see `context::query`) expand to every matching task
 ///
-/// Returns a deduplicated Vec of resolved IDs in order of first occurrence.
+/// Uids and aliases are translated to task ids. Returns a deduplicated Vec
+/// of resolved IDs in order of first occurrence.
 ///
 /// # Examples
 /// - `["task1", "task2"]` → `["task1", "task2"]`
 /// - `["?"]` → `["<picked-task>"]`
 /// - `["task1", "?", "?"]` → `["task1", "<picked1>", "<picked2>"]`
+/// - `["tag:api"]` → `["<match1>", "<match2>"]`
 pub fn resolve_ids(
     graph: &TaskGraph,
     ids: &[String],
@@ -159,21 +227,36 @@ pub fn resolve_ids(
     let mut seen = HashSet::new();
 
     for id in ids {
-        let actual_id = if id == "?" {
-            pick_task(graph, filter)?
+        let actual_ids = if !id.starts_with('?') && is_query(id, graph) {
+            let matches: Vec<String> = Query::parse(id)?.select(graph).into_iter().map(|t| t.id.clone()).collect();
+            if matches.is_empty() {
+                return Err(AppError::NoMatches(id.clone()));
+            }
+            matches
         } else {
-            id.clone()
+            vec![resolve_id(graph, id.clone(), filter)?]
         };
 
         // Deduplicate while preserving order
-        if seen.insert(actual_id.clone()) {
-            resolved.push(actual_id);
+        for actual_id in actual_ids {
+            if seen.insert(actual_id.clone()) {
+                resolved.push(actual_id);
+            }
         }
     }
 
     Ok(resolved)
 }
 
+/// Translate a task's uid or alias into its current id. Anything else,
+/// including unknown ids, is returned unchanged for the caller to report.
+pub fn canonical_id(graph: &TaskGraph, key: String) -> String {
+    match graph.resolve(&key) {
+        Some(task) => task.id.clone(),
+        None => key,
+    }
+}
+
 /// Create a temp file containing one or more tasks.
 ///
 /// The file is named `{ULID}_{suffix}.md` in the system temp directory.
@@ -226,16 +309,17 @@ pub fn parse_temp_file(path: &Path) -> Result<Vec<Task>, AppError> {
 /// Parse content containing one or more tasks.
 ///
 /// Parsing rule:
-/// - Odd `---` lines start a new task's frontmatter
-/// - Even `---` lines end frontmatter, start body
+/// - Odd `---` (or `+++` for TOML) lines start a new task's frontmatter
+/// - Even `---` (or `+++`) lines end frontmatter, start body
 /// - Content before the first `---` is ignored (allows for comments/instructions)
 pub fn parse_multi_task_content(content: &str, path: &Path) -> Result<Vec<Task>, AppError> {
     let mut tasks = Vec::new();
     let mut current_task = String::new();
     let mut delimiter_count = 0;
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
 
     for line in content.lines() {
-        if line.trim() == "---" {
+        if matches!(line.trim(), "---" | "+++") {
             delimiter_count += 1;
 
             if delimiter_count % 2 == 1 {
@@ -345,6 +429,7 @@ Task description here."#.to_string()
                 crate::TaskType::Task => "task",
                 crate::TaskType::Jot => "jot",
                 crate::TaskType::Gate => "gate",
+                crate::TaskType::Epic => "epic",
             };
             format!(
                 r#"Create {} tasks below. Each task starts with --- and ends with ---
@@ -372,24 +457,17 @@ pub fn remove_temp_file(path: &Path) -> Result<(), AppError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::TaskType;
 
     #[test]
     fn test_make_and_parse_single_task() {
         let task = Task {
             id: "test-task".to_string(),
-            new_id: None,
             title: Some("Test Title".to_string()),
             description: "Test description".to_string(),
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            task_type: TaskType::Task,
-            status: None,
-            deleted: false,
+            ..Default::default()
         };
 
-        let path = make_temp_file("test", &[task.clone()], None).unwrap();
+        let path = make_temp_file("test", std::slice::from_ref(&task), None).unwrap();
         assert!(path.exists());
 
         let parsed = parse_temp_file(&path).unwrap();
@@ -405,27 +483,16 @@ mod tests {
         let tasks = vec![
             Task {
                 id: "task-one".to_string(),
-                new_id: None,
                 title: Some("First Task".to_string()),
                 description: "First description".to_string(),
-                before: vec![],
-                after: vec![],
-                gates: vec![],
-                task_type: TaskType::Task,
-                status: None,
-                deleted: false,
+                ..Default::default()
             },
             Task {
                 id: "task-two".to_string(),
-                new_id: None,
                 title: Some("Second Task".to_string()),
                 description: "Second description".to_string(),
-                before: vec![],
                 after: vec!["task-one".to_string()],
-                gates: vec![],
-                task_type: TaskType::Task,
-                status: None,
-                deleted: false,
+                ..Default::default()
             },
         ];
 
@@ -444,15 +511,8 @@ mod tests {
     fn test_make_with_comment() {
         let task = Task {
             id: "commented-task".to_string(),
-            new_id: None,
             title: Some("Task".to_string()),
-            description: String::new(),
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            task_type: TaskType::Task,
-            status: None,
-            deleted: false,
+            ..Default::default()
         };
 
         let comment = "Instructions for editing\nLine two of instructions";
@@ -490,6 +550,26 @@ Description here
         std::fs::remove_file(&path).unwrap();
     }
 
+    #[test]
+    fn test_resolve_ids_expands_queries() {
+        let graph: TaskGraph = [
+            "---\nid: api\ntags: [backend]\n---\n",
+            "---\nid: db\ntags: [backend]\n---\n",
+            "---\nid: ui\n---\n",
+        ]
+        .into_iter()
+        .map(|content| parse(content).unwrap())
+        .collect();
+
+        let ids = vec!["ui".to_string(), "tag:backend".to_string(), "db".to_string()];
+        assert!(needs_resolving(&graph, &ids));
+        assert_eq!(resolve_ids(&graph, &ids, TaskFilter::Active).unwrap(), vec!["ui", "api", "db"]);
+
+        let ids = vec!["tag:frontend".to_string()];
+        assert!(matches!(resolve_ids(&graph, &ids, TaskFilter::Active), Err(AppError::NoMatches(_))));
+        assert!(!needs_resolving(&graph, &["ui".to_string()]));
+    }
+
     #[test]
     fn test_find_temp_files_ordering() {
         // Create files with known ULIDs (older first)
@@ -2,16 +2,45 @@
 
 use std::collections::HashSet;
 
+use chrono::{Local, Utc};
 use owo_colors::OwoColorize;
 
+use crate::context::graph::{effective_due_dates, epic_progress};
+use crate::context::{is_query, Query};
 use crate::error_fmt::AppError;
-use crate::render::{print_gates_section, TaskDisplayView};
+use crate::render::{format_blocker, format_due, format_epic_progress, print_gates_section, TaskDisplayView};
 use crate::{MontContext, Task, TaskType};
 
 /// Show details for a single task, or multiple tasks if group mode is enabled.
-pub fn show(ctx: &MontContext, id: &str, short: bool, group: bool) -> Result<(), AppError> {
+///
+/// `id` may also be a query, which shows every task it matches.
+///
+/// With `include_archived`, a task that isn't in the graph is looked up in
+/// `.tasks/archive` instead.
+pub fn show(ctx: &MontContext, id: &str, short: bool, group: bool, include_archived: bool) -> Result<(), AppError> {
+    // A query shows every matching task
+    let is_query = is_query(id, &*ctx.graph());
+    let seeds: Vec<String> = if is_query {
+        let query = Query::parse(id)?;
+        let matches: Vec<String> = query.select(&*ctx.graph()).into_iter().map(|t| t.id.clone()).collect();
+        if matches.is_empty() {
+            return Err(AppError::NoMatches(id.to_string()));
+        }
+        matches
+    } else {
+        vec![id.to_string()]
+    };
+
+    if !is_query && include_archived && ctx.graph().get(id).is_none() {
+        let archived = ctx.archived_tasks()?;
+        if let Some(task) = archived.iter().find(|t| t.has_key(id)) {
+            print_task_details(ctx, task, short);
+            return Ok(());
+        }
+    }
+
     // Verify the task exists first
-    if ctx.graph().get(id).is_none() {
+    if !is_query && ctx.graph().get(id).is_none() {
         return Err(AppError::TaskNotFound {
             task_id: id.to_string(),
             tasks_dir: ctx.tasks_dir().display().to_string(),
@@ -21,7 +50,8 @@ pub fn show(ctx: &MontContext, id: &str, short: bool, group: bool) -> Result<(),
     // Get the list of task IDs to show
     let ids: Vec<String> = if group {
         // Expand to full subgraph (same logic as task_cmd.rs)
-        let subgraph_ids: HashSet<String> = ctx.graph().subgraph(&[id]).into_iter().collect();
+        let seeds: Vec<&str> = seeds.iter().map(String::as_str).collect();
+        let subgraph_ids: HashSet<String> = ctx.graph().subgraph(&seeds).into_iter().collect();
 
         // Get topological order and filter to just the subgraph
         ctx.graph()
@@ -31,7 +61,7 @@ pub fn show(ctx: &MontContext, id: &str, short: bool, group: bool) -> Result<(),
             .map(|s| s.to_string())
             .collect()
     } else {
-        vec![id.to_string()]
+        seeds
     };
 
     // Print each task
@@ -58,6 +88,14 @@ fn print_task_details(ctx: &MontContext, task: &Task, short: bool) {
     // Task ID
     println!("{:LABEL_WIDTH$} {}", "Id".bold(), view.id_colored());
 
+    // Permanent identity and previous ids
+    if let Some(uid) = &task.uid {
+        println!("{:LABEL_WIDTH$} {}", "Uid".bold(), uid.dimmed());
+    }
+    if !task.aliases.is_empty() {
+        println!("{:LABEL_WIDTH$} {}", "Aliases".bold(), task.aliases.join(", ").dimmed());
+    }
+
     // Title
     if task.title.is_some() {
         println!(
@@ -69,15 +107,138 @@ fn print_task_details(ctx: &MontContext, task: &Task, short: bool) {
 
     // Status
     println!("{:LABEL_WIDTH$} {}", "Status".bold(), view.status_colored());
+    if graph.is_archived(&task.id) {
+        println!("{:LABEL_WIDTH$} {}", "Archived".bold(), "yes".dimmed());
+    }
+
+    // Blocker
+    if let Some(blocker) = task.blocker.as_ref().filter(|_| task.is_blocked()) {
+        println!("{:LABEL_WIDTH$} {}", "Blocked".bold(), format_blocker(blocker).red());
+    }
+
+    // Creation
+    if let Some(created) = task.created {
+        println!("{:LABEL_WIDTH$} {}", "Created".bold(), created.with_timezone(&Local).format("%Y-%m-%d"));
+    }
+
+    // Completion
+    if let Some(completed) = task.completed.filter(|_| task.is_complete()) {
+        println!("{:LABEL_WIDTH$} {}", "Completed".bold(), completed.with_timezone(&Local).format("%Y-%m-%d"));
+    }
+
+    // Cancellation
+    if let Some(cancelled) = task.cancelled.as_ref().filter(|_| task.is_cancelled()) {
+        let when = cancelled.at.with_timezone(&Local).format("%Y-%m-%d");
+        let value = match &cancelled.reason {
+            Some(reason) => format!("{} {}", reason, format!("({})", when).dimmed()),
+            None => when.to_string(),
+        };
+        println!("{:LABEL_WIDTH$} {}", "Cancelled".bold(), value);
+    }
+
+    // Reopen history, most recent first
+    for (i, event) in task.reopened.iter().rev().enumerate() {
+        let label = if i == 0 { "Reopened" } else { "" };
+        let when = event.at.with_timezone(&Local).format("%Y-%m-%d");
+        println!(
+            "{:LABEL_WIDTH$} {} {}",
+            label.bold(),
+            event.reason,
+            format!("({})", when).dimmed()
+        );
+    }
 
     // Type
     let type_value = match task.task_type {
         TaskType::Task => "[task]".bright_green().to_string(),
         TaskType::Jot => "[jot]".yellow().to_string(),
         TaskType::Gate => "[gate]".purple().to_string(),
+        TaskType::Epic => "[epic]".blue().to_string(),
     };
     println!("{:LABEL_WIDTH$} {}", "Type".bold(), type_value);
 
+    // Epic rollup from its children
+    if task.is_epic() {
+        let progress = epic_progress(&task.id, &graph, &config.default_gates);
+        println!("{:LABEL_WIDTH$} {}", "Progress".bold(), format_epic_progress(&progress));
+        if !progress.remaining_estimate.is_zero() {
+            println!("{:LABEL_WIDTH$} {}", "Remaining".bold(), progress.remaining_estimate);
+        }
+        if progress.gates_total > 0 {
+            println!(
+                "{:LABEL_WIDTH$} {}/{} passed",
+                "Gates".bold(),
+                progress.gates_passed,
+                progress.gates_total
+            );
+        }
+    }
+
+    // Assignee
+    if let Some(assignee) = &task.assignee {
+        println!("{:LABEL_WIDTH$} {}", "Assignee".bold(), assignee.cyan());
+    }
+
+    // Milestone, with its target date when defined
+    if let Some(name) = &task.milestone {
+        let date = config
+            .milestone(name)
+            .map(|m| format!("({})", m.date).dimmed().to_string())
+            .unwrap_or_default();
+        println!("{:LABEL_WIDTH$} {} {}", "Milestone".bold(), name.blue(), date);
+    }
+
+    if !task.tags.is_empty() {
+        println!("{:LABEL_WIDTH$} {}", "Tags".bold(), task.tags.join(", ").cyan());
+    }
+
+    // Effort: estimate vs actual time from work sessions
+    if let Some(estimate) = task.estimate {
+        println!("{:LABEL_WIDTH$} {}", "Estimate".bold(), estimate);
+    }
+    if !task.sessions.is_empty() {
+        let actual = task.actual_effort(Utc::now());
+        let over_estimate = task.estimate.is_some_and(|e| actual > e);
+        let actual_value = if over_estimate {
+            actual.to_string().yellow().to_string()
+        } else {
+            actual.to_string()
+        };
+        let sessions = match task.sessions.len() {
+            1 => "1 session".to_string(),
+            n => format!("{} sessions", n),
+        };
+        println!(
+            "{:LABEL_WIDTH$} {} {}",
+            "Actual".bold(),
+            actual_value,
+            format!("({})", sessions).dimmed()
+        );
+    }
+
+    // Due: own date, plus the effective date when a dependent's deadline is earlier
+    let effective_due = effective_due_dates(&graph).get(&task.id).copied();
+    if let Some(due) = effective_due {
+        let relative = if task.is_closed() {
+            String::new()
+        } else {
+            format_due(due, Local::now().date_naive())
+        };
+        let inherited = if task.due == Some(due) {
+            String::new()
+        } else {
+            let own = task.due.map(|d| format!(", own {}", d)).unwrap_or_default();
+            format!("(from dependents{})", own).dimmed().to_string()
+        };
+        println!(
+            "{:LABEL_WIDTH$} {} {} {}",
+            "Due".bold(),
+            due,
+            relative,
+            inherited
+        );
+    }
+
     // Before
     if !task.before.is_empty() {
         println!(
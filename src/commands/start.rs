@@ -1,5 +1,7 @@
 //! Start command - begin working on a task.
 
+use chrono::Utc;
+
 use crate::error_fmt::AppError;
 use crate::{jj, MontContext, Status};
 
@@ -7,7 +9,10 @@ use crate::{jj, MontContext, Status};
 ///
 /// Validates that the task exists, that the working copy is empty,
 /// and marks the task as in-progress.
-pub fn start(ctx: &MontContext, id: &str) -> Result<(), AppError> {
+///
+/// Tasks assigned to someone else are refused unless `claim` is set.
+/// With `claim`, the task is assigned to the current user.
+pub fn start(ctx: &MontContext, id: &str, claim: bool) -> Result<(), AppError> {
     // Validate task exists
     let graph = ctx.graph();
     let task = graph.get(id).ok_or_else(|| AppError::TaskNotFound {
@@ -20,11 +25,46 @@ pub fn start(ctx: &MontContext, id: &str) -> Result<(), AppError> {
         return Err(AppError::TaskAlreadyComplete(id.to_string()));
     }
 
+    // Epics complete through their children
+    if task.is_epic() {
+        return Err(AppError::CannotStartEpic(id.to_string()));
+    }
+
+    // Cancelled tasks are kept as a record, not worked on
+    if task.is_cancelled() {
+        return Err(AppError::TaskCancelled(id.to_string()));
+    }
+
     // Check if task is already in progress
     if task.is_in_progress() {
         return Err(AppError::TaskAlreadyInProgress(id.to_string()));
     }
 
+    // Blocked tasks must be explicitly unblocked first
+    if task.is_blocked() {
+        return Err(AppError::TaskBlocked {
+            task_id: id.to_string(),
+            reason: task.blocker.as_ref().map(|b| b.reason.clone()),
+        });
+    }
+
+    // Check the task isn't owned by someone else
+    let current_user = ctx.config().current_user();
+    if let Some(assignee) = &task.assignee
+        && !claim
+        && current_user.as_deref() != Some(assignee.as_str())
+    {
+        return Err(AppError::TaskAssignedToOther {
+            task_id: id.to_string(),
+            assignee: assignee.clone(),
+        });
+    }
+    let claimed_by = if claim {
+        Some(current_user.ok_or(AppError::UnknownUser)?)
+    } else {
+        None
+    };
+
     // Check if working copy is empty (skip if jj is disabled)
     let jj_enabled = ctx.config().jj.enabled;
     if jj_enabled {
@@ -34,13 +74,20 @@ pub fn start(ctx: &MontContext, id: &str) -> Result<(), AppError> {
         }
     }
 
-    // Update task status to in-progress
+    // Update task status to in-progress and open a work session
     let mut updated_task = task.clone();
     updated_task.status = Some(Status::InProgress);
+    updated_task.start_session(Utc::now());
+    if let Some(user) = &claimed_by {
+        updated_task.assignee = Some(user.clone());
+    }
     drop(graph);
 
     ctx.update(id, updated_task)?;
 
-    println!("Started task '{}'", id);
+    match claimed_by {
+        Some(user) => println!("Started task '{}' (claimed by {})", id, user),
+        None => println!("Started task '{}'", id),
+    }
     Ok(())
 }
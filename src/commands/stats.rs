@@ -0,0 +1,207 @@
+//! Stats command - estimate vs actual effort rollup across tasks.
+
+use chrono::{DateTime, Utc};
+use owo_colors::OwoColorize;
+
+use crate::error_fmt::AppError;
+use crate::render::TaskDisplayView;
+use crate::{Effort, MontContext, TaskGraph};
+
+/// Effort figures for a single task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskEffort {
+    pub id: String,
+    pub estimate: Option<Effort>,
+    pub actual: Effort,
+    pub complete: bool,
+}
+
+/// Estimate vs actual rollup over a set of tasks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffortRollup {
+    pub tasks: Vec<TaskEffort>,
+}
+
+impl EffortRollup {
+    /// Collect effort for the given task IDs, skipping gates, jots, epics and cancelled tasks.
+    pub fn collect(graph: &TaskGraph, ids: &[String], now: DateTime<Utc>) -> Self {
+        let tasks = ids
+            .iter()
+            .filter_map(|id| graph.get(id))
+            .filter(|t| !t.is_gate() && !t.is_jot() && !t.is_epic() && !t.is_cancelled())
+            .map(|t| TaskEffort {
+                id: t.id.clone(),
+                estimate: t.estimate,
+                actual: t.actual_effort(now),
+                complete: t.is_complete(),
+            })
+            .collect();
+        EffortRollup { tasks }
+    }
+
+    pub fn total_estimate(&self) -> Effort {
+        self.tasks.iter().filter_map(|t| t.estimate).sum()
+    }
+
+    pub fn total_actual(&self) -> Effort {
+        self.tasks.iter().map(|t| t.actual).sum()
+    }
+
+    pub fn estimated_count(&self) -> usize {
+        self.tasks.iter().filter(|t| t.estimate.is_some()).count()
+    }
+
+    /// Completed tasks that have both an estimate and recorded time.
+    fn calibrated(&self) -> impl Iterator<Item = &TaskEffort> {
+        self.tasks
+            .iter()
+            .filter(|t| t.complete && t.estimate.is_some_and(|e| !e.is_zero()) && !t.actual.is_zero())
+    }
+
+    /// Ratio of actual to estimated time over completed, estimated tasks.
+    ///
+    /// Returns None when no completed task has both an estimate and recorded time.
+    pub fn accuracy(&self) -> Option<f64> {
+        let (estimate, actual) = self.calibrated().fold((0u64, 0u64), |(e, a), t| {
+            (
+                e + t.estimate.map(|e| e.minutes()).unwrap_or(0),
+                a + t.actual.minutes(),
+            )
+        });
+        (estimate > 0).then(|| actual as f64 / estimate as f64)
+    }
+}
+
+/// Print an estimate vs actual rollup for the subgraph around `id`, or for all tasks.
+pub fn stats(ctx: &MontContext, id: Option<&str>) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let config = ctx.config();
+
+    let ids: Vec<String> = match id {
+        Some(id) => {
+            if !graph.contains(id) {
+                return Err(AppError::TaskNotFound {
+                    task_id: id.to_string(),
+                    tasks_dir: ctx.tasks_dir().display().to_string(),
+                });
+            }
+            graph.subgraph(&[id])
+        }
+        None => {
+            let mut ids: Vec<String> = graph.keys().cloned().collect();
+            ids.sort();
+            ids
+        }
+    };
+
+    let rollup = EffortRollup::collect(&graph, &ids, Utc::now());
+    if rollup.tasks.is_empty() {
+        println!("No tasks found");
+        return Ok(());
+    }
+
+    let heading = match id {
+        Some(id) => format!("Effort for group of {}", id),
+        None => "Effort across all tasks".to_string(),
+    };
+    println!("{}", heading.bold());
+
+    let id_width = rollup.tasks.iter().map(|t| t.id.len()).max().unwrap_or(0);
+    for entry in &rollup.tasks {
+        let Some(task) = graph.get(&entry.id) else {
+            continue;
+        };
+        let view = TaskDisplayView::from_task(task, &graph, &config.default_gates);
+        let estimate = entry
+            .estimate
+            .map(|e| e.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let actual = if entry.estimate.is_some_and(|e| entry.actual > e) {
+            entry.actual.to_string().yellow().to_string()
+        } else {
+            entry.actual.to_string()
+        };
+        println!(
+            "  {} {:>9} {} {}",
+            view.id_colored_padded(id_width),
+            estimate,
+            "→".dimmed(),
+            actual
+        );
+    }
+
+    println!();
+    println!("{}", "Totals".bold());
+    println!(
+        "  {:<9} estimated ({} of {} tasks)",
+        rollup.total_estimate().to_string().cyan(),
+        rollup.estimated_count(),
+        rollup.tasks.len()
+    );
+    println!("  {:<9} actual", rollup.total_actual().to_string().cyan());
+    match rollup.accuracy() {
+        Some(ratio) => println!(
+            "  {:<9} actual / estimate on {} completed tasks",
+            format!("{:.2}x", ratio).cyan(),
+            rollup.calibrated().count()
+        ),
+        None => println!(
+            "  {}",
+            "no completed tasks with both an estimate and recorded time".dimmed()
+        ),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Session, Status, Task};
+    use chrono::TimeZone;
+
+    fn at(h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, h, 0, 0).unwrap()
+    }
+
+    fn make_task(id: &str, estimate: Option<&str>, hours: &[(u32, u32)], complete: bool) -> Task {
+        Task {
+            id: id.to_string(),
+            status: complete.then_some(Status::Complete),
+            estimate: estimate.map(|e| e.parse().unwrap()),
+            sessions: hours
+                .iter()
+                .map(|(s, e)| Session { start: at(*s), end: Some(at(*e)) })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rollup_sums_sessions_and_estimates() {
+        let graph: TaskGraph = vec![
+            make_task("a", Some("2h"), &[(9, 10), (11, 13)], true),
+            make_task("b", Some("1h"), &[], false),
+            make_task("c", None, &[(9, 10)], false),
+        ]
+        .into_iter()
+        .collect();
+        let ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        let rollup = EffortRollup::collect(&graph, &ids, at(18));
+        assert_eq!(rollup.total_estimate().minutes(), 180);
+        assert_eq!(rollup.total_actual().minutes(), 240);
+        assert_eq!(rollup.estimated_count(), 2);
+        // Only "a" is complete with an estimate: 3h actual over 2h estimated
+        assert_eq!(rollup.accuracy(), Some(1.5));
+    }
+
+    #[test]
+    fn test_rollup_without_completed_estimates_has_no_accuracy() {
+        let graph: TaskGraph = vec![make_task("a", Some("2h"), &[(9, 10)], false)]
+            .into_iter()
+            .collect();
+        let rollup = EffortRollup::collect(&graph, &["a".to_string()], at(18));
+        assert_eq!(rollup.accuracy(), None);
+    }
+}
@@ -2,14 +2,21 @@
 
 use std::collections::HashSet;
 
+use chrono::{Local, NaiveDate};
 use owo_colors::OwoColorize;
 
+use crate::context::graph::{effective_due_dates, epic_progress};
 use crate::jj;
-use crate::render::{format_task_line, print_gates_section, task_marker_for_state, TaskDisplayView};
+use crate::render::{
+    format_blocker, format_due, format_epic_details, print_gates_section, task_marker_for_state, DueState, TaskDisplayView, MAX_TITLE_LEN,
+};
 use crate::{MontContext, Task, TaskGraph, TaskType};
 
 /// Show status of in-progress tasks with full details, up-next tasks, and info.
-pub fn status(ctx: &MontContext) {
+///
+/// With `assignee`, the in-progress and up-next sections only show tasks
+/// assigned to that user.
+pub fn status(ctx: &MontContext, assignee: Option<&str>) {
     let graph = ctx.graph();
     let config = ctx.config();
 
@@ -22,8 +29,12 @@ pub fn status(ctx: &MontContext) {
     let in_progress: Vec<_> = graph
         .values()
         .filter(|t| t.is_in_progress())
+        .filter(|t| assignee.is_none_or(|a| t.is_assigned_to(a)))
         .collect();
 
+    let due_dates = effective_due_dates(&graph);
+    let today = Local::now().date_naive();
+
     // Track if we've printed a section (for spacing)
     let mut has_printed_section = false;
 
@@ -34,45 +45,117 @@ pub fn status(ctx: &MontContext) {
             if i > 0 {
                 println!();
             }
-            print_task_details(ctx, task);
+            print_task_details(ctx, task, due_dates.get(&task.id).copied(), today);
         }
         has_printed_section = true;
     }
 
     // Up Next section (only shown if there are tasks)
-    let up_next = find_up_next(&in_progress, &graph);
+    let mut up_next = find_up_next(&in_progress, &graph);
+    up_next.retain(|t| assignee.is_none_or(|a| t.is_assigned_to(a)));
     if !up_next.is_empty() {
         if has_printed_section {
             println!();
         }
         println!("{}", "Up Next".bold());
         for task in up_next {
-            let view = TaskDisplayView::from_task(task, &graph, &config.default_gates);
+            let view = TaskDisplayView::from_task(task, &graph, &config.default_gates)
+                .with_due(due_dates.get(&task.id).copied(), today);
             let marker = task_marker_for_state(view.state);
-            let line = format_task_line(task, &graph, &config.default_gates);
+            let line = view.format_line(MAX_TITLE_LEN);
             println!("  {} {}", marker.bright_black(), line.bright_black());
         }
         has_printed_section = true;
     }
 
+    // Blocked section (only shown if there are blocked tasks)
+    let mut blocked: Vec<&Task> = graph
+        .values()
+        .filter(|t| t.is_blocked())
+        .filter(|t| assignee.is_none_or(|a| t.is_assigned_to(a)))
+        .collect();
+    blocked.sort_by(|a, b| a.id.cmp(&b.id));
+    if !blocked.is_empty() {
+        if has_printed_section {
+            println!();
+        }
+        println!("{}", "Blocked".bold());
+        for task in blocked {
+            let view = TaskDisplayView::from_task(task, &graph, &config.default_gates);
+            println!("  {} {}", task_marker_for_state(view.state), view.format_line(MAX_TITLE_LEN));
+            if let Some(blocker) = &task.blocker {
+                println!("      {}", format_blocker(blocker).dimmed());
+            }
+        }
+        has_printed_section = true;
+    }
+
+    // Epics section (only shown if there are open epics)
+    let mut epics: Vec<&Task> = graph
+        .values()
+        .filter(|t| t.is_epic() && !t.is_closed())
+        .filter(|t| assignee.is_none_or(|a| t.is_assigned_to(a)))
+        .collect();
+    epics.sort_by(|a, b| a.id.cmp(&b.id));
+    if !epics.is_empty() {
+        if has_printed_section {
+            println!();
+        }
+        println!("{}", "Epics".bold());
+        for task in epics {
+            let view = TaskDisplayView::from_task(task, &graph, &config.default_gates)
+                .with_due(due_dates.get(&task.id).copied(), today);
+            println!("  {} {}", task_marker_for_state(view.state), view.format_line(MAX_TITLE_LEN));
+            let details = format_epic_details(&epic_progress(&task.id, &graph, &config.default_gates));
+            if !details.is_empty() {
+                println!("      {}", details.dimmed());
+            }
+        }
+        has_printed_section = true;
+    }
+
     // Info section
     if has_printed_section {
         println!();
     }
     println!("{}", "Info".bold());
     let ready_count = count_ready_tasks(&graph);
-    let jot_count = graph.values().filter(|t| t.is_jot() && !t.is_complete()).count();
+    let jot_count = graph.values().filter(|t| t.is_jot() && !t.is_closed()).count();
     let stopped_count = graph.values().filter(|t| t.is_stopped()).count();
+    let blocked_count = graph.values().filter(|t| t.is_blocked()).count();
     let gate_count = graph.values().filter(|t| t.is_gate()).count();
 
     let completed_count = graph.values().filter(|t| t.is_complete()).count();
+    let cancelled_count = graph.values().filter(|t| t.is_cancelled()).count();
+
+    // Overdue and due-soon counts use effective due dates of open tasks
+    let open_due_states: Vec<DueState> = graph
+        .values()
+        .filter(|t| !t.is_closed() && !t.is_gate())
+        .filter_map(|t| due_dates.get(&t.id))
+        .map(|due| DueState::classify(*due, today))
+        .collect();
+    let overdue_count = open_due_states.iter().filter(|s| **s == DueState::Overdue).count();
+    let due_soon_count = open_due_states.iter().filter(|s| **s == DueState::Soon).count();
 
     // Left-align numbers in a 4-char field
     println!("  {:<4} tasks ready for work", ready_count.to_string().cyan());
     println!("  {:<4} jots needing distillation", jot_count.to_string().yellow());
     println!("  {:<4} stopped", stopped_count.to_string().yellow());
+    if blocked_count > 0 {
+        println!("  {:<4} blocked", blocked_count.to_string().red());
+    }
+    if overdue_count > 0 {
+        println!("  {:<4} overdue", overdue_count.to_string().red().bold());
+    }
+    if due_soon_count > 0 {
+        println!("  {:<4} due soon", due_soon_count.to_string().yellow());
+    }
     println!("  {:<4} gates", gate_count.to_string().purple());
     println!("  {:<4} completed", completed_count.to_string().bright_black());
+    if cancelled_count > 0 {
+        println!("  {:<4} cancelled", cancelled_count.to_string().bright_black());
+    }
 
     // Working Copy section (jj status) - skip if jj is disabled
     if config.jj.enabled
@@ -87,7 +170,7 @@ pub fn status(ctx: &MontContext) {
     }
 }
 
-fn print_task_details(ctx: &MontContext, task: &Task) {
+fn print_task_details(ctx: &MontContext, task: &Task, due: Option<NaiveDate>, today: NaiveDate) {
     let graph = ctx.graph();
     let config = ctx.config();
     let view = TaskDisplayView::from_task(task, &graph, &config.default_gates);
@@ -114,9 +197,20 @@ fn print_task_details(ctx: &MontContext, task: &Task) {
         TaskType::Task => "[task]".bright_green().to_string(),
         TaskType::Jot => "[jot]".yellow().to_string(),
         TaskType::Gate => "[gate]".purple().to_string(),
+        TaskType::Epic => "[epic]".blue().to_string(),
     };
     println!("  {:LABEL_WIDTH$} {}", "Type".bold(), type_value);
 
+    // Assignee
+    if let Some(assignee) = &task.assignee {
+        println!("  {:LABEL_WIDTH$} {}", "Assignee".bold(), assignee.cyan());
+    }
+
+    // Due (effective, so deadlines of dependents show here too)
+    if let Some(due) = due {
+        println!("  {:LABEL_WIDTH$} {} {}", "Due".bold(), due, format_due(due, today));
+    }
+
     // Before
     if !task.before.is_empty() {
         println!(
@@ -147,8 +241,8 @@ fn find_up_next<'a>(in_progress: &[&Task], graph: &'a TaskGraph) -> Vec<&'a Task
     let mut up_next: Vec<&Task> = graph
         .values()
         .filter(|task| {
-            // Skip if already in progress or complete
-            if task.is_in_progress() || task.is_complete() || task.is_gate() {
+            // Skip if already in progress, complete or cancelled
+            if task.is_in_progress() || task.is_closed() || task.is_gate() || task.is_epic() {
                 return false;
             }
 
@@ -180,14 +274,20 @@ fn count_ready_tasks(graph: &TaskGraph) -> usize {
     graph
         .values()
         .filter(|task| {
-            // Skip if already in progress, complete, or is a gate/jot
-            if task.is_in_progress() || task.is_complete() || task.is_gate() || task.is_jot() {
+            // Skip if already in progress, complete, cancelled, blocked, or is a gate/jot/epic
+            if task.is_in_progress()
+                || task.is_closed()
+                || task.is_blocked()
+                || task.is_gate()
+                || task.is_jot()
+                || task.is_epic()
+            {
                 return false;
             }
 
-            // Check all after dependencies are complete
+            // Check all after dependencies are satisfied
             for after_id in &task.after {
-                if graph.get(after_id).is_some_and(|t| !t.is_complete()) {
+                if graph.get(after_id).is_some_and(|t| !graph.satisfies_dependents(t)) {
                     return false;
                 }
             }
@@ -1,12 +1,15 @@
 //! Stop command - clear in-progress status from a task.
 
+use chrono::Utc;
+
 use crate::error_fmt::AppError;
 use crate::MontContext;
 
 /// Stop working on a task, making it ready for work again.
 ///
 /// Validates that the task exists and is in-progress,
-/// then clears the in-progress status.
+/// then clears the in-progress status. Time spent since `start` is
+/// recorded as a work session on the task.
 pub fn stop(ctx: &MontContext, id: &str) -> Result<(), AppError> {
     // Validate task exists
     let graph = ctx.graph();
@@ -20,9 +23,10 @@ pub fn stop(ctx: &MontContext, id: &str) -> Result<(), AppError> {
         return Err(AppError::TaskNotInProgress(id.to_string()));
     }
 
-    // Clear the in-progress status
+    // Clear the in-progress status and close the work session
     let mut updated_task = task.clone();
     updated_task.status = None;
+    updated_task.end_session(Utc::now());
     drop(graph);
 
     ctx.update(id, updated_task)?;
@@ -7,7 +7,7 @@ use owo_colors::OwoColorize;
 
 use super::shared::{
     build_multiedit_comment, find_most_recent_temp_file, make_temp_file, MultiEditMode,
-    parse_multi_task_content, remove_temp_file, resolve_ids, TaskFilter,
+    needs_resolving, parse_multi_task_content, remove_temp_file, resolve_ids, TaskFilter,
 };
 use crate::error_fmt::AppError;
 use crate::jj;
@@ -120,8 +120,8 @@ pub fn task(ctx: &MontContext, args: TaskArgs) -> Result<(), AppError> {
         return content_mode(ctx, &content, &args.ids);
     }
 
-    // Resolve `?` placeholders in IDs via interactive picker
-    let mut ids = if args.ids.iter().any(|id| id == "?") {
+    // Resolve `?` placeholders via interactive picker, and expand queries
+    let mut ids = if needs_resolving(&ctx.graph(), &args.ids) {
         resolve_ids(&ctx.graph(), &args.ids, TaskFilter::Active)?
     } else {
         args.ids.clone()
@@ -219,6 +219,16 @@ struct TaskPatch {
     status: Option<String>,
     #[serde(default)]
     r#type: Option<String>,
+    #[serde(default)]
+    estimate: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    assignee: Option<String>,
+    #[serde(default)]
+    milestone: Option<String>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
 }
 
 /// Apply a YAML patch to a single task.
@@ -294,19 +304,67 @@ fn patch_mode(ctx: &MontContext, ids: &[String], patch_yaml: &str) -> Result<(),
         task.status = match status.to_lowercase().as_str() {
             "inprogress" | "in-progress" | "in_progress" => Some(Status::InProgress),
             "stopped" => Some(Status::Stopped),
+            "blocked" => Some(Status::Blocked),
             "complete" | "done" => Some(Status::Complete),
+            "cancelled" | "canceled" => Some(Status::Cancelled),
             "" | "pending" | "ready" => None,
             _ => return Err(AppError::InvalidArgs(format!("invalid status: {}", status))),
         };
+        // Keep the status records in step with the status itself
+        if !task.is_blocked() {
+            task.blocker = None;
+        }
+        if !task.is_cancelled() {
+            task.cancelled = None;
+        } else if task.cancelled.is_none() {
+            task.cancelled = Some(crate::Cancellation { at: chrono::Utc::now(), reason: None });
+        }
+        if !task.is_complete() {
+            task.completed = None;
+        } else if task.completed.is_none() {
+            task.completed = Some(chrono::Utc::now());
+        }
     }
     if let Some(task_type) = patch.r#type {
         task.task_type = match task_type.to_lowercase().as_str() {
             "task" => TaskType::Task,
             "jot" => TaskType::Jot,
             "gate" => TaskType::Gate,
+            "epic" => TaskType::Epic,
             _ => return Err(AppError::InvalidArgs(format!("invalid type: {}", task_type))),
         };
     }
+    if let Some(estimate) = patch.estimate {
+        task.estimate = if estimate.trim().is_empty() {
+            None
+        } else {
+            Some(estimate.parse().map_err(|e: crate::EffortParseError| {
+                AppError::InvalidArgs(e.to_string())
+            })?)
+        };
+    }
+    if let Some(due) = patch.due {
+        task.due = if due.trim().is_empty() {
+            None
+        } else {
+            Some(crate::parse_due_date(&due).map_err(AppError::InvalidArgs)?)
+        };
+    }
+    if let Some(assignee) = patch.assignee {
+        task.assignee = Some(assignee).filter(|a| !a.trim().is_empty());
+    }
+    if let Some(milestone) = patch.milestone {
+        let milestone = Some(milestone).filter(|m| !m.trim().is_empty());
+        if let Some(name) = &milestone
+            && ctx.config().milestone(name).is_none()
+        {
+            return Err(AppError::UnknownMilestone(name.clone()));
+        }
+        task.milestone = milestone;
+    }
+    if let Some(tags) = patch.tags {
+        task.tags = tags;
+    }
 
     // Update the task (this handles reference rewriting if ID changed)
     ctx.update(original_id, task.clone())?;
@@ -398,43 +456,36 @@ fn create_mode(
         Some(TaskType::Gate) => {
             Task {
                 id: "new-gate".to_string(),
-                new_id: None,
                 title: Some("New Gate".to_string()),
                 description: "Description here.".to_string(),
-                before: vec![],
-                after: vec![],
-                gates: vec![],
                 task_type: TaskType::Gate,
-                status: None,
-                deleted: false,
+                ..Default::default()
+            }
+        }
+        Some(TaskType::Epic) => {
+            Task {
+                id: "new-epic".to_string(),
+                title: Some("New Epic".to_string()),
+                description: "Goal of this epic. Link tasks to it with `before: [new-epic]`.".to_string(),
+                task_type: TaskType::Epic,
+                ..Default::default()
             }
         }
         Some(TaskType::Jot) => {
             Task {
                 id: "new-jot".to_string(),
-                new_id: None,
                 title: Some("New Jot".to_string()),
                 description: "Quick idea here.".to_string(),
-                before: vec![],
-                after: vec![],
-                gates: vec![],
                 task_type: TaskType::Jot,
-                status: None,
-                deleted: false,
+                ..Default::default()
             }
         }
         _ => {
             Task {
                 id: "new-task".to_string(),
-                new_id: None,
                 title: Some("New Task".to_string()),
                 description: "Description here.".to_string(),
-                before: vec![],
-                after: vec![],
-                gates: vec![],
-                task_type: TaskType::Task,
-                status: None,
-                deleted: false,
+                ..Default::default()
             }
         }
     };
@@ -793,15 +844,9 @@ pub fn jot(ctx: &MontContext, args: JotArgs) -> Result<(), AppError> {
 
     let jot = Task {
         id: jot_id,
-        new_id: None,
         title: Some(jot_title),
-        description: String::new(),
-        before: vec![],
-        after: vec![],
-        gates: vec![],
         task_type: TaskType::Jot,
-        status: None,
-        deleted: false,
+        ..Default::default()
     };
 
     // Quick mode: skip editor and confirmation, create jot immediately
@@ -922,15 +967,9 @@ when you save and confirm."#,
     // Create a starter task template
     let starter = Task {
         id: "new-task".to_string(),
-        new_id: None,
         title: Some("New Task".to_string()),
         description: "Description here.".to_string(),
-        before: vec![],
-        after: vec![],
-        gates: vec![],
-        task_type: TaskType::Task,
-        status: None,
-        deleted: false,
+        ..Default::default()
     };
 
     let temp_path = make_temp_file("distill", std::slice::from_ref(&starter), Some(&comment))?;
@@ -0,0 +1,182 @@
+//! Why command - explains why a task isn't ready to work on.
+
+use std::collections::HashMap;
+
+use owo_colors::OwoColorize;
+
+use crate::context::graph::{is_available, waiting_on, waiting_on_transitively};
+use crate::context::EdgeKind;
+use crate::error_fmt::AppError;
+use crate::render::render_section;
+use crate::{MontContext, Task, TaskGraph};
+
+/// Explain why `id` doesn't show up in `mont ready`.
+///
+/// Lists the task's own state (blocked, an epic, ...), the `after`
+/// dependencies and subtasks it's waiting on, and then draws everything
+/// upstream that's holding it up, down to the tasks that can be started now.
+pub fn why(ctx: &MontContext, id: &str) -> Result<(), AppError> {
+    let graph = ctx.graph();
+    let config = ctx.config();
+
+    let Some(task) = graph.get(id) else {
+        return Err(AppError::TaskNotFound {
+            task_id: id.to_string(),
+            tasks_dir: ctx.tasks_dir().display().to_string(),
+        });
+    };
+
+    if task.is_closed() {
+        let status = if task.is_complete() { "complete" } else { "cancelled" };
+        println!("{} is already {}", id.bold(), status);
+        return Ok(());
+    }
+    if task.is_gate() {
+        println!("{} is a gate; gates are unlocked on the tasks that list them, not worked on directly", id.bold());
+        return Ok(());
+    }
+
+    let mut reasons: Vec<String> = Vec::new();
+    if task.is_epic() {
+        reasons.push("it's an epic; epics complete on their own when their tasks do".to_string());
+    }
+    if let Some(blocker) = &task.blocker {
+        let mut reason = format!("it's blocked: {}", blocker.reason);
+        if let Some(reference) = &blocker.reference {
+            reason.push_str(&format!(" [{}]", reference));
+        }
+        reason.push_str(&format!(" (clear it with `mont unblock {}`)", id));
+        reasons.push(reason);
+    }
+    for (kind, prerequisite) in waiting_on(task, &*graph) {
+        let state = describe(prerequisite, &graph);
+        reasons.push(match kind {
+            EdgeKind::After => format!("it's after {}, which {}", prerequisite.id.cyan(), state),
+            EdgeKind::Before => format!("its subtask {} {}", prerequisite.id.cyan(), state),
+        });
+    }
+
+    if reasons.is_empty() {
+        if task.is_in_progress() {
+            println!("{} is already in progress", id.bold());
+        } else {
+            println!("{} is ready; start it with `mont start {}`", id.bold(), id);
+        }
+        return Ok(());
+    }
+
+    println!("{} isn't ready:", id.bold());
+    for reason in &reasons {
+        println!("  - {}", reason);
+    }
+
+    let upstream = waiting_on_transitively(task, &graph);
+    if upstream.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    print!("{}", upstream_tree(task, &upstream, &graph, &config.default_gates));
+
+    let ready: Vec<&str> = upstream
+        .iter()
+        .filter(|t| is_ready(t, &graph) && !t.is_in_progress())
+        .map(|t| t.id.as_str())
+        .collect();
+    if !ready.is_empty() {
+        println!();
+        println!("Start with: {}", ready.join(", ").bright_green());
+    }
+
+    Ok(())
+}
+
+/// Draw `task` and the `upstream` work holding it up. Only those tasks are
+/// drawn, but their state comes from the whole `graph`, so the policy on
+/// cancelled tasks and anything outside the tree still count.
+fn upstream_tree(task: &Task, upstream: &[&Task], graph: &TaskGraph, default_gates: &[String]) -> String {
+    let mut notes: HashMap<String, String> = HashMap::new();
+    notes.insert(task.id.clone(), "← this task".bold().to_string());
+    for t in upstream {
+        if let Some(note) = note(t, graph) {
+            notes.insert(t.id.clone(), note);
+        }
+    }
+    let subgraph: TaskGraph = upstream.iter().map(|t| (*t).clone()).chain([task.clone()]).collect();
+    render_section(&subgraph, graph, default_gates, &notes)
+}
+
+/// Whether a task would be listed by `mont ready`.
+fn is_ready(task: &Task, graph: &TaskGraph) -> bool {
+    !task.is_closed() && !task.is_gate() && !task.is_epic() && !task.is_blocked() && is_available(task, graph)
+}
+
+/// Why a task that something waits on hasn't finished, completing the
+/// sentence "`after`/subtask X ...".
+fn describe(task: &Task, graph: &TaskGraph) -> String {
+    if task.is_cancelled() {
+        "was cancelled, and cancelled_blocks_dependents is on".to_string()
+    } else if task.is_blocked() {
+        "is blocked".to_string()
+    } else if task.is_in_progress() {
+        "is in progress".to_string()
+    } else if task.is_epic() {
+        "is an epic with open tasks".to_string()
+    } else if is_available(task, graph) {
+        "hasn't been started".to_string()
+    } else {
+        "is waiting on other work".to_string()
+    }
+}
+
+/// The note shown next to an upstream task in the tree, if any.
+fn note(task: &Task, graph: &TaskGraph) -> Option<String> {
+    if let Some(blocker) = &task.blocker {
+        Some(format!("blocked: {}", blocker.reason).red().to_string())
+    } else if task.is_cancelled() {
+        Some("cancelled".bright_black().to_string())
+    } else if task.is_in_progress() {
+        Some("in progress".yellow().to_string())
+    } else if is_ready(task, graph) {
+        Some("ready".bright_green().to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Status;
+
+    fn make_task(id: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: Some(format!("{} title", id)),
+            ..Default::default()
+        }
+    }
+
+    fn strip_ansi(s: &str) -> String {
+        let re = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+        re.replace_all(s, "").to_string()
+    }
+
+    #[test]
+    fn test_tree_honours_cancelled_blocks_dependents() {
+        let mut dropped = make_task("dropped");
+        dropped.status = Some(Status::Cancelled);
+        let mut next = make_task("next");
+        next.after = vec!["dropped".to_string()];
+        let mut graph: TaskGraph = vec![dropped, next].into_iter().collect();
+        graph.set_cancelled_blocks_dependents(true);
+
+        let task = graph.get("next").unwrap();
+        let upstream = waiting_on_transitively(task, &graph);
+        assert_eq!(upstream.len(), 1);
+
+        let output = strip_ansi(&upstream_tree(task, &upstream, &graph, &[]));
+        assert!(output.contains("[wait] next"), "{}", output);
+        assert!(!output.contains("[task] next"), "{}", output);
+    }
+}
@@ -0,0 +1,182 @@
+//! The archive: `.tasks/archive`, where finished task groups are moved.
+//!
+//! Archived tasks aren't parsed or validated when the graph loads. Only their
+//! ids are collected, from the file names, so that references to them stay
+//! valid and count as satisfied. The archive is laid out like `.tasks`
+//! itself, namespaces included.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::graph::{self, GraphReadError, TaskGraph};
+use super::namespace;
+use super::task::{parse, ParseError, Task};
+
+/// Name of the archive directory inside `.tasks`.
+pub const ARCHIVE_DIR: &str = "archive";
+
+pub(crate) fn archive_dir(tasks_dir: &Path) -> PathBuf {
+    tasks_dir.join(ARCHIVE_DIR)
+}
+
+/// The id of the task archived at `path`, taken from its location.
+pub(crate) fn archived_id(archive_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(archive_dir).ok()?.with_extension("");
+    let id = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!id.is_empty()).then_some(id)
+}
+
+/// Ids of the tasks that can be archived.
+///
+/// A task qualifies when its group is complete (see `graph::is_group_complete`)
+/// and `is_old` accepts it. Gates are never archived. A task only goes along
+/// with everything beneath it, so a parent stays while any of its subtasks
+/// can't be archived yet.
+pub(crate) fn archivable(graph: &TaskGraph, is_old: impl Fn(&Task) -> bool) -> Vec<String> {
+    let active = || graph.values().filter(|t| !t.is_deleted());
+
+    let mut ids: HashSet<&str> = active()
+        .filter(|t| !t.is_gate() && graph::is_group_complete(t, graph) && is_old(t))
+        .map(|t| t.id.as_str())
+        .collect();
+
+    loop {
+        let held: Vec<&str> = active()
+            .filter(|t| !ids.contains(t.id.as_str()))
+            .flat_map(|t| t.before.iter())
+            .map(String::as_str)
+            .filter(|id| ids.contains(id))
+            .collect();
+        if held.is_empty() {
+            break;
+        }
+        for id in held {
+            ids.remove(id);
+        }
+    }
+
+    let mut ids: Vec<String> = ids.into_iter().map(str::to_string).collect();
+    ids.sort();
+    ids
+}
+
+/// Read and parse every archived task.
+///
+/// References are resolved against both the archive and `graph`.
+pub(crate) fn load(tasks_dir: &Path, graph: &TaskGraph) -> Result<Vec<Task>, GraphReadError> {
+    let dir = archive_dir(tasks_dir);
+    let mut errors = GraphReadError::new();
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let paths = match namespace::task_files(&dir) {
+        Ok(paths) => paths,
+        Err((path, e)) => {
+            errors.add_io_error(path, e);
+            return Err(errors);
+        }
+    };
+
+    let mut tasks = Vec::new();
+    for path in paths {
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                errors.add_io_error(path, e);
+                continue;
+            }
+        };
+        match parse(&content) {
+            Ok(task) if task.id.is_empty() => errors.add_parse_error(path, ParseError::EmptyId),
+            Ok(mut task) => {
+                if let Some(ns) = namespace::namespace_of(&dir, &path) {
+                    namespace::qualify_id(&mut task, &ns);
+                }
+                tasks.push(task);
+            }
+            Err(e) => errors.add_parse_error(path, e),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let ids: HashSet<String> = tasks.iter().map(|t| t.id.clone()).collect();
+    for task in &mut tasks {
+        namespace::qualify_references(task, |key| ids.contains(key) || graph.contains(key));
+    }
+    Ok(tasks)
+}
+
+/// Parse an age such as `30d`, `2w` or `12h`.
+pub fn parse_age(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid age '{}': expected a number followed by h, d or w", s))?;
+
+    let hours = match unit {
+        "h" => 1,
+        "d" => 24,
+        "w" => 24 * 7,
+        _ => return Err(format!("invalid age '{}': unit must be h, d or w", s)),
+    };
+    Ok(Duration::from_secs(number * hours * 60 * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::task::Status;
+
+    fn task(id: &str, status: Option<Status>, before: &[&str]) -> Task {
+        let mut task = parse(&format!("---\nid: {}\n---\n", id)).unwrap();
+        task.status = status;
+        task.before = before.iter().map(|s| s.to_string()).collect();
+        task
+    }
+
+    #[test]
+    fn test_archivable_takes_whole_groups() {
+        let done = Some(Status::Complete);
+        let graph: TaskGraph = vec![
+            task("finished-parent", done, &[]),
+            task("finished-child", done, &["finished-parent"]),
+            task("open-parent", None, &[]),
+            task("done-under-open", done, &["open-parent"]),
+            task("held-parent", done, &[]),
+            task("open-child", None, &["held-parent"]),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(archivable(&graph, |_| true), vec!["finished-child", "finished-parent"]);
+        assert!(archivable(&graph, |t| t.id != "finished-child").is_empty());
+    }
+
+    #[test]
+    fn test_archived_id() {
+        let dir = Path::new(".tasks/archive");
+        assert_eq!(archived_id(dir, &dir.join("a.md")).as_deref(), Some("a"));
+        assert_eq!(archived_id(dir, &dir.join("backend/a.md")).as_deref(), Some("backend/a"));
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d"), Ok(Duration::from_secs(30 * 24 * 3600)));
+        assert_eq!(parse_age("2w"), Ok(Duration::from_secs(14 * 24 * 3600)));
+        assert_eq!(parse_age("12h"), Ok(Duration::from_secs(12 * 3600)));
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("3m").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! Load cache: `.tasks/.cache/tasks.json`.
+//!
+//! Every command loads the whole graph, and agents run mont after every step,
+//! so re-parsing every task file each time adds up in large directories. The
+//! cache keeps each file's parsed task keyed by its path, size and
+//! modification time, so a load only parses the files that changed since the
+//! last one. When nothing changed at all, the graph was already validated and
+//! validation is skipped too.
+//!
+//! The cache is disposable. If it's missing, unreadable or was written by a
+//! different build of mont it's ignored and rebuilt, and failing to write it
+//! never fails a command. Builds are told apart by the crate version plus
+//! `SCHEMA`, which has to be bumped whenever the cached shape of `Task` or
+//! `Entry` changes.
+
+use std::collections::BTreeMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use super::frontmatter::{FrontmatterFormat, SourceText};
+use super::task::Task;
+
+const CACHE_DIR: &str = ".cache";
+const CACHE_FILE: &str = "tasks.json";
+
+/// Version of the cache layout, bumped whenever the serialized shape of
+/// `Task` or `Entry` changes so an unreleased build never trusts an old cache.
+const SCHEMA: u32 = 2;
+
+/// What a cache is keyed to: the build that wrote it and its layout.
+fn cache_version() -> String {
+    format!("{}+{}", env!("CARGO_PKG_VERSION"), SCHEMA)
+}
+
+/// Parsed tasks from the last load, keyed by path relative to `.tasks`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Cache {
+    /// Version of mont that wrote the cache, and its layout (see `cache_version`)
+    version: String,
+    /// Whether `entries` together with `archived` formed a valid graph
+    valid: bool,
+    archived: Vec<String>,
+    entries: BTreeMap<String, Entry>,
+    /// Entries carried over unchanged from the previous cache
+    #[serde(skip)]
+    reused: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    size: u64,
+    modified: (u64, u32),
+    frontmatter: FrontmatterFormat,
+    description: String,
+    /// The task as parsed from the file, before namespace qualification
+    task: Task,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            version: cache_version(),
+            valid: false,
+            archived: Vec::new(),
+            entries: BTreeMap::new(),
+            reused: 0,
+        }
+    }
+}
+
+impl Cache {
+    /// Read the cache for `tasks_dir`, or an empty one if there's no usable cache.
+    ///
+    /// A cache is only used if it was written by the same crate version and
+    /// `SCHEMA`. Changing `Task`'s serde shape without bumping `SCHEMA` would
+    /// leave it to deserialization failing to notice, and a field that was
+    /// added with a default would silently read as that default.
+    pub(crate) fn read(tasks_dir: &Path) -> Self {
+        std::fs::read(path(tasks_dir))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Cache>(&bytes).ok())
+            .filter(|cache| cache.version == cache_version())
+            .unwrap_or_default()
+    }
+
+    /// Move the entry for the file at `path` over from `cached` if the file
+    /// hasn't changed, returning its task.
+    pub(crate) fn reuse(&mut self, cached: &mut Cache, key: String, path: &Path, meta: &Metadata) -> Option<Task> {
+        let entry = cached.entries.remove(&key)?;
+        if entry.size != meta.len() || Some(entry.modified) != modified(meta) {
+            return None;
+        }
+
+        let mut task = entry.task.clone();
+        task.frontmatter = entry.frontmatter;
+        task.description = entry.description.clone();
+        task.source = SourceText::file(path);
+        self.entries.insert(key, entry);
+        self.reused += 1;
+        Some(task)
+    }
+
+    pub(crate) fn insert(&mut self, key: String, meta: &Metadata, task: &Task) {
+        let Some(modified) = modified(meta) else {
+            return;
+        };
+        self.entries.insert(
+            key,
+            Entry {
+                size: meta.len(),
+                modified,
+                frontmatter: task.frontmatter,
+                description: task.description.clone(),
+                task: task.clone(),
+            },
+        );
+    }
+
+    /// Whether the cache this one was filled from held a valid graph with
+    /// exactly the same files and archive, so this one does too.
+    pub(crate) fn is_unchanged_from(&self, cached: &Cache) -> bool {
+        cached.valid && cached.archived == self.archived && cached.entries.is_empty() && self.reused == self.entries.len()
+    }
+
+    pub(crate) fn set_archived(&mut self, mut archived: Vec<String>) {
+        archived.sort();
+        self.archived = archived;
+    }
+
+    pub(crate) fn set_valid(&mut self, valid: bool) {
+        self.valid = valid;
+    }
+
+    /// Write the cache, ignoring failures.
+    pub(crate) fn write(&self, tasks_dir: &Path) {
+        let dir = tasks_dir.join(CACHE_DIR);
+        let Ok(bytes) = serde_json::to_vec(self) else {
+            return;
+        };
+        let _ = std::fs::create_dir_all(&dir)
+            // Keep the cache out of version control
+            .and_then(|()| std::fs::write(dir.join(".gitignore"), "*\n"))
+            .and_then(|()| {
+                let tmp = dir.join(format!("{}.tmp", CACHE_FILE));
+                std::fs::write(&tmp, bytes).and_then(|()| std::fs::rename(&tmp, path(tasks_dir)))
+            });
+    }
+}
+
+/// Key for a task file: its path relative to `tasks_dir`.
+pub(crate) fn key(tasks_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(tasks_dir).unwrap_or(path).to_string_lossy().into_owned()
+}
+
+fn path(tasks_dir: &Path) -> PathBuf {
+    tasks_dir.join(CACHE_DIR).join(CACHE_FILE)
+}
+
+fn modified(meta: &Metadata) -> Option<(u64, u32)> {
+    let since_epoch = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((since_epoch.as_secs(), since_epoch.subsec_nanos()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use tempfile::TempDir;
+
+    use crate::context::{MontContext, Task};
+
+    /// Overwrite a file without changing its size or modification time.
+    fn overwrite_in_place(path: &std::path::Path, content: &str) {
+        let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+        assert_eq!(std::fs::metadata(path).unwrap().len(), content.len() as u64);
+        std::fs::write(path, content).unwrap();
+        std::fs::File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn test_unchanged_files_come_from_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let path = root.join("task.md");
+        std::fs::write(&path, "---\nid: task\ntitle: One\n---\n").unwrap();
+
+        MontContext::load(root.to_path_buf()).unwrap();
+        assert!(root.join(".cache/tasks.json").exists());
+
+        // Same size and mtime: the cached parse wins
+        overwrite_in_place(&path, "---\nid: task\ntitle: Two\n---\n");
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        assert_eq!(ctx.graph().get("task").unwrap().title.as_deref(), Some("One"));
+
+        // A real edit changes the size or mtime and is picked up
+        std::fs::write(&path, "---\nid: task\ntitle: Three!\n---\n").unwrap();
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        assert_eq!(ctx.graph().get("task").unwrap().title.as_deref(), Some("Three!"));
+    }
+
+    #[test]
+    fn test_cached_tasks_keep_their_formatting() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let path = root.join("task.md");
+        std::fs::write(&path, "---\n# keep me\nid: task\ntitle: One\n---\n\nBody\n").unwrap();
+
+        MontContext::load(root.to_path_buf()).unwrap();
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        let mut task = ctx.graph().get("task").unwrap().clone();
+        task.title = Some("Renamed".to_string());
+        ctx.update("task", task).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("---\n# keep me\nid: task\n"));
+        assert!(content.contains("title: Renamed\n"));
+        assert!(content.ends_with("\nBody\n"));
+    }
+
+    #[test]
+    fn test_invalid_graph_is_not_trusted() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("task.md"), "---\nid: task\nafter:\n  - missing\n---\n").unwrap();
+
+        assert!(MontContext::load(root.to_path_buf()).is_err());
+        assert!(MontContext::load(root.to_path_buf()).is_err());
+    }
+
+    /// Compare a cold load of a synthetic 10k-task directory against a
+    /// cached one. Run with
+    /// `cargo test --release -- --ignored --nocapture load_benchmark`.
+    #[test]
+    #[ignore]
+    fn load_benchmark() {
+        const TASKS: usize = 10_000;
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for i in 0..TASKS {
+            let mut content = format!("---\nid: task-{i}\ntitle: Task number {i}\n");
+            if i % 3 == 0 {
+                content.push_str("status: complete\n");
+            }
+            if i > 0 {
+                content.push_str(&format!("after:\n  - task-{}\n  - task-{}\n", i / 2, i - 1));
+            }
+            content.push_str(&format!("---\n\nDescription of task {i}.\n"));
+            std::fs::write(root.join(format!("task-{i}.md")), content).unwrap();
+        }
+
+        let tasks = |ctx: &MontContext| {
+            let mut tasks: Vec<Task> = ctx.graph().values().cloned().collect();
+            tasks.sort_by(|a, b| a.id.cmp(&b.id));
+            tasks
+        };
+
+        let start = Instant::now();
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        let cold = start.elapsed();
+        let cold_tasks = tasks(&ctx);
+        assert_eq!(cold_tasks.len(), TASKS);
+
+        let start = Instant::now();
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        let cached = start.elapsed();
+        assert_eq!(tasks(&ctx), cold_tasks);
+
+        println!("{TASKS} tasks: cold load {cold:?}, cached load {cached:?}");
+    }
+}
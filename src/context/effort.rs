@@ -0,0 +1,220 @@
+//! Effort estimates and recorded work sessions.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use thiserror::Error;
+
+/// Minutes in a working day, used by the `d` suffix.
+const MINUTES_PER_DAY: u64 = 8 * 60;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("invalid effort '{0}', expected a duration like 30m, 2h, 1h30m or 1d")]
+pub struct EffortParseError(pub String);
+
+/// An amount of effort, stored in whole minutes.
+///
+/// Written as a duration string: `45m`, `2h`, `1h30m`, `1.5h` or `2d`,
+/// where a day is a working day of eight hours.
+///
+/// ```
+/// use mont::Effort;
+///
+/// let effort: Effort = "1h30m".parse().unwrap();
+/// assert_eq!(effort.minutes(), 90);
+/// assert_eq!(effort.to_string(), "1h 30m");
+/// assert_eq!("1d".parse::<Effort>().unwrap().minutes(), 480);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Effort(u64);
+
+impl Effort {
+    pub fn from_minutes(minutes: u64) -> Self {
+        Effort(minutes)
+    }
+
+    pub fn minutes(&self) -> u64 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Format compactly for frontmatter, e.g. `1h30m`.
+    pub fn to_compact(&self) -> String {
+        self.to_string().replace(' ', "")
+    }
+}
+
+impl std::ops::Add for Effort {
+    type Output = Effort;
+
+    fn add(self, rhs: Effort) -> Effort {
+        Effort(self.0 + rhs.0)
+    }
+}
+
+impl std::iter::Sum for Effort {
+    fn sum<I: Iterator<Item = Effort>>(iter: I) -> Effort {
+        iter.fold(Effort::default(), |acc, e| acc + e)
+    }
+}
+
+impl fmt::Display for Effort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hours = self.0 / 60;
+        let minutes = self.0 % 60;
+        match (hours, minutes) {
+            (0, m) => write!(f, "{}m", m),
+            (h, 0) => write!(f, "{}h", h),
+            (h, m) => write!(f, "{}h {}m", h, m),
+        }
+    }
+}
+
+impl FromStr for Effort {
+    type Err = EffortParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || EffortParseError(s.to_string());
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.is_empty() {
+            return Err(err());
+        }
+
+        let mut total = 0.0_f64;
+        let mut number = String::new();
+        for c in compact.chars() {
+            if c.is_ascii_digit() || c == '.' {
+                number.push(c);
+                continue;
+            }
+            let value: f64 = number.parse().map_err(|_| err())?;
+            let unit = match c.to_ascii_lowercase() {
+                'm' => 1.0,
+                'h' => 60.0,
+                'd' => MINUTES_PER_DAY as f64,
+                _ => return Err(err()),
+            };
+            total += value * unit;
+            number.clear();
+        }
+        // A trailing bare number is not allowed: "90" is ambiguous
+        if !number.is_empty() {
+            return Err(err());
+        }
+
+        Ok(Effort(total.round() as u64))
+    }
+}
+
+impl<'de> Deserialize<'de> for Effort {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Effort {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_compact())
+    }
+}
+
+/// A single period of active work on a task, opened by `mont start` and
+/// closed by `mont stop` or `mont done`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Session {
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub start: DateTime<Utc>,
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_timestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    /// Time spent in this session. Open sessions count up to `now`.
+    pub fn elapsed(&self, now: DateTime<Utc>) -> Effort {
+        let end = self.end.unwrap_or(now);
+        let seconds = (end - self.start).num_seconds().max(0) as u64;
+        Effort((seconds + 30) / 60)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.end.is_none()
+    }
+}
+
+/// Format a timestamp the way it is written to task frontmatter.
+pub(crate) fn format_timestamp(ts: &DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Serialize a timestamp with `format_timestamp`.
+pub(crate) fn serialize_timestamp<S: Serializer>(ts: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_timestamp(ts))
+}
+
+pub(crate) fn serialize_optional_timestamp<S: Serializer>(
+    ts: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match ts {
+        Some(ts) => serialize_timestamp(ts, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!("45m".parse::<Effort>().unwrap().minutes(), 45);
+        assert_eq!("2h".parse::<Effort>().unwrap().minutes(), 120);
+        assert_eq!("1h 15m".parse::<Effort>().unwrap().minutes(), 75);
+        assert_eq!("1.5h".parse::<Effort>().unwrap().minutes(), 90);
+        assert_eq!("2d".parse::<Effort>().unwrap().minutes(), 960);
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert!("".parse::<Effort>().is_err());
+        assert!("90".parse::<Effort>().is_err());
+        assert!("2x".parse::<Effort>().is_err());
+        assert!("h".parse::<Effort>().is_err());
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        for s in ["5m", "3h", "1h 5m"] {
+            assert_eq!(s.parse::<Effort>().unwrap().to_string(), s);
+        }
+        assert_eq!(Effort::from_minutes(90).to_compact(), "1h30m");
+    }
+
+    #[test]
+    fn test_session_elapsed() {
+        let closed = Session { start: at(9, 0), end: Some(at(10, 30)) };
+        assert_eq!(closed.elapsed(at(12, 0)).minutes(), 90);
+
+        let open = Session { start: at(11, 0), end: None };
+        assert!(open.is_open());
+        assert_eq!(open.elapsed(at(11, 20)).minutes(), 20);
+    }
+}
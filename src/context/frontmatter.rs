@@ -0,0 +1,606 @@
+//! Line-anchored frontmatter splitting for task files.
+//!
+//! A task file is an optional preamble, an opening delimiter line, the
+//! frontmatter, a matching closing delimiter line, and the markdown body.
+//! Delimiters only count when they are alone on a line and start it, so `---`
+//! inside a title, an indented one in a block scalar, or a horizontal rule in
+//! the body never ends the frontmatter.
+//!
+//! `---` delimits YAML frontmatter and `+++` delimits TOML. CRLF line endings
+//! and a leading byte order mark are accepted.
+//!
+//! Writing goes the other way: a task is serialized with serde, and if it was
+//! parsed from a file the result is merged into the original text so that
+//! unchanged keys, comments and line endings are left alone.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+use super::task::{parse, ParseError, Task};
+
+/// Syntax of a task's frontmatter block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FrontmatterFormat {
+    #[default]
+    Yaml,
+    Toml,
+}
+
+impl FrontmatterFormat {
+    /// The delimiter line that opens and closes this format.
+    pub fn delimiter(self) -> &'static str {
+        match self {
+            FrontmatterFormat::Yaml => "---",
+            FrontmatterFormat::Toml => "+++",
+        }
+    }
+
+    /// The format a delimiter line opens. The delimiter must start the line:
+    /// an indented `---` is content, e.g. inside a YAML block scalar.
+    fn from_delimiter(line: &str) -> Option<Self> {
+        match line.trim_end() {
+            "---" => Some(FrontmatterFormat::Yaml),
+            "+++" => Some(FrontmatterFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// A task file split into its frontmatter and body.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Split {
+    pub format: FrontmatterFormat,
+    /// Frontmatter text with line endings normalized to `\n`
+    pub frontmatter: String,
+    /// 1-based line number of the first frontmatter line in the original file
+    pub first_line: usize,
+    /// Body text after the closing delimiter, trimmed
+    pub body: String,
+    /// 0-based line index of the opening delimiter
+    pub open: usize,
+    /// 0-based line index of the closing delimiter
+    pub close: usize,
+}
+
+/// Split task file content at its frontmatter delimiters.
+pub(crate) fn split(content: &str) -> Result<Split, ParseError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let lines: Vec<&str> = content
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+
+    let (open, format) = lines
+        .iter()
+        .enumerate()
+        .find_map(|(i, line)| FrontmatterFormat::from_delimiter(line).map(|f| (i, f)))
+        .ok_or(ParseError::MissingFrontmatter)?;
+
+    let close = lines[open + 1..]
+        .iter()
+        .position(|line| line.trim_end() == format.delimiter())
+        .map(|offset| open + 1 + offset)
+        .ok_or(ParseError::UnclosedFrontmatter { line: open + 1 })?;
+
+    Ok(Split {
+        format,
+        frontmatter: lines[open + 1..close].join("\n"),
+        first_line: open + 2,
+        body: lines[close + 1..].join("\n").trim().to_string(),
+        open,
+        close,
+    })
+}
+
+/// The text a task was parsed from, kept so it can be written back faithfully.
+///
+/// Always compares equal: two tasks with the same fields are the same task
+/// regardless of how their files are formatted.
+#[derive(Clone, Default)]
+pub struct SourceText(Option<Arc<Source>>);
+
+#[derive(Debug)]
+enum Source {
+    Recorded(Recorded),
+    /// Not read yet: the task came from the load cache, and its file is only
+    /// read if the task is written back.
+    File(PathBuf),
+}
+
+#[derive(Debug)]
+struct Recorded {
+    content: String,
+    format: FrontmatterFormat,
+    open: usize,
+    close: usize,
+    /// The task's fields as serialized right after parsing
+    fields: Value,
+    description: String,
+}
+
+impl SourceText {
+    pub(crate) fn record(content: &str, split: &Split, task: &Task) -> Self {
+        let Ok(fields) = serde_yaml::to_value(task) else {
+            return Self::default();
+        };
+        Self(Some(Arc::new(Source::Recorded(Recorded {
+            content: content.to_string(),
+            format: split.format,
+            open: split.open,
+            close: split.close,
+            fields,
+            description: split.body.clone(),
+        }))))
+    }
+
+    /// Source text that is read from `path` when first needed.
+    pub(crate) fn file(path: &Path) -> Self {
+        Self(Some(Arc::new(Source::File(path.to_path_buf()))))
+    }
+
+    /// The recorded text, reading it from disk first if it was deferred. A file
+    /// that can no longer be read or parsed is treated as having no source.
+    fn recorded(&self) -> Option<Arc<Source>> {
+        match self.0.as_deref()? {
+            Source::Recorded(_) => self.0.clone(),
+            Source::File(path) => {
+                let content = std::fs::read_to_string(path).ok()?;
+                parse(&content).ok()?.source.0
+            }
+        }
+    }
+}
+
+impl PartialEq for SourceText {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for SourceText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.as_deref() {
+            Some(Source::Recorded(recorded)) => write!(f, "SourceText({} bytes)", recorded.content.len()),
+            Some(Source::File(path)) => write!(f, "SourceText({})", path.display()),
+            None => f.write_str("SourceText(None)"),
+        }
+    }
+}
+
+/// Serialize a task to file content. See `Task::to_markdown`.
+pub(crate) fn render(task: &Task) -> String {
+    let fields = match serde_yaml::to_value(task) {
+        Ok(Value::Mapping(fields)) => fields,
+        _ => Mapping::new(),
+    };
+
+    match task.source.recorded().as_deref() {
+        Some(Source::Recorded(recorded)) if recorded.format == task.frontmatter => {
+            let unchanged = recorded.fields.as_mapping() == Some(&fields);
+            if unchanged && recorded.description == task.description {
+                return recorded.content.clone();
+            }
+            merge(recorded, task, &fields, unchanged)
+        }
+        _ => {
+            let (delimiter, frontmatter) = canonical_frontmatter(task, &fields);
+            let mut content = format!("{}\n", delimiter);
+            for line in frontmatter {
+                content.push_str(&line);
+                content.push('\n');
+            }
+            content.push_str(delimiter);
+            content.push_str("\n\n");
+            if !task.description.is_empty() {
+                content.push_str(&task.description);
+                content.push('\n');
+            }
+            content
+        }
+    }
+}
+
+/// Frontmatter lines for a task with no source to preserve. TOML falls back to
+/// YAML if the task has no TOML representation.
+fn canonical_frontmatter(task: &Task, fields: &Mapping) -> (&'static str, Vec<String>) {
+    if task.frontmatter == FrontmatterFormat::Toml
+        && let Ok(toml) = toml::to_string(task)
+    {
+        return ("+++", toml.lines().map(str::to_string).collect());
+    }
+    let mut lines = Vec::new();
+    for (key, value) in fields {
+        emit_entry(&scalar(key), value, 0, &mut lines);
+    }
+    ("---", lines)
+}
+
+/// Rewrite a recorded file for a task that has changed since it was parsed.
+fn merge(recorded: &Recorded, task: &Task, fields: &Mapping, frontmatter_unchanged: bool) -> String {
+    let newline = if recorded.content.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines: Vec<&str> = recorded
+        .content
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    let original = &lines[recorded.open + 1..recorded.close];
+
+    let mut out: Vec<String> = lines[..=recorded.open].iter().map(|l| l.to_string()).collect();
+    if frontmatter_unchanged {
+        out.extend(original.iter().map(|l| l.to_string()));
+    } else if recorded.format == FrontmatterFormat::Toml {
+        // TOML is regenerated as a whole; comments in it are not kept
+        out.extend(canonical_frontmatter(task, fields).1);
+    } else {
+        let empty = Mapping::new();
+        let before = recorded.fields.as_mapping().unwrap_or(&empty);
+        out.extend(merge_yaml(original, before, fields));
+    }
+    out.push(lines[recorded.close].to_string());
+
+    if recorded.description == task.description {
+        out.extend(lines[recorded.close + 1..].iter().map(|l| l.to_string()));
+    } else {
+        out.push(String::new());
+        if !task.description.is_empty() {
+            out.extend(task.description.split('\n').map(str::to_string));
+        }
+        out.push(String::new());
+    }
+
+    out.join(newline)
+}
+
+/// A top-level key of YAML frontmatter and the lines it spans.
+struct Entry<'a> {
+    key: Option<String>,
+    /// Blank and comment lines immediately above the key
+    leading: Vec<&'a str>,
+    lines: Vec<String>,
+}
+
+/// Update YAML frontmatter lines from `before` to `after`.
+///
+/// Keys whose value is unchanged keep their original lines, changed keys are
+/// re-emitted in place, removed keys are dropped, and new keys are inserted
+/// after the last-placed key that precedes them in serialization order. Comments
+/// above a key are always kept; comments inside a changed value are not.
+fn merge_yaml(original: &[&str], before: &Mapping, after: &Mapping) -> Vec<String> {
+    let (mut entries, trailing) = split_entries(original);
+
+    for entry in &mut entries {
+        let Some(key) = &entry.key else { continue };
+        let key_value = Value::String(key.clone());
+        let old = before.get(&key_value);
+        let new = after.get(&key_value);
+        if old == new {
+            continue;
+        }
+        entry.lines.clear();
+        if let Some(new) = new {
+            emit_entry(&scalar(&key_value), new, 0, &mut entry.lines);
+        }
+    }
+
+    let mut cursor: Option<usize> = None;
+    for (key, value) in after {
+        let Some(key) = key.as_str() else { continue };
+        if let Some(index) = entries.iter().position(|e| e.key.as_deref() == Some(key)) {
+            cursor = cursor.max(Some(index));
+            continue;
+        }
+        let mut lines = Vec::new();
+        emit_entry(&scalar(&Value::String(key.to_string())), value, 0, &mut lines);
+        let index = cursor.map_or(0, |i| i + 1);
+        entries.insert(index, Entry { key: Some(key.to_string()), leading: vec![], lines });
+        cursor = Some(index);
+    }
+
+    let mut out = Vec::new();
+    for entry in entries {
+        out.extend(entry.leading.iter().map(|l| l.to_string()));
+        out.extend(entry.lines);
+    }
+    out.extend(trailing.iter().map(|l| l.to_string()));
+    out
+}
+
+/// Group frontmatter lines by top-level key. Returns the entries and any
+/// blank or comment lines after the last one.
+fn split_entries<'a>(lines: &[&'a str]) -> (Vec<Entry<'a>>, Vec<&'a str>) {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut pending: Vec<&str> = Vec::new();
+
+    for &line in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || line.starts_with('#') {
+            pending.push(line);
+            continue;
+        }
+        let continuation = line.starts_with(char::is_whitespace) || line.starts_with('-');
+        match (continuation, entries.last_mut()) {
+            (true, Some(entry)) => {
+                entry.lines.extend(pending.drain(..).map(str::to_string));
+                entry.lines.push(line.to_string());
+            }
+            _ => entries.push(Entry {
+                key: top_level_key(line),
+                leading: std::mem::take(&mut pending),
+                lines: vec![line.to_string()],
+            }),
+        }
+    }
+
+    (entries, pending)
+}
+
+/// The key of a `key: value` line, unquoted.
+fn top_level_key(line: &str) -> Option<String> {
+    let key = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = line[1..].find(quote)?;
+            return Some(line[1..1 + end].to_string());
+        }
+        _ => line.split(':').next()?,
+    };
+    Some(key.trim().to_string())
+}
+
+/// Append the block-style YAML lines for `key: value` at `indent`.
+fn emit_entry(key: &str, value: &Value, indent: usize, out: &mut Vec<String>) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Sequence(items) if !items.is_empty() => {
+            out.push(format!("{}{}:", pad, key));
+            for item in items {
+                emit_item(item, indent + 2, out);
+            }
+        }
+        Value::Mapping(map) if !map.is_empty() => {
+            out.push(format!("{}{}:", pad, key));
+            for (k, v) in map {
+                emit_entry(&scalar(k), v, indent + 2, out);
+            }
+        }
+        _ => out.push(format!("{}{}: {}", pad, key, scalar(value))),
+    }
+}
+
+/// Append a `- item` sequence entry at `indent`.
+fn emit_item(item: &Value, indent: usize, out: &mut Vec<String>) {
+    let pad = " ".repeat(indent);
+    match item {
+        Value::Mapping(map) if !map.is_empty() => {
+            // First key shares the dash line, the rest line up under it
+            let first = out.len();
+            for (k, v) in map {
+                emit_entry(&scalar(k), v, indent + 2, out);
+            }
+            out[first] = format!("{}- {}", pad, &out[first][indent + 2..]);
+        }
+        Value::Sequence(items) if !items.is_empty() => {
+            out.push(format!("{}-", pad));
+            for nested in items {
+                emit_item(nested, indent + 2, out);
+            }
+        }
+        _ => out.push(format!("{}- {}", pad, scalar(item))),
+    }
+}
+
+/// Render a scalar (or empty collection) as inline YAML.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => yaml_string(s),
+        Value::Sequence(_) => "[]".to_string(),
+        Value::Mapping(_) => "{}".to_string(),
+        Value::Tagged(tagged) => format!("{} {}", tagged.tag, scalar(&tagged.value)),
+    }
+}
+
+/// Characters that are legal in plain scalars but read as YAML syntax to
+/// people, so strings containing them are quoted anyway.
+const QUOTE_CHARS: &[char] = &['"', '\'', '[', ']', '{', '}', '&', '*', '!', '|', '>', '%', '@', '`', '\\'];
+
+/// Render a string plainly when it reads back as the same string, otherwise
+/// as a double-quoted scalar.
+fn yaml_string(s: &str) -> String {
+    let plain = !s.is_empty()
+        && s.trim() == s
+        && !s.starts_with(['-', '?', ','])
+        && !s.contains(QUOTE_CHARS)
+        && !s.contains(": ")
+        && !s.ends_with(':')
+        && !s.contains(" #")
+        && !s.starts_with('#')
+        && !s.chars().any(needs_escape)
+        && serde_yaml::from_str::<Value>(s).ok().as_ref().and_then(Value::as_str) == Some(s);
+    if plain {
+        return s.to_string();
+    }
+
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if needs_escape(c) => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Characters YAML doesn't allow unescaped in a double-quoted scalar, plus
+/// line breaks and the byte order mark.
+fn needs_escape(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{2028}' | '\u{2029}' | '\u{feff}' | '\u{fffe}' | '\u{ffff}')
+}
+
+/// Convert a YAML error into a `ParseError` located in the original file.
+pub(crate) fn yaml_error(err: serde_yaml::Error, first_line: usize) -> ParseError {
+    let location = err.location();
+    ParseError::InvalidYaml {
+        message: strip_location(&err.to_string()),
+        line: location.as_ref().map(|l| first_line + l.line() - 1),
+        column: location.as_ref().map(|l| l.column()),
+    }
+}
+
+/// Parse TOML frontmatter into a YAML value, so tasks deserialize the same way
+/// regardless of format. TOML dates and datetimes become strings.
+pub(crate) fn toml_to_yaml(source: &str, first_line: usize) -> Result<serde_yaml::Value, ParseError> {
+    let table: toml::Table = toml::from_str(source).map_err(|err| {
+        let position = err.span().map(|span| line_column(source, span.start));
+        ParseError::InvalidToml {
+            message: err.message().to_string(),
+            line: position.map(|(line, _)| first_line + line - 1),
+            column: position.map(|(_, column)| column),
+        }
+    })?;
+    Ok(convert_toml(toml::Value::Table(table)))
+}
+
+fn convert_toml(value: toml::Value) -> serde_yaml::Value {
+    use serde_yaml::Value;
+
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => Value::Number(f.into()),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => Value::Sequence(items.into_iter().map(convert_toml).collect()),
+        toml::Value::Table(table) => Value::Mapping(
+            table
+                .into_iter()
+                .map(|(k, v)| (Value::String(k), convert_toml(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// 1-based line and column of a byte offset.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+/// serde_yaml appends "at line X column Y" relative to the frontmatter; drop
+/// it since we report the location relative to the file instead.
+fn strip_location(message: &str) -> String {
+    match message.find(" at line ") {
+        Some(idx) => {
+            let (head, tail) = message.split_at(idx);
+            // Keep any context after the location, e.g. ", while parsing ..."
+            let rest = tail.find(',').map_or("", |i| &tail[i..]);
+            format!("{}{}", head, rest)
+        }
+        None => message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_ignores_inline_and_body_delimiters() {
+        let content = "---\ntitle: before --- after\n---\n\nIntro\n\n---\n\nMore";
+        let split = split(content).unwrap();
+        assert_eq!(split.frontmatter, "title: before --- after");
+        assert_eq!(split.body, "Intro\n\n---\n\nMore");
+    }
+
+    #[test]
+    fn test_split_ignores_indented_delimiters() {
+        let split_yaml = split("---\ntitle: |\n  a\n  ---\n  b\n---\nBody").unwrap();
+        assert_eq!(split_yaml.frontmatter, "title: |\n  a\n  ---\n  b");
+        assert_eq!(split_yaml.body, "Body");
+        let task = parse("---\nid: x\ntitle: |\n  a\n  ---\n  b\n---\n").unwrap();
+        assert_eq!(task.title.as_deref(), Some("a\n---\nb"));
+
+        let split_toml = split("+++\ntitle = \"\"\"\n  a\n  +++\n  b\"\"\"\n+++ \nBody").unwrap();
+        assert_eq!(split_toml.frontmatter, "title = \"\"\"\n  a\n  +++\n  b\"\"\"");
+        assert_eq!(split_toml.body, "Body");
+    }
+
+    #[test]
+    fn test_split_handles_crlf_and_bom() {
+        let content = "\u{feff}---\r\nid: win\r\n---\r\n\r\nBody\r\n";
+        let split = split(content).unwrap();
+        assert_eq!(split.format, FrontmatterFormat::Yaml);
+        assert_eq!(split.frontmatter, "id: win");
+        assert_eq!(split.body, "Body");
+    }
+
+    #[test]
+    fn test_render_changed_task_keeps_comments_and_order() {
+        let content = "---\n# Planning notes\ntitle: Ship it  # short\nid: ship\nafter:\n  - a  # first\n  - b\n---\n\nBody\n";
+        let mut task = crate::parse(content).unwrap();
+        assert_eq!(task.to_markdown(), content);
+
+        task.status = Some(crate::Status::InProgress);
+        task.after.retain(|id| id != "b");
+        assert_eq!(
+            task.to_markdown(),
+            "---\n# Planning notes\ntitle: Ship it  # short\nid: ship\nstatus: inprogress\nafter:\n  - a\n---\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_render_changed_task_keeps_crlf() {
+        let content = "\u{feff}---\r\nid: win\r\n---\r\n\r\nOld body\r\n";
+        let mut task = crate::parse(content).unwrap();
+        task.description = "New body".to_string();
+        assert_eq!(task.to_markdown(), "\u{feff}---\r\nid: win\r\n---\r\n\r\nNew body\r\n");
+    }
+
+    #[test]
+    fn test_yaml_string_quotes_only_when_needed() {
+        assert_eq!(yaml_string("plain words"), "plain words");
+        assert_eq!(yaml_string("café ☕"), "café ☕");
+        assert_eq!(yaml_string("2025-01-01T09:00:00Z"), "2025-01-01T09:00:00Z");
+        assert_eq!(yaml_string("Fix: it"), "\"Fix: it\"");
+        assert_eq!(yaml_string("123"), "\"123\"");
+        assert_eq!(yaml_string("true"), "\"true\"");
+        assert_eq!(yaml_string("~"), "\"~\"");
+        assert_eq!(yaml_string(""), "\"\"");
+        assert_eq!(yaml_string("two\nlines"), "\"two\\nlines\"");
+        assert_eq!(yaml_string("bell\u{7}"), "\"bell\\u0007\"");
+    }
+
+    #[test]
+    fn test_split_unclosed_reports_opening_line() {
+        let err = split("notes\n---\nid: x\n").unwrap_err();
+        assert!(matches!(err, ParseError::UnclosedFrontmatter { line: 2 }));
+    }
+
+    #[test]
+    fn test_toml_error_location() {
+        let err = toml_to_yaml("id = \"x\"\ntitle = \n", 2).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidToml { line: Some(3), .. }));
+    }
+
+    #[test]
+    fn test_strip_location() {
+        assert_eq!(
+            strip_location("did not find expected node content at line 3 column 1, while parsing a flow node"),
+            "did not find expected node content, while parsing a flow node"
+        );
+        assert_eq!(strip_location("unknown field `x`"), "unknown field `x`");
+    }
+}
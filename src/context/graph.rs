@@ -1,8 +1,13 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
 
-use super::task::{ParseError, Task};
-use super::validations::{validate_view, ValidationError};
+use chrono::NaiveDate;
+
+use super::effort::Effort;
+use super::task::{GateStatus, ParseError, Status, Task};
+use super::transaction::Op;
+use super::validations::{validate_all, EdgeKind, ValidationError};
+use super::view::{GraphView, ValidationView};
 
 /// Error collecting multiple issues found when reading a task graph.
 ///
@@ -64,13 +69,77 @@ impl std::fmt::Display for GraphReadError {
 
 impl std::error::Error for GraphReadError {}
 
+/// Index of the references between tasks.
+///
+/// The forward direction of every edge is the `before`, `after` and `gates`
+/// lists on the task itself; the index keeps the reverse direction, so finding
+/// the tasks that point at a given one doesn't take a scan of the graph.
+/// Entries are keyed by the reference as written. The uids and aliases tasks
+/// are known by are indexed too, so references using them resolve without a
+/// scan.
+#[derive(Debug, Clone, Default)]
+struct EdgeIndex {
+    /// id -> tasks listing it under `before` (its subtasks)
+    subtasks: HashMap<String, BTreeSet<String>>,
+    /// id -> tasks listing it under `after` (its dependents)
+    dependents: HashMap<String, BTreeSet<String>>,
+    /// id -> tasks listing it under `gates`
+    gated: HashMap<String, BTreeSet<String>>,
+    /// uid or alias -> tasks known by it
+    keys: HashMap<String, BTreeSet<String>>,
+}
+
+impl EdgeIndex {
+    fn build<'a>(tasks: impl Iterator<Item = &'a Task>) -> Self {
+        let mut index = Self::default();
+        for task in tasks {
+            index.add(task);
+        }
+        index
+    }
+
+    fn add(&mut self, task: &Task) {
+        let link = |map: &mut HashMap<String, BTreeSet<String>>, target: &str| {
+            map.entry(target.to_string()).or_default().insert(task.id.clone());
+        };
+        task.before.iter().for_each(|id| link(&mut self.subtasks, id));
+        task.after.iter().for_each(|id| link(&mut self.dependents, id));
+        task.gate_ids().for_each(|id| link(&mut self.gated, id));
+        task.uid.iter().chain(&task.aliases).for_each(|key| link(&mut self.keys, key));
+    }
+
+    fn remove(&mut self, task: &Task) {
+        let unlink = |map: &mut HashMap<String, BTreeSet<String>>, target: &str| {
+            if let Some(sources) = map.get_mut(target) {
+                sources.remove(&task.id);
+                if sources.is_empty() {
+                    map.remove(target);
+                }
+            }
+        };
+        task.before.iter().for_each(|id| unlink(&mut self.subtasks, id));
+        task.after.iter().for_each(|id| unlink(&mut self.dependents, id));
+        task.gate_ids().for_each(|id| unlink(&mut self.gated, id));
+        task.uid.iter().chain(&task.aliases).for_each(|key| unlink(&mut self.keys, key));
+    }
+}
+
 /// A graph of tasks with their dependencies.
 ///
-/// Tracks which tasks have been modified ("dirty") since the last save.
+/// Tracks which tasks have been modified ("dirty") since the last save, and
+/// keeps an index of the references between tasks up to date as they change.
 #[derive(Debug, Clone, Default)]
 pub struct TaskGraph {
     tasks: HashMap<String, Task>,
     dirty: HashSet<String>,
+    index: EdgeIndex,
+    /// Whether cancelled tasks keep blocking their dependents
+    cancelled_blocks_dependents: bool,
+    /// Ids of tasks moved to `.tasks/archive`
+    archived: HashSet<String>,
+    /// Tasks whose references `canonicalize_references` rewrote, so their
+    /// files may still use a uid or alias
+    keyed: HashSet<String>,
 }
 
 impl TaskGraph {
@@ -78,12 +147,66 @@ impl TaskGraph {
         Self {
             tasks: HashMap::new(),
             dirty: HashSet::new(),
+            cancelled_blocks_dependents: false,
+            archived: HashSet::new(),
+            index: EdgeIndex::default(),
+            keyed: HashSet::new(),
         }
     }
 
+    /// Record the ids of archived tasks, which count as satisfied references.
+    pub fn set_archived(&mut self, ids: HashSet<String>) {
+        self.archived = ids;
+    }
+
+    /// Whether `id` names an archived task.
+    pub fn is_archived(&self, id: &str) -> bool {
+        self.archived.contains(id)
+    }
+
+    /// Move a task out of the active graph into the archive.
+    ///
+    /// Like `remove`, the task is soft-deleted so its file gets removed on
+    /// save, but references to it are left alone.
+    pub fn archive(&mut self, id: &str) -> bool {
+        let removed = self.remove(id);
+        if removed {
+            self.archived.insert(id.to_string());
+        }
+        removed
+    }
+
+    /// Set whether cancelled tasks keep blocking their dependents.
+    pub fn set_cancelled_blocks_dependents(&mut self, blocks: bool) {
+        self.cancelled_blocks_dependents = blocks;
+    }
+
+    /// Whether cancelled tasks keep blocking their dependents.
+    pub fn cancelled_blocks_dependents(&self) -> bool {
+        self.cancelled_blocks_dependents
+    }
+
+    /// Returns true if `task` no longer holds up tasks that depend on it.
+    ///
+    /// Complete tasks always satisfy their dependents; cancelled tasks do
+    /// unless the graph is configured to treat them as blocking.
+    pub fn satisfies_dependents(&self, task: &Task) -> bool {
+        task.is_complete() || (task.is_cancelled() && !self.cancelled_blocks_dependents)
+    }
+
     /// Insert a task, marking it as dirty.
     pub fn insert(&mut self, task: Task) {
         self.dirty.insert(task.id.clone());
+        self.keyed.remove(&task.id);
+        self.replace(task);
+    }
+
+    /// Insert or replace a task, keeping the edge index in step.
+    fn replace(&mut self, task: Task) {
+        if let Some(old) = self.tasks.get(&task.id) {
+            self.index.remove(old);
+        }
+        self.index.add(&task);
         self.tasks.insert(task.id.clone(), task);
     }
 
@@ -91,16 +214,74 @@ impl TaskGraph {
         self.tasks.get(id)
     }
 
-    /// Get a mutable reference to a task, marking it as dirty.
-    pub fn get_mut(&mut self, id: &str) -> Option<&mut Task> {
-        if self.tasks.contains_key(id) {
-            self.dirty.insert(id.to_string());
+    pub fn contains(&self, id: &str) -> bool {
+        self.tasks.contains_key(id)
+    }
+
+    /// Find the task a reference names: by id, then by uid or alias.
+    pub fn resolve(&self, key: &str) -> Option<&Task> {
+        self.tasks.get(key).or_else(|| self.known_by(key).next())
+    }
+
+    /// Tasks that have `key` as their uid or one of their aliases.
+    pub(crate) fn known_by(&self, key: &str) -> impl Iterator<Item = &Task> {
+        self.sources(&self.index.keys, key)
+    }
+
+    /// Rewrite `before`, `after` and `gates` references that use a uid or an
+    /// alias to the referenced task's current id.
+    ///
+    /// Only the in-memory graph changes: tasks aren't marked dirty, so files
+    /// keep whatever key they were written with until they're next saved.
+    /// The rewritten tasks are remembered (see `refers_by_key`).
+    pub fn canonicalize_references(&mut self) {
+        // Only tasks referencing a uid or alias that isn't also a live id need
+        // rewriting; the index finds them without visiting every task
+        let mut canonical: HashMap<String, String> = HashMap::new();
+        let mut sources: BTreeSet<&String> = BTreeSet::new();
+        for key in self.index.keys.keys() {
+            if self.tasks.get(key).is_some_and(|t| !t.is_deleted()) {
+                continue;
+            }
+            let Some(owner) = self.known_by(key).find(|t| !t.is_deleted()) else {
+                continue;
+            };
+            let referencing: Vec<&String> = [&self.index.subtasks, &self.index.dependents, &self.index.gated]
+                .into_iter()
+                .flat_map(|map| map.get(key).into_iter().flatten())
+                .collect();
+            if !referencing.is_empty() {
+                canonical.insert(key.clone(), owner.id.clone());
+                sources.extend(referencing);
+            }
+        }
+
+        let rewrite = |key: &mut String| {
+            if let Some(id) = canonical.get(key.as_str()) {
+                *key = id.clone();
+            }
+        };
+        let updates: Vec<Task> = sources
+            .into_iter()
+            .filter_map(|id| self.tasks.get(id))
+            .map(|task| {
+                let mut updated = task.clone();
+                updated.before.iter_mut().for_each(rewrite);
+                updated.after.iter_mut().for_each(rewrite);
+                updated.gates.iter_mut().for_each(|g| rewrite(&mut g.id));
+                updated
+            })
+            .collect();
+        for task in updates {
+            self.keyed.insert(task.id.clone());
+            self.replace(task);
         }
-        self.tasks.get_mut(id)
     }
 
-    pub fn contains(&self, id: &str) -> bool {
-        self.tasks.contains_key(id)
+    /// Whether `id`'s file may refer to other tasks by uid or alias rather
+    /// than by id.
+    pub(crate) fn refers_by_key(&self, id: &str) -> bool {
+        self.keyed.contains(id)
     }
 
     /// Mark a task as deleted (soft-delete).
@@ -141,7 +322,31 @@ impl TaskGraph {
     where
         F: FnMut(&String, &mut Task) -> bool,
     {
-        self.tasks.retain(f)
+        self.tasks.retain(f);
+        self.index = EdgeIndex::build(self.tasks.values());
+    }
+
+    /// Tasks that list `id` under `before`: its subtasks.
+    pub fn subtasks(&self, id: &str) -> impl Iterator<Item = &Task> {
+        self.sources(&self.index.subtasks, id)
+    }
+
+    /// Tasks that list `id` under `after`: the tasks waiting on it.
+    pub fn dependents(&self, id: &str) -> impl Iterator<Item = &Task> {
+        self.sources(&self.index.dependents, id)
+    }
+
+    /// Tasks that reference `id` under `before`, `after` or `gates`, in id order.
+    pub fn referencing(&self, id: &str) -> Vec<&Task> {
+        let mut ids: BTreeSet<&String> = BTreeSet::new();
+        for map in [&self.index.subtasks, &self.index.dependents, &self.index.gated] {
+            ids.extend(map.get(id).into_iter().flatten());
+        }
+        ids.into_iter().filter_map(|id| self.tasks.get(id)).collect()
+    }
+
+    fn sources<'a>(&'a self, map: &'a HashMap<String, BTreeSet<String>>, id: &str) -> impl Iterator<Item = &'a Task> {
+        map.get(id).into_iter().flatten().filter_map(|source| self.tasks.get(source))
     }
 
     // ---- Dirty tracking methods ----
@@ -180,20 +385,18 @@ impl TaskGraph {
     /// since their files have been removed.
     pub fn clear_dirty(&mut self) {
         self.dirty.clear();
+        for task in self.tasks.values().filter(|t| t.deleted) {
+            self.index.remove(task);
+        }
         self.tasks.retain(|_, task| !task.deleted);
     }
 
     // ---- Graph Algorithm Methods ----
 
-    /// Computes the transitive reduction of dependency edges.
-    /// Returns a map from task_id to its effective successors after removing redundant edges.
-    ///
-    /// For example, if A → B → C and A → C, the edge A → C is redundant.
-    /// After reduction, A's effective successors are just [B] (not [B, C]).
-    pub fn transitive_reduction(&self) -> HashMap<&str, Vec<&str>> {
-        let task_ids: HashSet<&str> = self.tasks.keys().map(|s| s.as_str()).collect();
-
-        // Build dependency edges: from task to tasks that depend on it
+    /// Dependency edges: from each task to the tasks that depend on it (its
+    /// `before` targets, and the tasks listing it under `after`). Every task
+    /// has an entry, even without edges.
+    fn dependency_edges(&self) -> HashMap<&str, HashSet<&str>> {
         let mut edges: HashMap<&str, HashSet<&str>> = HashMap::new();
 
         for task in self.tasks.values() {
@@ -201,7 +404,7 @@ impl TaskGraph {
 
             // Before relationship: before targets depend on this task
             for before_id in &task.before {
-                if task_ids.contains(before_id.as_str()) {
+                if self.tasks.contains_key(before_id) {
                     edges
                         .entry(task.id.as_str())
                         .or_default()
@@ -211,7 +414,7 @@ impl TaskGraph {
 
             // After relationship: this task depends on after dependency
             for after_id in &task.after {
-                if task_ids.contains(after_id.as_str()) {
+                if self.tasks.contains_key(after_id) {
                     edges
                         .entry(after_id.as_str())
                         .or_default()
@@ -220,6 +423,18 @@ impl TaskGraph {
             }
         }
 
+        edges
+    }
+
+    /// Computes the transitive reduction of dependency edges.
+    /// Returns a map from task_id to its effective successors after removing redundant edges.
+    ///
+    /// For example, if A → B → C and A → C, the edge A → C is redundant.
+    /// After reduction, A's effective successors are just [B] (not [B, C]).
+    pub fn transitive_reduction(&self) -> HashMap<&str, Vec<&str>> {
+        let task_ids: HashSet<&str> = self.tasks.keys().map(|s| s.as_str()).collect();
+        let edges = self.dependency_edges();
+
         // Compute reachability for each node
         let mut reachable: HashMap<&str, HashSet<&str>> = HashMap::new();
 
@@ -279,13 +494,16 @@ impl TaskGraph {
 
     /// Returns task IDs in topological order.
     ///
-    /// Uses Kahn's algorithm with the transitive reduction of the graph.
+    /// Uses Kahn's algorithm on the direct dependency edges. A redundant edge
+    /// never decides when a task becomes ready (the path that makes it
+    /// redundant finishes later), so the order is the same as over the
+    /// transitive reduction, without the cost of computing it.
     pub fn topological_order(&self) -> Vec<&str> {
         if self.tasks.is_empty() {
             return Vec::new();
         }
 
-        let effective_successors = self.transitive_reduction();
+        let effective_successors = self.dependency_edges();
 
         let mut in_degree: HashMap<&str, usize> = HashMap::new();
         for id in self.tasks.keys() {
@@ -412,23 +630,6 @@ impl TaskGraph {
             .map(|s| s.to_string())
             .collect();
 
-        // Build reverse index: task_id -> tasks that reference it in before/after
-        let mut referenced_by: HashMap<String, Vec<String>> = HashMap::new();
-        for task in self.tasks.values() {
-            for before_id in &task.before {
-                referenced_by
-                    .entry(before_id.clone())
-                    .or_default()
-                    .push(task.id.clone());
-            }
-            for after_id in &task.after {
-                referenced_by
-                    .entry(after_id.clone())
-                    .or_default()
-                    .push(task.id.clone());
-            }
-        }
-
         while let Some(id) = stack.pop() {
             if visited.contains(&id) {
                 continue;
@@ -452,11 +653,9 @@ impl TaskGraph {
             }
 
             // Follow reverse references (tasks that point to this one)
-            if let Some(refs) = referenced_by.get(&id) {
-                for ref_id in refs {
-                    if !visited.contains(ref_id) {
-                        stack.push(ref_id.clone());
-                    }
+            for task in self.subtasks(&id).chain(self.dependents(&id)) {
+                if !visited.contains(&task.id) {
+                    stack.push(task.id.clone());
                 }
             }
         }
@@ -465,6 +664,29 @@ impl TaskGraph {
         result.sort();
         result
     }
+
+    /// Returns the seed IDs and every task they depend on, directly or
+    /// transitively: their `after` dependencies and their subtasks (tasks
+    /// listing them under `before`). Tasks that depend on a seed aren't
+    /// included.
+    pub fn upstream(&self, seeds: &[&str]) -> Vec<String> {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut stack: Vec<&str> = seeds.iter().copied().filter(|id| self.tasks.contains_key(*id)).collect();
+
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id.to_string()) {
+                continue;
+            }
+            if let Some(task) = self.tasks.get(id) {
+                stack.extend(task.after.iter().map(String::as_str).filter(|id| self.tasks.contains_key(*id)));
+            }
+            stack.extend(self.subtasks(id).map(|t| t.id.as_str()));
+        }
+
+        let mut result: Vec<String> = visited.into_iter().collect();
+        result.sort();
+        result
+    }
 }
 
 impl PartialEq for TaskGraph {
@@ -476,14 +698,15 @@ impl PartialEq for TaskGraph {
 
 impl FromIterator<Task> for TaskGraph {
     fn from_iter<I: IntoIterator<Item = Task>>(iter: I) -> Self {
-        let tasks = iter.into_iter().map(|t| (t.id.clone(), t)).collect();
-        Self { tasks, dirty: HashSet::new() }
+        iter.into_iter().map(|t| (t.id.clone(), t)).collect()
     }
 }
 
 impl FromIterator<(String, Task)> for TaskGraph {
     fn from_iter<I: IntoIterator<Item = (String, Task)>>(iter: I) -> Self {
-        Self { tasks: iter.into_iter().collect(), dirty: HashSet::new() }
+        let tasks: HashMap<String, Task> = iter.into_iter().collect();
+        let index = EdgeIndex::build(tasks.values());
+        Self { tasks, index, ..Self::default() }
     }
 }
 
@@ -495,44 +718,82 @@ impl FromIterator<(String, Task)> for TaskGraph {
 /// Returns tasks that are available to work on (all dependencies satisfied).
 ///
 /// A task is available if:
-/// - It is not complete
-/// - It is not a gate
-/// - All after dependencies are complete
+/// - It is not complete or cancelled
+/// - It is not a gate or an epic
+/// - It is not blocked
+/// - All after dependencies are complete (or cancelled, unless configured otherwise)
 /// - All subtasks are complete (tasks that have this task as before target)
 pub fn available_tasks(graph: &TaskGraph) -> Vec<&Task> {
     graph
         .values()
-        .filter(|task| !task.is_complete() && !task.is_gate() && is_available(task, graph))
+        .filter(|task| {
+            !task.is_closed()
+                && !task.is_gate()
+                && !task.is_epic()
+                && !task.is_blocked()
+                && is_available(task, graph)
+        })
         .collect()
 }
 
 /// Check if a specific task is available to work on.
-pub fn is_available(task: &Task, graph: &TaskGraph) -> bool {
-    // Check all after dependencies are complete
-    for after_id in &task.after {
-        if let Some(after_task) = graph.get(after_id)
-            && !after_task.is_complete()
-        {
-            return false;
-        }
-    }
+///
+/// Works on any `GraphView`, so proposed changes can be checked before
+/// they're committed.
+pub fn is_available<V: GraphView>(task: &Task, graph: &V) -> bool {
+    waiting_on(task, graph).is_empty()
+}
 
-    // Check all subtasks are complete (tasks that have this task as before target)
-    for other_task in graph.values() {
-        for before_id in &other_task.before {
-            if before_id == &task.id && !other_task.is_complete() {
-                return false;
-            }
+/// The tasks holding `task` up, in the order `is_available` checks them:
+/// `after` dependencies that don't satisfy their dependents yet, then
+/// subtasks (tasks listing it under `before`) that haven't finished.
+pub fn waiting_on<'a, V: GraphView>(task: &Task, graph: &'a V) -> Vec<(EdgeKind, &'a Task)> {
+    let mut waiting: Vec<(EdgeKind, &Task)> = task
+        .after
+        .iter()
+        .filter_map(|after_id| graph.get(after_id))
+        .filter(|after_task| !graph.satisfies_dependents(after_task))
+        .map(|after_task| (EdgeKind::After, after_task))
+        .collect();
+
+    waiting.extend(
+        graph
+            .referencing(&task.id)
+            .into_iter()
+            .filter(|t| t.before.contains(&task.id) && !graph.satisfies_dependents(t))
+            .map(|subtask| (EdgeKind::Before, subtask)),
+    );
+    waiting
+}
+
+/// Every task holding `task` up, directly or through the tasks it waits on,
+/// in id order.
+///
+/// The walk follows `waiting_on`, so it stops at closed tasks: a cancelled
+/// dependency can hold a task up, but what it was waiting on no longer does.
+pub fn waiting_on_transitively<'a>(task: &Task, graph: &'a TaskGraph) -> Vec<&'a Task> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut stack: Vec<&Task> = waiting_on(task, graph).into_iter().map(|(_, t)| t).collect();
+    let mut found = Vec::new();
+
+    while let Some(next) = stack.pop() {
+        if next.id == task.id || !seen.insert(next.id.as_str()) {
+            continue;
+        }
+        found.push(next);
+        if !next.is_closed() {
+            stack.extend(waiting_on(next, graph).into_iter().map(|(_, t)| t));
         }
     }
 
-    true
+    found.sort_by(|a, b| a.id.cmp(&b.id));
+    found
 }
 
 /// Check if a task belongs to a fully complete group.
 /// A task is in a complete group if it and all its ancestors are complete.
 pub fn is_group_complete(task: &Task, graph: &TaskGraph) -> bool {
-    if !task.is_complete() {
+    if !task.is_closed() {
         return false;
     }
 
@@ -547,25 +808,387 @@ pub fn is_group_complete(task: &Task, graph: &TaskGraph) -> bool {
     true
 }
 
+/// Progress of an epic, rolled up from its descendants.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EpicProgress {
+    /// Descendants counted toward progress (cancelled ones are left out)
+    pub total: usize,
+    pub complete: usize,
+    /// Sum of estimates of descendants still open
+    pub remaining_estimate: Effort,
+    /// Gates passed or skipped across all counted descendants
+    pub gates_passed: usize,
+    pub gates_total: usize,
+}
+
+impl EpicProgress {
+    /// Completion percentage, rounded down. An empty epic is at 0%.
+    pub fn percent(&self) -> usize {
+        (self.complete * 100).checked_div(self.total).unwrap_or(0)
+    }
+
+    /// Returns true if every counted descendant is complete.
+    pub fn is_finished(&self) -> bool {
+        self.total > 0 && self.complete == self.total
+    }
+}
+
+/// Collect every task under an epic: tasks listing it in `before`, their own
+/// children, and so on. Gates and jots are not part of an epic's work.
+pub fn epic_descendants<'a>(epic_id: &str, graph: &'a TaskGraph) -> Vec<&'a Task> {
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut stack = vec![epic_id];
+    let mut result = Vec::new();
+
+    while let Some(id) = stack.pop() {
+        for child in graph.subtasks(id) {
+            if child.id != epic_id && visited.insert(child.id.as_str()) {
+                stack.push(child.id.as_str());
+                if !child.is_gate() && !child.is_jot() {
+                    result.push(child);
+                }
+            }
+        }
+    }
+
+    result.sort_by(|a, b| a.id.cmp(&b.id));
+    result
+}
+
+/// Roll up completion, remaining estimate and gate status for an epic.
+///
+/// Nested epics count as items themselves; their own children are counted too.
+pub fn epic_progress(epic_id: &str, graph: &TaskGraph, default_gates: &[String]) -> EpicProgress {
+    let mut progress = EpicProgress::default();
+
+    for task in epic_descendants(epic_id, graph).into_iter().filter(|t| !t.is_cancelled()) {
+        progress.total += 1;
+        if task.is_complete() {
+            progress.complete += 1;
+        } else if let Some(estimate) = task.estimate {
+            progress.remaining_estimate = progress.remaining_estimate + estimate;
+        }
+
+        if task.is_epic() {
+            continue;
+        }
+        let gate_ids: HashSet<&str> = default_gates
+            .iter()
+            .map(String::as_str)
+            .chain(task.gate_ids())
+            .collect();
+        progress.gates_total += gate_ids.len();
+        progress.gates_passed += task
+            .gates
+            .iter()
+            .filter(|g| matches!(g.status, GateStatus::Passed | GateStatus::Skipped))
+            .count();
+    }
+
+    progress
+}
+
+/// Open epics whose descendants have all finished.
+///
+/// Returns IDs in sorted order. Completing one of these may finish an
+/// enclosing epic, so callers should repeat until nothing is returned.
+///
+/// Each task's subtree is summed up once, from the bottom, rather than
+/// collecting every epic's descendants separately. What counts is the same
+/// as for `epic_progress`.
+pub fn finished_epics(graph: &TaskGraph) -> Vec<String> {
+    let work = subtree_work(graph);
+    let mut ids: Vec<String> = graph
+        .values()
+        .filter(|t| t.is_epic() && !t.is_closed())
+        .filter(|t| work.get(t.id.as_str()).is_some_and(|w| w.any && w.done))
+        .map(|t| t.id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// The work beneath a task, as `epic_progress` counts it.
+#[derive(Debug, Clone, Copy)]
+struct SubtreeWork {
+    /// Some descendant counts
+    any: bool,
+    /// Every descendant that counts is complete
+    done: bool,
+}
+
+/// `SubtreeWork` for every task, each worked out once from its subtasks'.
+fn subtree_work(graph: &TaskGraph) -> HashMap<&str, SubtreeWork> {
+    let mut work: HashMap<&str, SubtreeWork> = HashMap::new();
+    let mut entered: HashSet<&str> = HashSet::new();
+
+    for root in graph.values() {
+        let mut stack = vec![(root.id.as_str(), false)];
+        while let Some((id, children_done)) = stack.pop() {
+            if work.contains_key(id) {
+                continue;
+            }
+            if !children_done {
+                // A task seen again before it's finished is on a cycle; leave it out
+                if !entered.insert(id) {
+                    continue;
+                }
+                stack.push((id, true));
+                stack.extend(graph.subtasks(id).map(|c| (c.id.as_str(), false)));
+                continue;
+            }
+
+            let mut total = SubtreeWork { any: false, done: true };
+            for child in graph.subtasks(id).filter(|c| c.id != id) {
+                let counts = !child.is_gate() && !child.is_jot() && !child.is_cancelled();
+                let below = work.get(child.id.as_str()).copied().unwrap_or(SubtreeWork { any: false, done: true });
+                total.any |= counts || below.any;
+                total.done &= (!counts || child.is_complete()) && below.done;
+            }
+            work.insert(id, total);
+        }
+    }
+    work
+}
+
+/// Compute the effective due date of every task.
+///
+/// A task's effective due date is the earliest of its own `due` and the
+/// effective due dates of every task that depends on it: tasks listing it in
+/// `after`, and the tasks in its own `before` list. Deadlines therefore flow
+/// upstream through the graph. Tasks with no deadline anywhere downstream are
+/// absent from the result.
+pub fn effective_due_dates(graph: &TaskGraph) -> HashMap<String, NaiveDate> {
+    // Dependents come after a task in topological order, so walking it
+    // backwards settles every dependent before the tasks it waits on
+    let mut due_dates: HashMap<String, NaiveDate> = HashMap::new();
+    for id in graph.topological_order().into_iter().rev() {
+        let Some(task) = graph.get(id) else { continue };
+        // A cancelled task's deadline no longer applies, to itself or upstream
+        if task.is_cancelled() {
+            continue;
+        }
+        let downstream = graph
+            .dependents(id)
+            .map(|t| t.id.as_str())
+            .chain(task.before.iter().map(String::as_str))
+            .filter_map(|dependent| due_dates.get(dependent).copied());
+        if let Some(due) = task.due.into_iter().chain(downstream).min() {
+            due_dates.insert(task.id.clone(), due);
+        }
+    }
+    due_dates
+}
+
+/// The longest chain of open work in the graph; see `critical_path`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CriticalPath {
+    /// Task ids in the order they have to be done
+    pub tasks: Vec<String>,
+    /// Sum of the estimates along the path
+    pub estimate: Effort,
+    /// Tasks on the path with no estimate
+    pub unestimated: usize,
+}
+
+/// Find the longest chain of open tasks leading to `target`, or to any task
+/// when there's no target.
+///
+/// `target` may be an id, uid or alias. Chains are compared by total estimate
+/// first and by number of tasks second, so a fully unestimated graph falls
+/// back to the longest chain by count.
+/// Closed tasks are already done and drop out; gates aren't part of the
+/// dependency graph. Epics can end a chain but don't add to its length.
+pub fn critical_path(graph: &TaskGraph, target: Option<&str>) -> CriticalPath {
+    let is_open = |t: &Task| !t.is_deleted() && !t.is_closed() && !t.is_gate();
+
+    // Longest chain ending at each open task, and the prerequisite it
+    // continues through. Prerequisites come first in topological order, so
+    // each task's are settled by the time it's reached.
+    let mut chains: HashMap<&str, ((u64, usize), Option<&str>)> = HashMap::new();
+    for id in graph.topological_order() {
+        let Some(task) = graph.get(id).filter(|t| is_open(t)) else {
+            continue;
+        };
+        let mut prerequisites: Vec<&str> = task
+            .after
+            .iter()
+            .filter_map(|after_id| graph.get(after_id))
+            .chain(graph.subtasks(id))
+            .filter(|t| is_open(t))
+            .map(|t| t.id.as_str())
+            .collect();
+        prerequisites.sort();
+
+        let mut best: ((u64, usize), Option<&str>) = ((0, 0), None);
+        for prerequisite in prerequisites {
+            let Some(&(length, _)) = chains.get(prerequisite) else { continue };
+            if best.1.is_none() || length > best.0 {
+                best = (length, Some(prerequisite));
+            }
+        }
+        let own = if task.is_epic() { (0, 0) } else { (task.estimate.map_or(0, |e| e.minutes()), 1) };
+        chains.insert(task.id.as_str(), ((best.0.0 + own.0, best.0.1 + own.1), best.1));
+    }
+
+    let end = match target {
+        Some(target) => graph.resolve(target).filter(|t| is_open(t)).map(|t| t.id.as_str()),
+        None => {
+            let mut ends: Vec<(&str, (u64, usize))> = chains.iter().map(|(id, (length, _))| (*id, *length)).collect();
+            ends.sort();
+            let mut end: Option<(&str, (u64, usize))> = None;
+            for (id, length) in ends {
+                if end.is_none_or(|(_, best)| length > best) {
+                    end = Some((id, length));
+                }
+            }
+            end.map(|(id, _)| id)
+        }
+    };
+    let Some(end) = end else {
+        return CriticalPath::default();
+    };
+
+    let mut path = vec![end];
+    let mut current = end;
+    while let Some(&(_, Some(next))) = chains.get(current) {
+        path.push(next);
+        current = next;
+    }
+    path.reverse();
+
+    let tasks: Vec<&Task> = path.iter().filter_map(|id| graph.get(id)).collect();
+    CriticalPath {
+        tasks: path.iter().map(|id| id.to_string()).collect(),
+        estimate: tasks.iter().filter_map(|t| t.estimate).sum(),
+        unestimated: tasks.iter().filter(|t| !t.is_epic() && t.estimate.is_none()).count(),
+    }
+}
+
+/// What finishing a task would do for the work waiting on it; see `impact`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Impact {
+    /// Tasks that become ready once the task completes, in id order
+    pub unblocked: Vec<String>,
+    /// Open tasks waiting on the task, directly or transitively, in id order
+    pub blocked: Vec<String>,
+    /// Longest chain of waiting tasks below the task (0 if nothing waits on it)
+    pub depth: usize,
+}
+
+/// Work out what completing `id` (an id, uid or alias) would unblock.
+///
+/// A task waits on `id` if it lists it under `after`, or if `id` lists it
+/// under `before`. Whether a waiting task becomes ready is decided by
+/// `is_available` on a view where `id` is complete, so it follows the same
+/// rules as `mont ready`. Closed tasks end the walk: whatever waits on them
+/// isn't held up by `id`.
+pub fn impact(graph: &TaskGraph, id: &str) -> Impact {
+    let Some(task) = graph.resolve(id).filter(|t| !t.is_closed()) else {
+        return Impact::default();
+    };
+
+    // Open tasks waiting directly on `id`
+    let waiting = |id: &str| -> Vec<&Task> {
+        let mut waiting: Vec<&Task> = graph.dependents(id).collect();
+        if let Some(task) = graph.get(id) {
+            waiting.extend(task.before.iter().filter_map(|before_id| graph.get(before_id)));
+        }
+        waiting.retain(|t| !t.is_deleted() && !t.is_closed());
+        waiting.sort_by(|a, b| a.id.cmp(&b.id));
+        waiting.dedup_by(|a, b| a.id == b.id);
+        waiting
+    };
+
+    // Everything waiting on the task, directly or transitively
+    let mut reached: HashSet<&str> = HashSet::from([task.id.as_str()]);
+    let mut stack = vec![task.id.as_str()];
+    while let Some(next) = stack.pop() {
+        for t in waiting(next) {
+            if reached.insert(t.id.as_str()) {
+                stack.push(t.id.as_str());
+            }
+        }
+    }
+
+    // Longest chain below each reached task; what waits on a task comes after
+    // it in topological order, so walk the order backwards
+    let mut depths: HashMap<&str, usize> = HashMap::new();
+    for id in graph.topological_order().into_iter().rev().filter(|id| reached.contains(id)) {
+        let below = waiting(id)
+            .iter()
+            .filter_map(|t| depths.get(t.id.as_str()))
+            .map(|depth| depth + 1)
+            .max()
+            .unwrap_or(0);
+        depths.insert(id, below);
+    }
+    let depth = depths.get(task.id.as_str()).copied().unwrap_or(0);
+
+    let mut blocked: Vec<String> = reached.into_iter().filter(|t| *t != task.id).map(str::to_string).collect();
+    blocked.sort();
+
+    let mut completed = task.clone();
+    completed.status = Some(Status::Complete);
+    let ops = [Op::Update { old_id: task.id.clone(), task: completed }];
+    let view = ValidationView::new(graph, &ops);
+    let unblocked = waiting(&task.id)
+        .into_iter()
+        .filter(|t| !t.is_gate() && !t.is_epic() && !t.is_blocked())
+        .filter(|t| !is_available(t, graph) && view.get(&t.id).is_some_and(|t| is_available(t, &view)))
+        .map(|t| t.id.clone())
+        .collect();
+
+    Impact { unblocked, blocked, depth }
+}
+
 /// Build a TaskGraph from a list of tasks and validate it.
 ///
 /// Checks for duplicate IDs, validates all references, and ensures no cycles.
+/// References by uid or alias are then rewritten to task ids.
 pub fn form_graph(tasks: Vec<Task>) -> Result<TaskGraph, ValidationError> {
+    form_graph_with_archive(tasks, HashSet::new()).map_err(|mut errors| errors.swap_remove(0))
+}
+
+/// Like `form_graph`, for a directory with archived tasks: references to
+/// `archived` ids are accepted. Every problem found is returned, not just the
+/// first.
+pub fn form_graph_with_archive(tasks: Vec<Task>, archived: HashSet<String>) -> Result<TaskGraph, Vec<ValidationError>> {
     let mut graph = TaskGraph::new();
+    graph.set_archived(archived);
 
+    let mut errors = Vec::new();
     for task in tasks {
         if graph.contains(&task.id) {
-            return Err(ValidationError::DuplicateTaskId(task.id));
+            errors.push(ValidationError::DuplicateTaskId(task.id));
+            continue;
         }
         graph.insert(task);
     }
 
-    validate_view(&graph)?;
+    errors.extend(validate_all(&graph));
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    graph.canonicalize_references();
     graph.clear_dirty();
 
     Ok(graph)
 }
 
+/// Build a TaskGraph from tasks already known to form a valid graph, without
+/// validating them again.
+pub(crate) fn form_trusted_graph(tasks: Vec<Task>, archived: HashSet<String>) -> TaskGraph {
+    let mut graph = TaskGraph::new();
+    graph.set_archived(archived);
+    for task in tasks {
+        graph.insert(task);
+    }
+    graph.canonicalize_references();
+    graph.clear_dirty();
+    graph
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -574,30 +1197,15 @@ mod tests {
     fn make_task(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
     fn make_gate(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
-            status: None,
             task_type: TaskType::Gate,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
@@ -778,7 +1386,7 @@ mod tests {
         task.before = vec!["task".to_string()];
 
         let result = form_graph(vec![task]);
-        assert_eq!(result, Err(ValidationError::CycleDetected));
+        assert!(matches!(result, Err(ValidationError::CycleDetected { .. })));
     }
 
     #[test]
@@ -789,7 +1397,7 @@ mod tests {
         b.before = vec!["a".to_string()];
 
         let result = form_graph(vec![a, b]);
-        assert_eq!(result, Err(ValidationError::CycleDetected));
+        assert!(matches!(result, Err(ValidationError::CycleDetected { .. })));
     }
 
     #[test]
@@ -802,7 +1410,7 @@ mod tests {
         c.before = vec!["a".to_string()];
 
         let result = form_graph(vec![a, b, c]);
-        assert_eq!(result, Err(ValidationError::CycleDetected));
+        assert!(matches!(result, Err(ValidationError::CycleDetected { .. })));
     }
 
     #[test]
@@ -813,7 +1421,7 @@ mod tests {
         b.after = vec!["a".to_string()];
 
         let result = form_graph(vec![a, b]);
-        assert_eq!(result, Err(ValidationError::CycleDetected));
+        assert!(matches!(result, Err(ValidationError::CycleDetected { .. })));
     }
 
     #[test]
@@ -826,7 +1434,7 @@ mod tests {
         c.before = vec!["a".to_string()];
 
         let result = form_graph(vec![a, b, c]);
-        assert_eq!(result, Err(ValidationError::CycleDetected));
+        assert!(matches!(result, Err(ValidationError::CycleDetected { .. })));
     }
 
     #[test]
@@ -848,4 +1456,349 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 5);
     }
+
+    #[test]
+    fn test_effective_due_propagates_upstream() {
+        let date = |d| NaiveDate::from_ymd_opt(2025, 3, d);
+
+        // design -> build (via after) -> release (via before), release due on the 10th
+        let design = make_task("design");
+        let mut build = make_task("build");
+        build.after = vec!["design".to_string()];
+        build.before = vec!["release".to_string()];
+        build.due = date(20);
+        let mut release = make_task("release");
+        release.due = date(10);
+        let unrelated = make_task("unrelated");
+
+        let graph = form_graph(vec![design, build, release, unrelated]).unwrap();
+        let due = effective_due_dates(&graph);
+
+        assert_eq!(due.get("release").copied(), date(10));
+        assert_eq!(due.get("build").copied(), date(10));
+        assert_eq!(due.get("design").copied(), date(10));
+        assert!(!due.contains_key("unrelated"));
+    }
+
+    #[test]
+    fn test_effective_due_keeps_earlier_own_date() {
+        let date = |d| NaiveDate::from_ymd_opt(2025, 3, d);
+        let mut first = make_task("first");
+        first.due = date(1);
+        let mut second = make_task("second");
+        second.after = vec!["first".to_string()];
+        second.due = date(15);
+
+        let graph = form_graph(vec![first, second]).unwrap();
+        let due = effective_due_dates(&graph);
+        assert_eq!(due.get("first").copied(), date(1));
+        assert_eq!(due.get("second").copied(), date(15));
+    }
+
+    #[test]
+    fn test_critical_path_by_count_and_estimate() {
+        use super::super::task::Status;
+        let hours = |h: u64| Some(Effort::from_minutes(h * 60));
+
+        // done -> a -> b -> c -> release, and a short but expensive x -> release
+        let mut done = make_task("done");
+        done.status = Some(Status::Complete);
+        let mut a = make_task("a");
+        a.after = vec!["done".to_string()];
+        let mut b = make_task("b");
+        b.after = vec!["a".to_string()];
+        b.aliases = vec!["old-b".to_string()];
+        let mut c = make_task("c");
+        c.after = vec!["b".to_string()];
+        c.before = vec!["release".to_string()];
+        let mut x = make_task("x");
+        let mut release = make_task("release");
+        release.after = vec!["x".to_string()];
+
+        let graph = form_graph(vec![done.clone(), a.clone(), b.clone(), c.clone(), x.clone(), release.clone()]).unwrap();
+        let path = critical_path(&graph, None);
+        assert_eq!(path.tasks, vec!["a", "b", "c", "release"]);
+        assert_eq!(path.unestimated, 4);
+        assert_eq!(critical_path(&graph, Some("b")).tasks, vec!["a", "b"]);
+        assert_eq!(critical_path(&graph, Some("old-b")).tasks, vec!["a", "b"]);
+        assert!(critical_path(&graph, Some("done")).tasks.is_empty());
+
+        // Estimates outweigh length
+        a.estimate = hours(1);
+        b.estimate = hours(1);
+        x.estimate = hours(3);
+        let graph = form_graph(vec![done, a, b, c, x, release]).unwrap();
+        let path = critical_path(&graph, Some("release"));
+        assert_eq!(path.tasks, vec!["x", "release"]);
+        assert_eq!(path.estimate, Effort::from_minutes(180));
+        assert_eq!(path.unestimated, 1);
+    }
+
+    #[test]
+    fn test_impact_of_completing_a_task() {
+        use super::super::task::Status;
+
+        // auth -> login -> checkout, auth -> admin (also after other), auth is
+        // a subtask of release, and done -> auth was already finished
+        let mut auth = make_task("auth");
+        auth.uid = Some("01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string());
+        auth.before = vec!["release".to_string()];
+        let mut login = make_task("login");
+        login.after = vec!["auth".to_string()];
+        let mut checkout = make_task("checkout");
+        checkout.after = vec!["login".to_string()];
+        let other = make_task("other");
+        let mut admin = make_task("admin");
+        admin.after = vec!["auth".to_string(), "other".to_string()];
+        let release = make_task("release");
+        let mut shipped = make_task("shipped");
+        shipped.status = Some(Status::Complete);
+        shipped.after = vec!["auth".to_string()];
+
+        let graph = form_graph(vec![auth, login, checkout, other, admin, release, shipped]).unwrap();
+        let impact = impact(&graph, "auth");
+        assert_eq!(impact.unblocked, vec!["login", "release"]);
+        assert_eq!(impact.blocked, vec!["admin", "checkout", "login", "release"]);
+        assert_eq!(impact.depth, 2);
+        assert_eq!(super::impact(&graph, "01ARZ3NDEKTSV4RRFFQ69G5FAV"), impact);
+
+        assert_eq!(super::impact(&graph, "checkout"), Impact::default());
+        assert_eq!(super::impact(&graph, "shipped"), Impact::default());
+    }
+
+    #[test]
+    fn test_waiting_on_explains_unavailable_tasks() {
+        use super::super::task::Status;
+        let ids = |tasks: Vec<&Task>| tasks.into_iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+
+        // login waits on api (after) and form (a subtask), api waits on db,
+        // and db's own dependency is finished
+        let mut done = make_task("done");
+        done.status = Some(Status::Complete);
+        let mut db = make_task("db");
+        db.after = vec!["done".to_string()];
+        let mut api = make_task("api");
+        api.after = vec!["db".to_string()];
+        let mut form = make_task("form");
+        form.before = vec!["login".to_string()];
+        let mut login = make_task("login");
+        login.after = vec!["api".to_string(), "done".to_string()];
+
+        let graph = form_graph(vec![done, db, api, form, login]).unwrap();
+        let login = graph.get("login").unwrap();
+        let direct: Vec<(EdgeKind, &str)> = waiting_on(login, &graph).into_iter().map(|(k, t)| (k, t.id.as_str())).collect();
+        assert_eq!(direct, vec![(EdgeKind::After, "api"), (EdgeKind::Before, "form")]);
+        assert_eq!(ids(waiting_on_transitively(login, &graph)), vec!["api", "db", "form"]);
+
+        let db = graph.get("db").unwrap();
+        assert!(waiting_on(db, &graph).is_empty());
+        assert!(is_available(db, &graph));
+    }
+
+    #[test]
+    fn test_edge_index_follows_changes() {
+        let ids = |tasks: Vec<&Task>| tasks.into_iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+
+        let parent = make_task("parent");
+        let mut child = make_task("child");
+        child.before = vec!["parent".to_string()];
+        let mut next = make_task("next");
+        next.after = vec!["parent".to_string()];
+        let mut graph: TaskGraph = vec![parent, child.clone(), next].into_iter().collect();
+
+        assert_eq!(ids(graph.subtasks("parent").collect()), vec!["child"]);
+        assert_eq!(ids(graph.dependents("parent").collect()), vec!["next"]);
+        assert_eq!(ids(graph.referencing("parent")), vec!["child", "next"]);
+
+        // Replacing a task replaces its edges
+        child.before.clear();
+        child.after = vec!["parent".to_string()];
+        graph.insert(child);
+        assert_eq!(graph.subtasks("parent").count(), 0);
+        assert_eq!(ids(graph.dependents("parent").collect()), vec!["child", "next"]);
+
+        // Deleted tasks drop out once they're purged
+        graph.remove("next");
+        graph.clear_dirty();
+        assert_eq!(ids(graph.referencing("parent")), vec!["child"]);
+    }
+
+    #[test]
+    fn test_key_index_resolves_and_canonicalizes() {
+        let mut auth = make_task("auth");
+        auth.uid = Some("01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string());
+        auth.aliases = vec!["login".to_string()];
+        let mut page = make_task("page");
+        page.after = vec!["login".to_string()];
+        page.gates = vec![validation("01ARZ3NDEKTSV4RRFFQ69G5FAV")];
+        let mut graph: TaskGraph = vec![auth.clone(), page, make_task("other")].into_iter().collect();
+
+        assert_eq!(graph.resolve("login").map(|t| t.id.as_str()), Some("auth"));
+        assert_eq!(graph.resolve("01ARZ3NDEKTSV4RRFFQ69G5FAV").map(|t| t.id.as_str()), Some("auth"));
+
+        graph.canonicalize_references();
+        let page = graph.get("page").unwrap();
+        assert_eq!(page.after, vec!["auth"]);
+        assert_eq!(page.gate_ids().collect::<Vec<_>>(), vec!["auth"]);
+        assert!(!graph.is_dirty("page"));
+
+        // Replacing a task replaces the keys it's known by
+        auth.aliases.clear();
+        graph.insert(auth);
+        assert!(graph.resolve("login").is_none());
+    }
+
+    /// Time `available_tasks` on a few thousand tasks against the scan it
+    /// replaced. Run with
+    /// `cargo test --release -- --ignored --nocapture availability_benchmark`.
+    #[test]
+    #[ignore]
+    fn availability_benchmark() {
+        use std::time::Instant;
+
+        const TASKS: usize = 5_000;
+        let graph: TaskGraph = (0..TASKS)
+            .map(|i| {
+                let mut task = make_task(&format!("task-{i}"));
+                if i > 0 {
+                    task.before = vec![format!("task-{}", i / 4)];
+                }
+                task
+            })
+            .collect();
+
+        let start = Instant::now();
+        let indexed = available_tasks(&graph).len();
+        let indexed_time = start.elapsed();
+
+        let start = Instant::now();
+        let scanned = graph
+            .values()
+            .filter(|task| {
+                graph
+                    .values()
+                    .all(|other| !other.before.contains(&task.id) || graph.satisfies_dependents(other))
+            })
+            .count();
+        let scanned_time = start.elapsed();
+
+        assert_eq!(indexed, scanned);
+        println!("{TASKS} tasks: indexed {indexed_time:?}, scan {scanned_time:?}");
+    }
+
+    #[test]
+    fn test_blocked_task_not_available() {
+        use super::super::task::Status;
+        let mut blocked = make_task("blocked");
+        blocked.status = Some(Status::Blocked);
+        let free = make_task("free");
+
+        let graph = form_graph(vec![blocked, free]).unwrap();
+        let ids: Vec<&str> = available_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["free"]);
+    }
+
+    #[test]
+    fn test_cancelled_dependency_policy() {
+        use super::super::task::Status;
+        let mut dropped = make_task("dropped");
+        dropped.status = Some(Status::Cancelled);
+        let mut dependent = make_task("dependent");
+        dependent.after = vec!["dropped".to_string()];
+
+        let mut graph = form_graph(vec![dropped, dependent]).unwrap();
+        let ids: Vec<&str> = available_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["dependent"]);
+
+        graph.set_cancelled_blocks_dependents(true);
+        assert!(available_tasks(&graph).is_empty());
+    }
+
+    #[test]
+    fn test_epic_progress_rollup() {
+        use super::super::task::{GateItem, Status, TaskType};
+        let mut epic = make_task("epic");
+        epic.task_type = TaskType::Epic;
+
+        let mut done = make_task("done");
+        done.before = vec!["epic".to_string()];
+        done.status = Some(Status::Complete);
+        done.gates = vec![GateItem { id: "review".to_string(), status: GateStatus::Passed }];
+
+        let mut open = make_task("open");
+        open.before = vec!["epic".to_string()];
+        open.estimate = Some("3h".parse().unwrap());
+
+        // Grandchild through a nested task still counts toward the epic
+        let mut nested = make_task("nested");
+        nested.before = vec!["open".to_string()];
+        nested.estimate = Some("1h".parse().unwrap());
+
+        let mut dropped = make_task("dropped");
+        dropped.before = vec!["epic".to_string()];
+        dropped.status = Some(Status::Cancelled);
+
+        let mut review = make_task("review");
+        review.task_type = TaskType::Gate;
+
+        let graph = form_graph(vec![epic, done, open, nested, dropped, review]).unwrap();
+        let progress = epic_progress("epic", &graph, &["review".to_string()]);
+
+        assert_eq!(progress.total, 3);
+        assert_eq!(progress.complete, 1);
+        assert_eq!(progress.percent(), 33);
+        assert_eq!(progress.remaining_estimate.minutes(), 240);
+        assert_eq!((progress.gates_passed, progress.gates_total), (1, 3));
+        assert!(finished_epics(&graph).is_empty());
+        assert!(!available_tasks(&graph).iter().any(|t| t.id == "epic"));
+    }
+
+    #[test]
+    fn test_finished_epics_requires_all_children_closed() {
+        use super::super::task::{Status, TaskType};
+        let mut epic = make_task("epic");
+        epic.task_type = TaskType::Epic;
+        let mut empty = make_task("empty");
+        empty.task_type = TaskType::Epic;
+
+        let mut done = make_task("done");
+        done.before = vec!["epic".to_string()];
+        done.status = Some(Status::Complete);
+        let mut dropped = make_task("dropped");
+        dropped.before = vec!["epic".to_string()];
+        dropped.status = Some(Status::Cancelled);
+
+        let graph = form_graph(vec![epic, empty, done, dropped]).unwrap();
+        assert_eq!(finished_epics(&graph), vec!["epic".to_string()]);
+    }
+
+    #[test]
+    fn test_finished_epics_looks_through_nested_work() {
+        use super::super::task::{Status, TaskType};
+        let epic = |id: &str, before: &[&str]| {
+            let mut task = make_task(id);
+            task.task_type = TaskType::Epic;
+            task.before = before.iter().map(|s| s.to_string()).collect();
+            task
+        };
+        let mut shared = make_task("shared");
+        shared.before = vec!["inner".to_string(), "other".to_string()];
+        shared.status = Some(Status::Complete);
+        let mut open = make_task("open");
+        open.before = vec!["shared".to_string()];
+
+        let tasks = vec![epic("outer", &[]), epic("inner", &["outer"]), epic("other", &[]), shared.clone(), open];
+        let graph = form_graph(tasks.clone()).unwrap();
+        assert!(finished_epics(&graph).is_empty());
+
+        // Once the task beneath the shared one is done, both epics holding it
+        // finish; the outer one waits for the inner epic to be completed
+        let mut tasks = tasks;
+        tasks[4].status = Some(Status::Complete);
+        let graph = form_graph(tasks.clone()).unwrap();
+        assert_eq!(finished_epics(&graph), vec!["inner".to_string(), "other".to_string()]);
+        tasks[1].status = Some(Status::Complete);
+        let graph = form_graph(tasks).unwrap();
+        assert_eq!(finished_epics(&graph), vec!["other".to_string(), "outer".to_string()]);
+    }
 }
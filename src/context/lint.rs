@@ -0,0 +1,248 @@
+//! Lint rules for task quality, run by `mont check`.
+//!
+//! Validation decides whether the graph can be loaded at all; lints flag tasks
+//! that load fine but are probably not what you meant to write down. Each rule
+//! is set to `off`, `warn` or `error` under `lint:` in config.yml.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+use super::task::Task;
+use super::TaskGraph;
+
+/// How a lint rule is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warn,
+    Error,
+}
+
+/// The `lint:` section of config.yml.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Open tasks with an empty description.
+    pub missing_description: Severity,
+    /// Titles longer than `max_title_length` characters.
+    pub long_title: Severity,
+    pub max_title_length: usize,
+    /// Gates that no task lists and that aren't default gates.
+    pub unused_gate: Severity,
+    /// Jots that haven't been distilled `stale_jot_days` after creation.
+    /// Only jots with a recorded `created` time are checked; ones written
+    /// before mont recorded it have no known age.
+    pub stale_jot: Severity,
+    pub stale_jot_days: u32,
+    /// In-progress tasks started over `idle_hours` ago with no code changes
+    /// in the working copy.
+    pub idle_in_progress: Severity,
+    pub idle_hours: u32,
+    /// Open tasks without gates, when config.yml has no `default_gates`.
+    pub no_gates: Severity,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            missing_description: Severity::Warn,
+            long_title: Severity::Warn,
+            max_title_length: 80,
+            unused_gate: Severity::Warn,
+            stale_jot: Severity::Warn,
+            stale_jot_days: 14,
+            idle_in_progress: Severity::Warn,
+            idle_hours: 24,
+            no_gates: Severity::Off,
+        }
+    }
+}
+
+/// A lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    MissingDescription,
+    LongTitle,
+    UnusedGate,
+    StaleJot,
+    IdleInProgress,
+    NoGates,
+}
+
+impl Rule {
+    /// The rule's name in output; its config key uses underscores.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::MissingDescription => "missing-description",
+            Rule::LongTitle => "long-title",
+            Rule::UnusedGate => "unused-gate",
+            Rule::StaleJot => "stale-jot",
+            Rule::IdleInProgress => "idle-in-progress",
+            Rule::NoGates => "no-gates",
+        }
+    }
+}
+
+/// A task that broke a lint rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    pub rule: Rule,
+    pub severity: Severity,
+    pub task_id: String,
+    pub message: String,
+}
+
+/// What the lints need to know beyond the graph.
+pub struct LintEnv {
+    pub now: DateTime<Utc>,
+    /// Whether the working copy has changes outside `.tasks`, if known.
+    pub has_code_changes: Option<bool>,
+}
+
+/// Run every enabled rule over the graph, in task id order.
+pub fn lint(graph: &TaskGraph, config: &LintConfig, default_gates: &[String], env: &LintEnv) -> Vec<LintFinding> {
+    let mut tasks: Vec<&Task> = graph.values().filter(|t| !t.is_deleted()).collect();
+    tasks.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut findings = Vec::new();
+    let mut report = |rule: Rule, severity: Severity, task: &Task, message: String| {
+        if severity != Severity::Off {
+            findings.push(LintFinding {
+                rule,
+                severity,
+                task_id: task.id.clone(),
+                message,
+            });
+        }
+    };
+
+    for task in tasks {
+        if task.is_gate() {
+            let used = default_gates.contains(&task.id)
+                || graph.values().any(|t| !t.is_deleted() && t.gate_ids().any(|g| g == task.id));
+            if !used {
+                report(Rule::UnusedGate, config.unused_gate, task, "gate is not listed by any task".to_string());
+            }
+        }
+
+        if task.is_closed() {
+            continue;
+        }
+
+        if let Some(title) = &task.title {
+            let length = title.chars().count();
+            if length > config.max_title_length {
+                report(
+                    Rule::LongTitle,
+                    config.long_title,
+                    task,
+                    format!("title is {} characters (max {})", length, config.max_title_length),
+                );
+            }
+        }
+
+        if task.is_jot() {
+            if let Some(created) = task.created
+                && env.now - created > Duration::days(config.stale_jot_days.into())
+            {
+                report(
+                    Rule::StaleJot,
+                    config.stale_jot,
+                    task,
+                    format!("jot created {} days ago; distill or cancel it", (env.now - created).num_days()),
+                );
+            }
+            continue;
+        }
+
+        if task.description.trim().is_empty() {
+            report(Rule::MissingDescription, config.missing_description, task, "task has no description".to_string());
+        }
+
+        if task.is_in_progress()
+            && env.has_code_changes == Some(false)
+            && let Some(session) = task.sessions.last().filter(|s| s.is_open())
+            && env.now - session.start > Duration::hours(config.idle_hours.into())
+        {
+            report(
+                Rule::IdleInProgress,
+                config.idle_in_progress,
+                task,
+                format!(
+                    "in progress for {} hours with no code changes",
+                    (env.now - session.start).num_hours()
+                ),
+            );
+        }
+
+        if default_gates.is_empty() && task.gates.is_empty() && !task.is_gate() && !task.is_epic() {
+            report(Rule::NoGates, config.no_gates, task, "task has no gates".to_string());
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::task::parse;
+    use crate::context::Session;
+
+    fn task(content: &str) -> Task {
+        parse(content).unwrap()
+    }
+
+    fn rules(findings: &[LintFinding], id: &str) -> Vec<&'static str> {
+        findings.iter().filter(|f| f.task_id == id).map(|f| f.rule.name()).collect()
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        let now = Utc::now();
+
+        let mut working = task("---\nid: working\nstatus: inprogress\ngates:\n  - review\n---\n\nBody\n");
+        working.sessions = vec![Session { start: now - Duration::hours(30), end: None }];
+
+        let graph: TaskGraph = vec![
+            task("---\nid: review\ntype: gate\n---\n\nGet a review.\n"),
+            task("---\nid: unused\ntype: gate\n---\n\nNobody asks for this.\n"),
+            Task { created: Some(now - Duration::days(30)), ..task("---\nid: old-jot\ntype: jot\n---\n") },
+            Task { created: Some(now), ..task("---\nid: new-jot\ntype: jot\n---\n") },
+            // The uid of a migrated jot says when it was migrated, not created
+            task(&format!(
+                "---\nid: migrated-jot\nuid: {}\ntype: jot\n---\n",
+                ulid::Ulid::from_datetime((now - Duration::days(30)).into())
+            )),
+            task(&format!("---\nid: bare\ntitle: {}\n---\n", "x".repeat(81))),
+            task("---\nid: finished\nstatus: complete\n---\n"),
+            working,
+        ]
+        .into_iter()
+        .collect();
+
+        let config = LintConfig { no_gates: Severity::Error, ..LintConfig::default() };
+        let env = LintEnv { now, has_code_changes: Some(false) };
+        let findings = lint(&graph, &config, &[], &env);
+
+        assert_eq!(rules(&findings, "review"), Vec::<&str>::new());
+        assert_eq!(rules(&findings, "unused"), vec!["unused-gate"]);
+        assert_eq!(rules(&findings, "old-jot"), vec!["stale-jot"]);
+        assert_eq!(rules(&findings, "new-jot"), Vec::<&str>::new());
+        assert_eq!(rules(&findings, "migrated-jot"), Vec::<&str>::new());
+        assert_eq!(rules(&findings, "bare"), vec!["long-title", "missing-description", "no-gates"]);
+        assert_eq!(rules(&findings, "finished"), Vec::<&str>::new());
+        assert_eq!(rules(&findings, "working"), vec!["idle-in-progress"]);
+
+        // Code changes in the working copy mean the task isn't idle
+        let env = LintEnv { now, has_code_changes: Some(true) };
+        assert!(rules(&lint(&graph, &config, &[], &env), "working").is_empty());
+
+        // Default gates cover every task, and count as using the gate
+        let defaults = vec!["unused".to_string()];
+        let findings = lint(&graph, &config, &defaults, &env);
+        assert!(rules(&findings, "unused").is_empty());
+        assert_eq!(rules(&findings, "bare"), vec!["long-title", "missing-description"]);
+    }
+}
@@ -0,0 +1,413 @@
+//! On-disk format versioning for `.tasks` directories.
+//!
+//! `config.yml` records the format version as `version:`. A directory
+//! without a config file is taken to be current; a config without a
+//! `version` key predates versioning and is format 1.
+//!
+//! Each migration upgrades the directory by exactly one version. All steps
+//! run in memory first, so a failing step leaves the files untouched, and
+//! `config.yml` is written last so an interrupted migration can be re-run.
+//!
+//! Task files read from other revisions are upgraded in memory instead (see
+//! `upgrade_contents`).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::frontmatter;
+use super::namespace;
+
+/// Format version written by this build of mont.
+pub const FORMAT_VERSION: u32 = 3;
+
+/// Errors that can occur when checking or migrating a directory's format.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrateError {
+    #[error("failed to access {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to read version from config.yml: {0}")]
+    Config(#[source] serde_yaml::Error),
+
+    #[error(".tasks is format version {found}, but this mont only supports up to {supported}")]
+    Newer { found: u32, supported: u32 },
+
+    #[error(".tasks is format version {found} and needs migrating to {current}")]
+    Outdated { found: u32, current: u32 },
+}
+
+/// One upgrade step, from version `from` to `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    /// What the step changes, shown by `mont migrate`
+    pub description: &'static str,
+    /// Whether the step makes up data (like uids) that only means something
+    /// once it's written back. `upgrade_contents` skips these.
+    invents: bool,
+    apply: fn(&mut Files),
+}
+
+/// Registered migrations, in order.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "rename 'validations' to 'gates' in task frontmatter",
+        invents: false,
+        apply: rename_validations,
+    },
+    Migration {
+        from: 2,
+        description: "give every task a permanent uid",
+        invents: true,
+        apply: assign_uids,
+    },
+];
+
+/// What a migration did (or would do, for a dry run).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    /// Descriptions of the steps that ran
+    pub steps: Vec<&'static str>,
+    /// Files whose contents changed
+    pub changed: Vec<PathBuf>,
+}
+
+/// Contents of a `.tasks` directory, keyed by path.
+struct Files {
+    config: Option<String>,
+    tasks: BTreeMap<PathBuf, String>,
+}
+
+#[derive(Deserialize)]
+struct VersionOnly {
+    #[serde(default)]
+    version: Option<u32>,
+}
+
+/// Read the format version of a `.tasks` directory.
+pub fn directory_version(tasks_dir: &Path) -> Result<u32, MigrateError> {
+    let path = tasks_dir.join("config.yml");
+    match std::fs::read_to_string(&path) {
+        Ok(content) => config_version(&content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(FORMAT_VERSION),
+        Err(source) => Err(MigrateError::Io { path, source }),
+    }
+}
+
+/// Ensure a directory is at the current format version.
+pub fn check_version(tasks_dir: &Path) -> Result<(), MigrateError> {
+    let found = directory_version(tasks_dir)?;
+    match found.cmp(&FORMAT_VERSION) {
+        std::cmp::Ordering::Equal => Ok(()),
+        std::cmp::Ordering::Greater => Err(MigrateError::Newer { found, supported: FORMAT_VERSION }),
+        std::cmp::Ordering::Less => Err(MigrateError::Outdated { found, current: FORMAT_VERSION }),
+    }
+}
+
+/// Upgrade a `.tasks` directory to `FORMAT_VERSION`.
+///
+/// With `dry_run` set, reports what would change without writing anything.
+pub fn migrate(tasks_dir: &Path, dry_run: bool) -> Result<MigrationReport, MigrateError> {
+    let from = directory_version(tasks_dir)?;
+    if from > FORMAT_VERSION {
+        return Err(MigrateError::Newer { found: from, supported: FORMAT_VERSION });
+    }
+
+    let original = read_files(tasks_dir)?;
+    let mut files = Files {
+        config: original.config.clone(),
+        tasks: original.tasks.clone(),
+    };
+
+    let mut steps = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from && m.from < FORMAT_VERSION) {
+        (migration.apply)(&mut files);
+        steps.push(migration.description);
+    }
+    if from < FORMAT_VERSION {
+        files.config = Some(set_config_version(files.config.as_deref().unwrap_or(""), FORMAT_VERSION));
+    }
+
+    let mut changed: Vec<PathBuf> = files
+        .tasks
+        .iter()
+        .filter(|(path, content)| original.tasks.get(*path) != Some(*content))
+        .map(|(path, _)| path.clone())
+        .collect();
+    let config_path = tasks_dir.join("config.yml");
+    if files.config != original.config {
+        changed.push(config_path.clone());
+    }
+
+    if !dry_run {
+        for path in &changed {
+            let content = if *path == config_path {
+                files.config.as_deref()
+            } else {
+                files.tasks.get(path).map(String::as_str)
+            };
+            if let Some(content) = content {
+                write_atomic(path, content)?;
+            }
+        }
+    }
+
+    Ok(MigrationReport { from, to: FORMAT_VERSION, steps, changed })
+}
+
+/// Bring task file contents read from elsewhere (e.g. an older revision) up
+/// to the current format in memory. `config` is the `config.yml` they came
+/// with, if any, and decides which steps run.
+///
+/// Steps that invent data are skipped: a uid made up here would match
+/// nothing, on disk or in another revision.
+pub fn upgrade_contents(config: Option<&str>, tasks: &mut BTreeMap<PathBuf, String>) -> Result<(), MigrateError> {
+    let from = config.map_or(Ok(FORMAT_VERSION), config_version)?;
+    if from > FORMAT_VERSION {
+        return Err(MigrateError::Newer { found: from, supported: FORMAT_VERSION });
+    }
+
+    let mut files = Files {
+        config: None,
+        tasks: std::mem::take(tasks),
+    };
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from && m.from < FORMAT_VERSION && !m.invents) {
+        (migration.apply)(&mut files);
+    }
+    *tasks = files.tasks;
+    Ok(())
+}
+
+fn config_version(content: &str) -> Result<u32, MigrateError> {
+    if content.trim().is_empty() {
+        return Ok(1);
+    }
+    let parsed: VersionOnly = serde_yaml::from_str(content).map_err(MigrateError::Config)?;
+    Ok(parsed.version.unwrap_or(1))
+}
+
+fn read_files(tasks_dir: &Path) -> Result<Files, MigrateError> {
+    let io = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| MigrateError::Io { path, source }
+    };
+
+    let config_path = tasks_dir.join("config.yml");
+    let config = match std::fs::read_to_string(&config_path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(io(&config_path)(e)),
+    };
+
+    let mut tasks = BTreeMap::new();
+    let paths = namespace::task_files(tasks_dir).map_err(|(path, source)| MigrateError::Io { path, source })?;
+    for path in paths {
+        let content = std::fs::read_to_string(&path).map_err(io(&path))?;
+        tasks.insert(path, content);
+    }
+
+    Ok(Files { config, tasks })
+}
+
+/// Write through a temporary file so a crash never leaves a half-written file.
+fn write_atomic(path: &Path, content: &str) -> Result<(), MigrateError> {
+    let tmp = path.with_extension("migrate.tmp");
+    std::fs::write(&tmp, content)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|source| MigrateError::Io { path: path.to_path_buf(), source })
+}
+
+/// Set the top-level `version:` key, replacing it if present and otherwise
+/// adding it after any leading comment block.
+fn set_config_version(config: &str, version: u32) -> String {
+    let newline = if config.contains("\r\n") { "\r\n" } else { "\n" };
+    let line = format!("version: {}{}", version, newline);
+    let mut lines: Vec<String> = config.split_inclusive('\n').map(str::to_string).collect();
+    if let Some(last) = lines.last_mut()
+        && !last.ends_with('\n')
+    {
+        last.push_str(newline);
+    }
+
+    match lines.iter().position(|l| l.starts_with("version:")) {
+        Some(existing) => lines[existing] = line,
+        None => {
+            let insert_at = lines
+                .iter()
+                .position(|l| !l.starts_with('#') && !l.trim().is_empty())
+                .unwrap_or(lines.len());
+            lines.insert(insert_at, line);
+        }
+    }
+    lines.concat()
+}
+
+/// Rename a top-level frontmatter key in a task file, keeping everything else
+/// byte-for-byte. Files whose frontmatter can't be located are left alone.
+fn rename_key(content: &str, from: &str, to: &str) -> String {
+    let Ok(split) = frontmatter::split(content) else {
+        return content.to_string();
+    };
+    let separator = match split.format {
+        frontmatter::FrontmatterFormat::Yaml => ':',
+        frontmatter::FrontmatterFormat::Toml => '=',
+    };
+
+    content
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let renames = i > split.open
+                && i < split.close
+                && line
+                    .strip_prefix(from)
+                    .is_some_and(|rest| rest.trim_start().starts_with(separator));
+            if renames {
+                format!("{}{}", to, &line[from.len()..])
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+/// 1 → 2: gates were originally called validations.
+fn rename_validations(files: &mut Files) {
+    for content in files.tasks.values_mut() {
+        *content = rename_key(content, "validations", "gates");
+    }
+}
+
+/// Insert a `uid` key after a task's `id`, or at the top of the frontmatter
+/// if it has none. Files that already have a uid are left alone.
+fn insert_uid(content: &str, uid: &str) -> String {
+    let Ok(split) = frontmatter::split(content) else {
+        return content.to_string();
+    };
+    let (separator, line) = match split.format {
+        frontmatter::FrontmatterFormat::Yaml => (':', format!("uid: {}", uid)),
+        frontmatter::FrontmatterFormat::Toml => ('=', format!("uid = \"{}\"", uid)),
+    };
+    let is_key = |line: &str, key: &str| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with(separator))
+    };
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let frontmatter = &lines[split.open + 1..split.close];
+    if frontmatter.iter().any(|l| is_key(l, "uid")) {
+        return content.to_string();
+    }
+    let after = frontmatter
+        .iter()
+        .position(|l| is_key(l, "id"))
+        .map_or(split.open, |i| split.open + 1 + i);
+    let newline = if lines[after].ends_with("\r\n") { "\r\n" } else { "\n" };
+
+    let mut out = lines[..=after].concat();
+    out.push_str(&line);
+    out.push_str(newline);
+    out.push_str(&lines[after + 1..].concat());
+    out
+}
+
+/// 2 → 3: tasks get a ULID that survives renames.
+fn assign_uids(files: &mut Files) {
+    for content in files.tasks.values_mut() {
+        *content = insert_uid(content, &ulid::Ulid::new().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_insert_uid() {
+        assert_eq!(insert_uid("---\r\ntitle: T\r\nid: x\r\n---\r\n", "U1"), "---\r\ntitle: T\r\nid: x\r\nuid: U1\r\n---\r\n");
+        assert_eq!(insert_uid("+++\ntitle = \"T\"\n+++\n", "U1"), "+++\nuid = \"U1\"\ntitle = \"T\"\n+++\n");
+        assert_eq!(insert_uid("---\nid: x\nuid: U0\n---\n", "U1"), "---\nid: x\nuid: U0\n---\n");
+    }
+
+    #[test]
+    fn test_directory_version() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(directory_version(temp_dir.path()).unwrap(), FORMAT_VERSION);
+
+        std::fs::write(temp_dir.path().join("config.yml"), "default_gates: []\n").unwrap();
+        assert_eq!(directory_version(temp_dir.path()).unwrap(), 1);
+
+        std::fs::write(temp_dir.path().join("config.yml"), "version: 99\n").unwrap();
+        assert!(matches!(
+            check_version(temp_dir.path()),
+            Err(MigrateError::Newer { found: 99, .. })
+        ));
+
+        std::fs::write(temp_dir.path().join("config.yml"), "version: 2\n").unwrap();
+        assert!(matches!(
+            check_version(temp_dir.path()),
+            Err(MigrateError::Outdated { found: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_upgrade_contents() {
+        let path = PathBuf::from(".tasks/old.md");
+        let mut tasks = BTreeMap::from([(path.clone(), "---\nid: old\nvalidations:\n  - review\n---\n".to_string())]);
+
+        upgrade_contents(Some("default_gates: []\n"), &mut tasks).unwrap();
+        assert_eq!(tasks[&path], "---\nid: old\ngates:\n  - review\n---\n");
+
+        assert!(matches!(
+            upgrade_contents(Some("version: 99\n"), &mut tasks),
+            Err(MigrateError::Newer { found: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn test_migrate_from_legacy() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        std::fs::write(dir.join("config.yml"), "# Mont configuration\n\ndefault_gates: []\n").unwrap();
+        std::fs::write(
+            dir.join("old.md"),
+            "---\nid: old\nvalidations:\n  - review\n---\n\nvalidations: stays in the body\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("new.md"), "---\nid: new\n---\n").unwrap();
+
+        let report = migrate(dir, true).unwrap();
+        assert_eq!((report.from, report.to), (1, FORMAT_VERSION));
+        assert_eq!(report.steps.len(), 2);
+        assert_eq!(
+            report.changed,
+            vec![dir.join("new.md"), dir.join("old.md"), dir.join("config.yml")]
+        );
+        assert_eq!(directory_version(dir).unwrap(), 1);
+
+        migrate(dir, false).unwrap();
+        let old = std::fs::read_to_string(dir.join("old.md")).unwrap();
+        let uid = crate::parse(&old).unwrap().uid.unwrap();
+        assert_eq!(
+            old,
+            format!("---\nid: old\nuid: {}\ngates:\n  - review\n---\n\nvalidations: stays in the body\n", uid)
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("config.yml")).unwrap(),
+            "# Mont configuration\n\nversion: 3\ndefault_gates: []\n"
+        );
+        assert!(check_version(dir).is_ok());
+
+        let again = migrate(dir, false).unwrap();
+        assert!(again.steps.is_empty() && again.changed.is_empty());
+    }
+}
@@ -7,22 +7,45 @@
 //! - `Transaction` - Atomic batch operations with validation
 //! - Validation logic for ensuring graph integrity
 
+mod archive;
+mod cache;
+mod effort;
+mod frontmatter;
+mod lint;
 pub(crate) mod graph;
+mod migrate;
+mod namespace;
+mod query;
+mod schedule;
 mod settings;
+mod snapshot;
 mod task;
 mod transaction;
 pub(crate) mod validations;
 mod view;
 
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::Utc;
+use cache::Cache;
 
 // Re-export public types
+pub use archive::{parse_age, ARCHIVE_DIR};
+pub use effort::{Effort, EffortParseError, Session};
+pub use frontmatter::{FrontmatterFormat, SourceText};
+pub use lint::{lint, LintConfig, LintEnv, LintFinding, Rule, Severity};
 pub use graph::{GraphReadError, TaskGraph};
-pub use settings::{GlobalConfig, SettingsError};
-pub use task::{parse, ParseError, Status, Task, TaskType, GateItem, GateStatus};
+pub use query::{is_query, Query, QueryError};
+pub use schedule::{plan, Plan, Wave};
+pub use migrate::{check_version, directory_version, migrate, MigrateError, Migration, MigrationReport, FORMAT_VERSION, MIGRATIONS};
+pub use settings::{GlobalConfig, Milestone, SettingsError};
+pub use snapshot::Snapshot;
+pub use task::{parse, parse_due_date, Blocker, Cancellation, ParseError, Reopening, Status, Task, TaskType, GateItem, GateStatus};
 pub use transaction::{Op, Transaction};
-pub use validations::ValidationError;
+pub use validations::{CycleEdge, EdgeKind, ValidationError};
 pub use view::{GraphView, ValidationView};
 
 /// Errors that can occur when loading a MontContext.
@@ -33,6 +56,9 @@ pub enum LoadError {
 
     #[error("failed to load config.yml")]
     Settings(#[source] SettingsError),
+
+    #[error("unsupported .tasks format")]
+    Format(#[source] MigrateError),
 }
 
 /// Internal state protected by RwLock.
@@ -78,52 +104,90 @@ impl MontContext {
 
     /// Load a MontContext from a tasks directory.
     ///
-    /// Reads all .md files from the directory, parses them, and validates
+    /// Reads all .md files from the directory and its namespace
+    /// subdirectories (see `namespace`), parses them, and validates
     /// the resulting graph. Uses batch error collection - all errors are
     /// gathered and returned together rather than failing on the first error.
+    ///
+    /// Files that haven't changed since the last load come from the load
+    /// cache instead of being parsed again (see `cache`).
+    ///
+    /// Refuses directories in any format version other than `FORMAT_VERSION`,
+    /// before reading a single task: older ones need `mont migrate`, newer ones
+    /// a newer mont.
     pub fn load(tasks_dir: PathBuf) -> Result<Self, LoadError> {
+        check_version(&tasks_dir).map_err(LoadError::Format)?;
+
         let mut errors = GraphReadError::new();
         let mut tasks = Vec::new();
 
-        // Read directory entries
-        let entries = match std::fs::read_dir(&tasks_dir) {
-            Ok(entries) => entries,
-            Err(e) => {
-                errors.add_io_error(tasks_dir.clone(), e);
+        // Collect task files, including those in namespace subdirectories
+        let paths = match namespace::task_files(&tasks_dir) {
+            Ok(paths) => paths,
+            Err((path, e)) => {
+                errors.add_io_error(path, e);
                 return Err(LoadError::Graph(errors));
             }
         };
 
-        // Collect and sort paths
-        let mut paths: Vec<PathBuf> = entries
-            .filter_map(|e| e.ok())
-            .map(|e| e.path())
-            .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        // Archived tasks aren't loaded; only their ids are kept
+        let archive_dir = archive::archive_dir(&tasks_dir);
+        let (archived, paths): (Vec<_>, Vec<_>) = paths.into_iter().partition(|p| p.starts_with(&archive_dir));
+        let archived: HashSet<String> = archived
+            .iter()
+            .filter_map(|p| archive::archived_id(&archive_dir, p))
             .collect();
-        paths.sort();
 
-        // Read and parse each file
+        // Read and parse each file, reusing the cached parse of unchanged ones
+        let mut cached = Cache::read(&tasks_dir);
+        let mut fresh = Cache::default();
+        fresh.set_archived(archived.iter().cloned().collect());
         for path in paths {
-            let content = match std::fs::read_to_string(&path) {
-                Ok(content) => content,
+            let meta = match std::fs::metadata(&path) {
+                Ok(meta) => meta,
                 Err(e) => {
                     errors.add_io_error(path, e);
                     continue;
                 }
             };
-
-            match parse(&content) {
-                Ok(parsed) => {
-                    if parsed.id.is_empty() {
-                        errors.add_parse_error(path, ParseError::EmptyId);
-                    } else {
-                        tasks.push(parsed);
+            let key = cache::key(&tasks_dir, &path);
+
+            let mut parsed = match fresh.reuse(&mut cached, key.clone(), &path, &meta) {
+                Some(task) => task,
+                None => {
+                    let content = match std::fs::read_to_string(&path) {
+                        Ok(content) => content,
+                        Err(e) => {
+                            errors.add_io_error(path, e);
+                            continue;
+                        }
+                    };
+                    match parse(&content) {
+                        Ok(parsed) if parsed.id.is_empty() => {
+                            errors.add_parse_error(path, ParseError::EmptyId);
+                            continue;
+                        }
+                        Ok(parsed) => {
+                            fresh.insert(key, &meta, &parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            errors.add_parse_error(path, e);
+                            continue;
+                        }
                     }
                 }
-                Err(e) => {
-                    errors.add_parse_error(path, e);
-                }
+            };
+
+            if let Some(ns) = namespace::namespace_of(&tasks_dir, &path) {
+                namespace::qualify_id(&mut parsed, &ns);
             }
+            tasks.push(parsed);
+        }
+
+        let ids: HashSet<String> = tasks.iter().map(|t| t.id.clone()).collect();
+        for task in &mut tasks {
+            namespace::qualify_references(task, |key| ids.contains(key) || archived.contains(key));
         }
 
         // If we have IO or parse errors, return them before validation
@@ -131,11 +195,23 @@ impl MontContext {
             return Err(LoadError::Graph(errors));
         }
 
-        // Validate and form the graph
-        let graph = match graph::form_graph(tasks) {
+        // Validate and form the graph, unless the same files were valid last time
+        let unchanged = fresh.is_unchanged_from(&cached);
+        let result = if unchanged {
+            Ok(graph::form_trusted_graph(tasks, archived))
+        } else {
+            graph::form_graph_with_archive(tasks, archived)
+        };
+        if !unchanged {
+            fresh.set_valid(result.is_ok());
+            fresh.write(&tasks_dir);
+        }
+        let mut graph = match result {
             Ok(graph) => graph,
-            Err(e) => {
-                errors.add_validation_error(e);
+            Err(validation_errors) => {
+                for e in validation_errors {
+                    errors.add_validation_error(e);
+                }
                 return Err(LoadError::Graph(errors));
             }
         };
@@ -144,6 +220,7 @@ impl MontContext {
         let config_path = tasks_dir.join("config.yml");
         let config = GlobalConfig::load(&config_path).map_err(LoadError::Settings)?;
         config.validate(&graph).map_err(LoadError::Settings)?;
+        graph.set_cancelled_blocks_dependents(config.cancelled_blocks_dependents);
 
         Ok(Self {
             inner: RwLock::new(ContextInner { graph, config, version: 0 }),
@@ -177,12 +254,16 @@ impl MontContext {
             });
         }
 
+        let mut ops = txn.into_ops();
+        qualify_references(&inner.graph, &mut ops);
+        assign_identities(&inner.graph, &mut ops)?;
+
         // Build validation view and validate
-        let view = ValidationView::new(&inner.graph, txn.ops());
+        let view = ValidationView::new(&inner.graph, &ops);
         validations::validate_view(&view)?;
 
         // Apply changes to the graph
-        for op in txn.into_ops() {
+        for op in ops {
             match op {
                 Op::Insert(task) => {
                     inner.graph.insert(task);
@@ -199,6 +280,7 @@ impl MontContext {
             }
         }
 
+        inner.graph.canonicalize_references();
         inner.version += 1;
 
         // Save to disk
@@ -318,7 +400,7 @@ impl MontContext {
         // Generate ID if empty, or check for duplicates
         if task.id.is_empty() {
             task.id = self.generate_id(&self.graph())?;
-        } else if self.graph().contains(&task.id) {
+        } else if self.graph().resolve(&task.id).is_some() || self.graph().is_archived(&task.id) {
             return Err(TransactionError::TaskAlreadyExists(task.id));
         }
 
@@ -334,8 +416,9 @@ impl MontContext {
     /// The `old_id` identifies the task to update. The `task` contains the new
     /// state (including potentially a new ID in `task.id`).
     ///
-    /// If the ID changes, all references to the old ID in other tasks are
-    /// automatically updated (before, after, and validations fields).
+    /// If the ID changes, the old ID is kept as an alias of the task, so
+    /// references to it in other tasks still resolve and their files are left
+    /// untouched.
     pub fn update(&self, old_id: &str, task: Task) -> Result<(), TransactionError> {
         let graph = self.graph();
 
@@ -344,16 +427,11 @@ impl MontContext {
         }
 
         let new_id = &task.id;
-        let id_changed = old_id != new_id;
-
-        if id_changed && graph.contains(new_id) {
+        if graph.resolve(new_id).is_some_and(|t| t.id != old_id) || graph.is_archived(new_id) {
             return Err(TransactionError::TaskAlreadyExists(new_id.clone()));
         }
 
         let mut txn = self.begin();
-        if id_changed {
-            txn.rewrite_references(&*graph, old_id, Some(new_id));
-        }
         txn.update(old_id, task);
         drop(graph);
         self.commit(txn)
@@ -365,7 +443,8 @@ impl MontContext {
 
         for _ in 0..MAX_ATTEMPTS {
             if let Some(candidate) = petname::petname(2, "-")
-                && !graph.contains(&candidate)
+                && graph.resolve(&candidate).is_none()
+                && !graph.is_archived(&candidate)
             {
                 return Ok(candidate);
             }
@@ -374,11 +453,77 @@ impl MontContext {
         Err(TransactionError::IdGenerationFailed(MAX_ATTEMPTS))
     }
 
+    /// Move finished task groups into `.tasks/archive`.
+    ///
+    /// With `older_than`, only tasks completed or cancelled at least that long
+    /// ago are moved, going by the time recorded on the task (see
+    /// `Task::closed_at`); tasks with no recorded time stay. Files that refer
+    /// to an archived task by uid or alias are rewritten to use its id.
+    /// Returns the archived ids.
+    #[allow(clippy::expect_used)] // RwLock poisoning is a bug
+    pub fn archive(&self, older_than: Option<Duration>) -> Result<Vec<String>, TransactionError> {
+        let mut inner = self.inner.write().expect("lock poisoned");
+
+        let now = Utc::now();
+        let is_old = |task: &Task| {
+            older_than.is_none_or(|age| {
+                task.closed_at()
+                    .and_then(|at| (now - at).to_std().ok())
+                    .is_some_and(|elapsed| elapsed >= age)
+            })
+        };
+        let ids = archive::archivable(&inner.graph, is_old);
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+
+        let archive_dir = archive::archive_dir(&self.tasks_dir);
+        for id in &ids {
+            let Some(task) = inner.graph.get(id) else { continue };
+            let path = archive_dir.join(format!("{}.md", id));
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let graph = &inner.graph;
+            let exists = |key: &str| graph.contains(key) || graph.is_archived(key);
+            std::fs::write(&path, namespace::localize(task, exists).to_markdown())?;
+        }
+
+        // Only ids are kept for archived tasks, so files that reach one by
+        // its uid or an alias are rewritten to use its id
+        let rewrite: Vec<String> = ids
+            .iter()
+            .flat_map(|id| inner.graph.referencing(id))
+            .filter(|t| !ids.contains(&t.id) && inner.graph.refers_by_key(&t.id))
+            .map(|t| t.id.clone())
+            .collect();
+        for id in &rewrite {
+            inner.graph.mark_dirty(id);
+        }
+
+        for id in &ids {
+            inner.graph.archive(id);
+        }
+        inner.version += 1;
+        self.save_inner(&mut inner.graph)?;
+
+        Ok(ids)
+    }
+
+    /// Read the tasks in `.tasks/archive`.
+    pub fn archived_tasks(&self) -> Result<Vec<Task>, LoadError> {
+        archive::load(&self.tasks_dir, &self.graph()).map_err(LoadError::Graph)
+    }
+
     /// Save dirty tasks to disk.
     fn save_inner(&self, graph: &mut TaskGraph) -> Result<usize, std::io::Error> {
-        let dirty_tasks: Vec<_> = graph.dirty_tasks().into_iter().cloned().collect();
+        let mut dirty_tasks: Vec<_> = graph.dirty_tasks().into_iter().cloned().collect();
         let count = dirty_tasks.len();
 
+        // Write before removing: a renamed task may still need to read its
+        // old file to preserve its formatting
+        dirty_tasks.sort_by_key(Task::is_deleted);
+
         for task in dirty_tasks {
             let path = self.tasks_dir.join(format!("{}.md", task.id));
             if task.is_deleted() {
@@ -388,8 +533,18 @@ impl MontContext {
                 {
                     return Err(e);
                 }
+                // Drop the namespace directory once it's empty
+                if task.namespace().is_some()
+                    && let Some(dir) = path.parent()
+                {
+                    let _ = std::fs::remove_dir(dir);
+                }
             } else {
-                let content = task.to_markdown();
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let exists = |key: &str| graph.get(key).is_some_and(|t| !t.is_deleted());
+                let content = namespace::localize(&task, exists).to_markdown();
                 std::fs::write(&path, content)?;
             }
         }
@@ -399,6 +554,66 @@ impl MontContext {
     }
 }
 
+/// Resolve namespace-relative references in the tasks being written, against
+/// both the graph and the other tasks in the transaction.
+fn qualify_references(graph: &TaskGraph, ops: &mut [Op]) {
+    let pending: HashSet<String> = ops
+        .iter()
+        .filter_map(|op| match op {
+            Op::Insert(task) | Op::Update { task, .. } => Some(task.id.clone()),
+            Op::Delete(_) => None,
+        })
+        .collect();
+    let exists = |key: &str| pending.contains(key) || graph.get(key).is_some_and(|t| !t.is_deleted());
+
+    for op in ops {
+        if let Op::Insert(task) | Op::Update { task, .. } = op {
+            namespace::qualify_references(task, exists);
+        }
+    }
+}
+
+/// Give inserted tasks a uid and a creation time, carry both over on updates,
+/// and record the old id of a renamed task as an alias.
+fn assign_identities(graph: &TaskGraph, ops: &mut [Op]) -> Result<(), ValidationError> {
+    let now = Utc::now();
+    for op in ops {
+        match op {
+            Op::Insert(task) => {
+                task.uid.get_or_insert_with(new_uid);
+                task.created.get_or_insert(now);
+            }
+            Op::Update { old_id, task } => {
+                let existing = graph.get(old_id).and_then(|t| t.uid.clone());
+                match (&task.uid, existing) {
+                    (Some(uid), Some(existing)) if *uid != existing => {
+                        return Err(ValidationError::UidChanged { task_id: old_id.clone() });
+                    }
+                    (None, existing) => task.uid = Some(existing.unwrap_or_else(new_uid)),
+                    _ => {}
+                }
+                if task.created.is_none() {
+                    task.created = graph.get(old_id).and_then(|t| t.created);
+                }
+
+                if *old_id != task.id {
+                    if !task.aliases.contains(old_id) {
+                        task.aliases.push(old_id.clone());
+                    }
+                    let id = task.id.clone();
+                    task.aliases.retain(|alias| *alias != id);
+                }
+            }
+            Op::Delete(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn new_uid() -> String {
+    ulid::Ulid::new().to_string()
+}
+
 /// Errors that can occur during MontContext operations.
 #[derive(Debug, thiserror::Error)]
 pub enum TransactionError {
@@ -429,15 +644,8 @@ mod tests {
     fn make_task(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
             title: Some(format!("{} title", id)),
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
@@ -684,6 +892,144 @@ mod tests {
         assert_eq!(task3.after, vec!["renamed-task".to_string()]);
     }
 
+    #[test]
+    fn test_uid_is_assigned_and_immutable() {
+        let temp_dir = TempDir::new().unwrap();
+        let ctx = MontContext::new(temp_dir.path().to_path_buf());
+        ctx.insert(make_task("task1")).unwrap();
+
+        let uid = ctx.graph().get("task1").unwrap().uid.clone().unwrap();
+        assert_eq!(uid.len(), 26);
+        let created = ctx.graph().get("task1").unwrap().created;
+        assert!(created.is_some());
+
+        // A missing uid or creation time is carried over from the stored task
+        ctx.update("task1", make_task("task1")).unwrap();
+        assert_eq!(ctx.graph().get("task1").unwrap().uid.as_deref(), Some(uid.as_str()));
+        assert_eq!(ctx.graph().get("task1").unwrap().created, created);
+
+        let mut changed = make_task("task1");
+        changed.uid = Some("01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string());
+        assert!(matches!(
+            ctx.update("task1", changed),
+            Err(TransactionError::Validation(ValidationError::UidChanged { .. }))
+        ));
+
+        // References may use the uid; they're stored under the id in memory
+        let mut task2 = make_task("task2");
+        task2.after = vec![uid.clone()];
+        ctx.insert(task2).unwrap();
+        assert_eq!(ctx.graph().get("task2").unwrap().after, vec!["task1"]);
+
+        // Old ids stay reserved for the renamed task
+        ctx.update("task1", make_task("renamed")).unwrap();
+        assert!(matches!(
+            ctx.insert(make_task("task1")),
+            Err(TransactionError::TaskAlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_namespaces_load_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("backend")).unwrap();
+        std::fs::write(root.join("backend/db.md"), "---\nid: db\n---\n").unwrap();
+        std::fs::write(root.join("backend/auth.md"), "---\nid: auth\nafter:\n  - db\n---\n").unwrap();
+        std::fs::write(root.join("top.md"), "---\nid: top\nafter:\n  - backend/auth\n---\n").unwrap();
+
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        {
+            let graph = ctx.graph();
+            assert!(graph.contains("backend/db"));
+            assert_eq!(graph.get("backend/auth").unwrap().after, vec!["backend/db"]);
+            assert_eq!(graph.get("top").unwrap().after, vec!["backend/auth"]);
+        }
+
+        // Tasks are written back to their directory in local form
+        let mut auth = ctx.graph().get("backend/auth").unwrap().clone();
+        auth.title = Some("Auth".to_string());
+        ctx.update("backend/auth", auth).unwrap();
+        let content = std::fs::read_to_string(root.join("backend/auth.md")).unwrap();
+        assert!(content.contains("id: auth\n"));
+        assert!(content.contains("  - db\n"));
+
+        // New namespaced tasks get their directory created
+        let mut page = make_task("frontend/page");
+        page.after = vec!["backend/auth".to_string()];
+        ctx.insert(page).unwrap();
+        let content = std::fs::read_to_string(root.join("frontend/page.md")).unwrap();
+        assert!(content.contains("id: page\n"));
+        assert!(content.contains("  - backend/auth\n"));
+
+        ctx.delete("frontend/page").unwrap();
+        assert!(!root.join("frontend").exists());
+    }
+
+    #[test]
+    fn test_archive_moves_finished_groups() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let ctx = MontContext::new(root.to_path_buf());
+
+        let mut done = make_task("done");
+        done.complete(Utc::now() - chrono::Duration::hours(2));
+        let mut recent = make_task("recent");
+        recent.complete(Utc::now());
+        let mut unrecorded = make_task("unrecorded");
+        unrecorded.status = Some(Status::Complete);
+        let mut next = make_task("next");
+        next.after = vec!["done".to_string()];
+        for task in [done, recent, unrecorded, next] {
+            ctx.insert(task).unwrap();
+        }
+
+        // Age goes by the recorded completion time, not the file
+        assert_eq!(ctx.archive(Some(Duration::from_secs(3600))).unwrap(), vec!["done"]);
+        assert!(ctx.archive(Some(Duration::from_secs(3600))).unwrap().is_empty());
+
+        assert_eq!(ctx.archive(None).unwrap(), vec!["recent", "unrecorded"]);
+        assert!(!root.join("done.md").exists());
+        assert!(root.join("archive/done.md").exists());
+
+        // References to archived tasks stay valid after a reload
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        assert!(!ctx.graph().contains("done"));
+        assert!(ctx.graph().is_archived("done"));
+        assert_eq!(ctx.graph().get("next").unwrap().after, vec!["done"]);
+        assert!(graph::is_available(ctx.graph().get("next").unwrap(), &*ctx.graph()));
+        assert!(matches!(ctx.insert(make_task("done")), Err(TransactionError::TaskAlreadyExists(_))));
+
+        let archived = ctx.archived_tasks().unwrap();
+        assert_eq!(archived.len(), 3);
+        assert!(archived.iter().all(Task::is_complete));
+    }
+
+    #[test]
+    fn test_archive_rewrites_references_by_uid_or_alias() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let done = Utc::now().to_rfc3339();
+        std::fs::write(
+            root.join("new-a.md"),
+            format!("---\nid: new-a\naliases: [old-a]\nstatus: complete\ncompleted: {}\n---\n", done),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("c.md"),
+            format!("---\nid: c\nuid: 01J000000000000000000000CC\nstatus: complete\ncompleted: {}\n---\n", done),
+        )
+        .unwrap();
+        std::fs::write(root.join("b.md"), "---\nid: b\nafter: [old-a, 01J000000000000000000000CC]\n---\n").unwrap();
+
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        assert_eq!(ctx.archive(None).unwrap(), vec!["c", "new-a"]);
+
+        let ctx = MontContext::load(root.to_path_buf()).unwrap();
+        assert_eq!(ctx.graph().get("b").unwrap().after, vec!["new-a", "c"]);
+        assert!(graph::is_available(ctx.graph().get("b").unwrap(), &*ctx.graph()));
+    }
+
     #[test]
     fn test_update_nonexistent_task() {
         let temp_dir = TempDir::new().unwrap();
@@ -715,4 +1061,20 @@ mod tests {
             Err(TransactionError::TaskAlreadyExists(ref id)) if id == "task2"
         ));
     }
+
+    #[test]
+    fn test_load_refuses_older_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        std::fs::write(dir.join("config.yml"), "default_gates: []\n").unwrap();
+        let original = "---\nid: task\nvalidations: []\n---\n";
+        std::fs::write(dir.join("task.md"), original).unwrap();
+
+        // Nothing is rewritten until `mont migrate` is run
+        assert!(matches!(
+            MontContext::load(dir.to_path_buf()),
+            Err(LoadError::Format(MigrateError::Outdated { found: 1, .. }))
+        ));
+        assert_eq!(std::fs::read_to_string(dir.join("task.md")).unwrap(), original);
+    }
 }
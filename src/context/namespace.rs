@@ -0,0 +1,165 @@
+//! Namespaces: subdirectories of `.tasks`.
+//!
+//! A task stored in `.tasks/backend/auth.md` has the id `backend/auth` in
+//! memory, but its file just says `id: auth`. References in that file are
+//! relative to the namespace first: `after: [db]` means `backend/db` if such a
+//! task exists and the top-level `db` otherwise. A leading `/` (`/db`) always
+//! means the top-level task. Tasks are written back in the same local form,
+//! so a file never needs to repeat its own namespace.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use super::task::Task;
+
+/// Find the task files under `dir`, descending into subdirectories.
+///
+/// Hidden files and directories are skipped. On failure, returns the path
+/// that couldn't be read along with the error.
+pub(crate) fn task_files(dir: &Path) -> Result<Vec<PathBuf>, (PathBuf, std::io::Error)> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| (dir.clone(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| (dir.clone(), e))?;
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type().map_err(|e| (path.clone(), e))?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "md") {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The namespace implied by where a task file sits under `tasks_dir`.
+pub(crate) fn namespace_of(tasks_dir: &Path, path: &Path) -> Option<String> {
+    let dir = path.parent()?.strip_prefix(tasks_dir).ok()?;
+    let ns = dir
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!ns.is_empty()).then_some(ns)
+}
+
+/// Give a task loaded from namespace `ns` its full id.
+pub(crate) fn qualify_id(task: &mut Task, ns: &str) {
+    let prefix = format!("{}/", ns);
+    if !task.id.starts_with(&prefix) {
+        task.id = format!("{}{}", prefix, task.id);
+    }
+}
+
+/// Turn the references a task was written with into full ids.
+///
+/// `exists` says whether a full id names a task; it decides whether a bare
+/// reference points into the task's own namespace.
+pub(crate) fn qualify_references(task: &mut Task, exists: impl Fn(&str) -> bool) {
+    let ns = task.namespace().map(str::to_string);
+    let qualify = |key: &mut String| {
+        if let Some(absolute) = key.strip_prefix('/') {
+            *key = absolute.to_string();
+        } else if let Some(ns) = &ns {
+            let qualified = format!("{}/{}", ns, key);
+            if exists(&qualified) {
+                *key = qualified;
+            }
+        }
+    };
+
+    task.before.iter_mut().for_each(qualify);
+    task.after.iter_mut().for_each(qualify);
+    task.gates.iter_mut().for_each(|g| qualify(&mut g.id));
+}
+
+/// The form a task is written to disk in: its local id, with references
+/// into its own namespace made relative.
+///
+/// A top-level reference that a relative one would shadow gets a leading `/`.
+pub(crate) fn localize(task: &Task, exists: impl Fn(&str) -> bool) -> Cow<'_, Task> {
+    let Some(ns) = task.namespace() else {
+        return Cow::Borrowed(task);
+    };
+
+    let prefix = format!("{}/", ns);
+    let localize = |key: &mut String| {
+        if let Some(relative) = key.strip_prefix(&prefix) {
+            *key = relative.to_string();
+        } else if exists(&format!("{}{}", prefix, key)) {
+            *key = format!("/{}", key);
+        }
+    };
+
+    let mut local = task.clone();
+    local.id = task.id[prefix.len()..].to_string();
+    local.before.iter_mut().for_each(localize);
+    local.after.iter_mut().for_each(localize);
+    local.gates.iter_mut().for_each(|g| localize(&mut g.id));
+    Cow::Owned(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::task::parse;
+
+    fn task(content: &str) -> Task {
+        parse(content).unwrap()
+    }
+
+    #[test]
+    fn test_namespace_of() {
+        let root = Path::new("/repo/.tasks");
+        assert_eq!(namespace_of(root, &root.join("a.md")), None);
+        assert_eq!(namespace_of(root, &root.join("backend/a.md")).as_deref(), Some("backend"));
+        assert_eq!(
+            namespace_of(root, &root.join("backend/api/a.md")).as_deref(),
+            Some("backend/api")
+        );
+    }
+
+    #[test]
+    fn test_qualify_prefers_own_namespace() {
+        let mut t = task("---\nid: auth\nafter:\n  - db\n  - config\n  - /cache\n---\n");
+        qualify_id(&mut t, "backend");
+        let ids = ["backend/db", "db", "config", "backend/cache", "cache"];
+        qualify_references(&mut t, |k| ids.contains(&k));
+
+        assert_eq!(t.id, "backend/auth");
+        assert_eq!(t.after, vec!["backend/db", "config", "cache"]);
+    }
+
+    #[test]
+    fn test_localize_round_trips() {
+        let content = "---\nid: auth\nafter:\n  - db\n  - config\n  - /cache\n---\n";
+        let mut t = task(content);
+        qualify_id(&mut t, "backend");
+        let ids = ["backend/db", "config", "backend/cache", "cache"];
+        qualify_references(&mut t, |k| ids.contains(&k));
+
+        let local = localize(&t, |k| ids.contains(&k));
+        assert_eq!(local.id, "auth");
+        assert_eq!(local.after, vec!["db", "config", "/cache"]);
+        assert_eq!(local.to_markdown(), content);
+    }
+
+    #[test]
+    fn test_in_namespace() {
+        let t = task("---\nid: backend/api/auth\n---\n");
+        assert_eq!(t.namespace(), Some("backend/api"));
+        assert!(t.in_namespace("backend"));
+        assert!(t.in_namespace("backend/api/"));
+        assert!(!t.in_namespace("back"));
+        assert!(!t.in_namespace("backend/api/auth"));
+    }
+}
@@ -0,0 +1,391 @@
+//! Query expressions for selecting tasks.
+//!
+//! A query is a list of terms that must all match, e.g.
+//! `status:ready type:task tag:api after:auth-backend title~"login"`.
+//!
+//! - `key:value` compares a field; `key~text` searches it, ignoring case.
+//! - A leading `-` negates a term: `-tag:docs`.
+//! - Values containing spaces go in double quotes.
+//! - A bare word searches ids and titles, like `id~word`.
+//!
+//! Queries are evaluated over a `GraphView`, so they work on the committed
+//! graph and on a `ValidationView` of changes that haven't been committed.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::graph::is_available;
+use super::task::{Status, Task, TaskType};
+use super::view::GraphView;
+
+/// Keys a query term can use.
+const KEYS: &str = "id, status, type, tag, assignee, milestone, ns, after, before, gate, title, desc";
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum QueryError {
+    #[error("empty query")]
+    Empty,
+    #[error("unknown query key '{0}' (expected one of: {KEYS})")]
+    UnknownKey(String),
+    #[error("'{key}' has no value")]
+    MissingValue { key: String },
+    #[error("unterminated quote in query")]
+    UnterminatedQuote,
+    #[error("invalid value '{value}' for '{key}' (expected one of: {expected})")]
+    InvalidValue {
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+    #[error("'{key}' can't be searched with '~'; use '{key}:'")]
+    NotSearchable { key: String },
+    #[error("'{key}' can only be searched; use '{key}~'")]
+    SearchOnly { key: String },
+}
+
+/// A parsed query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negated: bool,
+    filter: Filter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    /// Id pattern, where `*` matches any run of characters
+    Id(String),
+    Status(StatusFilter),
+    Type(TaskType),
+    Tag(String),
+    /// `None` matches tasks without an assignee
+    Assignee(Option<String>),
+    Milestone(Option<String>),
+    Namespace(String),
+    After(String),
+    Before(String),
+    Gate(String),
+    Title(String),
+    /// Case-insensitive substring of the id, title or description
+    Search { id: bool, title: bool, description: bool, text: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatusFilter {
+    /// Available to work on (see `graph::is_available`)
+    Ready,
+    /// No explicit status
+    Pending,
+    Is(Status),
+    Open,
+    Closed,
+}
+
+impl Query {
+    /// Parse a query expression.
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let terms = split_terms(input)?
+            .into_iter()
+            .map(|term| parse_term(&term))
+            .collect::<Result<Vec<_>, _>>()?;
+        if terms.is_empty() {
+            return Err(QueryError::Empty);
+        }
+        Ok(Self { terms })
+    }
+
+    /// Whether `task` matches every term. Deleted tasks never match.
+    pub fn matches<V: GraphView>(&self, task: &Task, view: &V) -> bool {
+        !task.is_deleted() && self.terms.iter().all(|term| term.filter.matches(task, view) != term.negated)
+    }
+
+    /// The tasks in `view` that match, in id order.
+    pub fn select<'a, V: GraphView>(&self, view: &'a V) -> Vec<&'a Task> {
+        let mut tasks: Vec<&Task> = view.values().filter(|t| self.matches(t, view)).collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        tasks
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Whether a task argument is a query rather than a task.
+///
+/// An argument that names a task in `view` (by id, uid or alias) is that task,
+/// even if it looks like a query; otherwise anything containing `:`, `~` or
+/// whitespace is a query.
+pub fn is_query<V: GraphView>(arg: &str, view: &V) -> bool {
+    (arg.contains([':', '~']) || arg.contains(char::is_whitespace)) && view.resolve(arg).is_none()
+}
+
+impl Filter {
+    fn matches<V: GraphView>(&self, task: &Task, view: &V) -> bool {
+        // Resolve a referenced key so uids and aliases match too
+        let resolve = |key: &str| view.resolve(key).map_or(key.to_string(), |t| t.id.clone());
+        let references = |refs: &[String], key: &str| {
+            let target = resolve(key);
+            refs.iter().any(|r| resolve(r) == target)
+        };
+
+        match self {
+            Filter::Id(pattern) => glob_match(pattern, &task.id) || (!pattern.contains('*') && task.has_key(pattern)),
+            Filter::Status(status) => match status {
+                StatusFilter::Ready => {
+                    !task.is_closed()
+                        && !task.is_gate()
+                        && !task.is_epic()
+                        && !task.is_blocked()
+                        && is_available(task, view)
+                }
+                StatusFilter::Pending => task.status.is_none() && !task.is_gate(),
+                StatusFilter::Is(status) => task.status == Some(*status),
+                StatusFilter::Open => !task.is_closed(),
+                StatusFilter::Closed => task.is_closed(),
+            },
+            Filter::Type(task_type) => task.task_type == *task_type,
+            Filter::Tag(tag) => task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            Filter::Assignee(assignee) => match assignee {
+                Some(assignee) => task.is_assigned_to(assignee),
+                None => task.assignee.is_none(),
+            },
+            Filter::Milestone(milestone) => task.milestone == *milestone,
+            Filter::Namespace(ns) => task.in_namespace(ns),
+            Filter::After(key) => references(&task.after, key),
+            Filter::Before(key) => references(&task.before, key),
+            Filter::Gate(key) => {
+                let target = resolve(key);
+                task.gate_ids().any(|g| resolve(g) == target)
+            }
+            Filter::Title(title) => task.title.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(title)),
+            Filter::Search { id, title, description, text } => {
+                let text = text.to_lowercase();
+                let found = |s: &str| s.to_lowercase().contains(&text);
+                (*id && found(&task.id))
+                    || (*title && task.title.as_deref().is_some_and(found))
+                    || (*description && found(&task.description))
+            }
+        }
+    }
+}
+
+/// Split a query into terms on whitespace, keeping quoted values together
+/// and dropping the quotes.
+fn split_terms(input: &str) -> Result<Vec<String>, QueryError> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    terms.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(QueryError::UnterminatedQuote);
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+    Ok(terms)
+}
+
+fn parse_term(term: &str) -> Result<Term, QueryError> {
+    let (negated, term) = match term.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, term),
+    };
+
+    let Some(split) = term.find([':', '~']) else {
+        let filter = Filter::Search { id: true, title: true, description: false, text: term.to_string() };
+        return Ok(Term { negated, filter });
+    };
+    let (key, rest) = term.split_at(split);
+    let search = rest.starts_with('~');
+    let value = &rest[1..];
+    if value.is_empty() {
+        return Err(QueryError::MissingValue { key: key.to_string() });
+    }
+
+    let invalid = |expected| QueryError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+        expected,
+    };
+    let optional = |value: &str| (value != "none").then(|| value.to_string());
+
+    let filter = match (key, search) {
+        ("id", false) => Filter::Id(value.to_string()),
+        ("id", true) => Filter::Search { id: true, title: false, description: false, text: value.to_string() },
+        ("title", false) => Filter::Title(value.to_string()),
+        ("title", true) => Filter::Search { id: false, title: true, description: false, text: value.to_string() },
+        ("desc" | "description", true) => {
+            Filter::Search { id: false, title: false, description: true, text: value.to_string() }
+        }
+        ("status", false) => Filter::Status(match value {
+            "ready" => StatusFilter::Ready,
+            "pending" => StatusFilter::Pending,
+            "inprogress" => StatusFilter::Is(Status::InProgress),
+            "stopped" => StatusFilter::Is(Status::Stopped),
+            "blocked" => StatusFilter::Is(Status::Blocked),
+            "complete" | "done" => StatusFilter::Is(Status::Complete),
+            "cancelled" => StatusFilter::Is(Status::Cancelled),
+            "open" => StatusFilter::Open,
+            "closed" => StatusFilter::Closed,
+            _ => {
+                return Err(invalid(
+                    "ready, pending, inprogress, stopped, blocked, complete, cancelled, open, closed",
+                ));
+            }
+        }),
+        ("type", false) => Filter::Type(match value {
+            "task" => TaskType::Task,
+            "jot" => TaskType::Jot,
+            "gate" => TaskType::Gate,
+            "epic" => TaskType::Epic,
+            _ => return Err(invalid("task, jot, gate, epic")),
+        }),
+        ("tag", false) => Filter::Tag(value.to_string()),
+        ("assignee", false) => Filter::Assignee(optional(value)),
+        ("milestone", false) => Filter::Milestone(optional(value)),
+        ("ns", false) => Filter::Namespace(value.to_string()),
+        ("after", false) => Filter::After(value.to_string()),
+        ("before", false) => Filter::Before(value.to_string()),
+        ("gate", false) => Filter::Gate(value.to_string()),
+        (
+            "status" | "type" | "tag" | "assignee" | "milestone" | "ns" | "after" | "before" | "gate",
+            true,
+        ) => {
+            return Err(QueryError::NotSearchable { key: key.to_string() });
+        }
+        ("desc" | "description", false) => return Err(QueryError::SearchOnly { key: key.to_string() }),
+        _ => return Err(QueryError::UnknownKey(key.to_string())),
+    };
+    Ok(Term { negated, filter })
+}
+
+/// Match `text` against `pattern`, where `*` matches any run of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` at all: the whole text must match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::graph::TaskGraph;
+    use crate::context::task::parse;
+    use crate::context::transaction::Op;
+    use crate::context::view::ValidationView;
+
+    fn graph() -> TaskGraph {
+        [
+            "---\nid: auth-backend\ntitle: Auth backend\nstatus: complete\n---\n",
+            "---\nid: login-ui\ntitle: Login page\ntags: [api, ui]\nafter: [auth-backend]\n---\n\nBuild the form.\n",
+            "---\nid: login-api\ntitle: Login endpoint\ntags: [api]\nassignee: alice\nafter: [auth-backend, login-ui]\n---\n",
+            "---\nid: docs\ntitle: Write docs\ntype: jot\n---\n",
+        ]
+        .into_iter()
+        .map(|content| parse(content).unwrap())
+        .collect()
+    }
+
+    fn select(query: &str, view: &impl GraphView) -> Vec<String> {
+        Query::parse(query).unwrap().select(view).into_iter().map(|t| t.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_query_terms() {
+        let graph = graph();
+        assert_eq!(select("status:ready type:task tag:api after:auth-backend title~\"login\"", &graph), vec!["login-ui"]);
+        assert_eq!(select("tag:api", &graph), vec!["login-api", "login-ui"]);
+        assert_eq!(select("tag:api -assignee:alice", &graph), vec!["login-ui"]);
+        assert_eq!(select("assignee:none status:open", &graph), vec!["docs", "login-ui"]);
+        assert_eq!(select("id:login-*", &graph), vec!["login-api", "login-ui"]);
+        assert_eq!(select("desc~FORM", &graph), vec!["login-ui"]);
+        assert_eq!(select("status:done", &graph), vec!["auth-backend"]);
+        assert_eq!(select("endpoint", &graph), vec!["login-api"]);
+    }
+
+    #[test]
+    fn test_query_over_uncommitted_changes() {
+        let graph = graph();
+        let mut ui = graph.get("login-ui").unwrap().clone();
+        ui.status = Some(Status::Complete);
+        let ops = vec![Op::Update { old_id: ui.id.clone(), task: ui }];
+        let view = ValidationView::new(&graph, &ops);
+
+        assert_eq!(select("status:ready type:task", &graph), vec!["login-ui"]);
+        assert_eq!(select("status:ready type:task", &view), vec!["login-api"]);
+    }
+
+    #[test]
+    fn test_query_errors() {
+        assert_eq!(Query::parse("  "), Err(QueryError::Empty));
+        assert_eq!(Query::parse("colour:red"), Err(QueryError::UnknownKey("colour".to_string())));
+        assert_eq!(Query::parse("tag:"), Err(QueryError::MissingValue { key: "tag".to_string() }));
+        assert_eq!(Query::parse("title~\"login"), Err(QueryError::UnterminatedQuote));
+        assert!(matches!(Query::parse("status:later"), Err(QueryError::InvalidValue { .. })));
+        assert!(matches!(Query::parse("tag~api"), Err(QueryError::NotSearchable { .. })));
+        assert_eq!(Query::parse("desc:login"), Err(QueryError::SearchOnly { key: "desc".to_string() }));
+    }
+
+    #[test]
+    fn test_is_query() {
+        let graph: TaskGraph = [parse("---\nid: mont llm claude\naliases: [\"old:name\"]\n---\n").unwrap()]
+            .into_iter()
+            .collect();
+        assert!(is_query("tag:api", &graph));
+        assert!(is_query("title~login", &graph));
+        assert!(is_query("docs login", &graph));
+        assert!(!is_query("backend/login-ui", &graph));
+
+        // Existing tasks are never read as queries
+        assert!(!is_query("mont llm claude", &graph));
+        assert!(!is_query("old:name", &graph));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("login-*", "login-ui"));
+        assert!(glob_match("*-ui", "login-ui"));
+        assert!(glob_match("l*n*i", "login-ui"));
+        assert!(glob_match("login-ui", "login-ui"));
+        assert!(!glob_match("login", "login-ui"));
+        assert!(!glob_match("*-api", "login-ui"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Simulated scheduling: how the open work plays out across parallel workers.
+//!
+//! `plan` hands ready tasks to free workers the way `mont ready` would list
+//! them, lets them finish after their estimate, and repeats until nothing more
+//! can start. Ready tasks are picked in-progress first, then by the longest
+//! chain of work still waiting below them, so the critical path starts early.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use super::effort::Effort;
+use super::graph::{available_tasks, finished_epics};
+use super::task::{Status, Task};
+use super::TaskGraph;
+
+/// Tasks that start at the same moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wave {
+    /// Time from the start of the plan
+    pub start: Effort,
+    /// Task ids, in the order they were handed out
+    pub tasks: Vec<String>,
+}
+
+/// The outcome of a simulated schedule; see `plan`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Plan {
+    pub waves: Vec<Wave>,
+    /// When the last task finishes
+    pub finish: Effort,
+    /// Sum of the durations of every scheduled task
+    pub work: Effort,
+    /// Most tasks running at once
+    pub peak: usize,
+    /// Scheduled tasks that had no estimate
+    pub unestimated: usize,
+    /// Open tasks that never became ready: blocked ones and the work waiting
+    /// on them, in id order
+    pub unschedulable: Vec<String>,
+}
+
+/// Simulate greedy scheduling of the open tasks across `workers` workers.
+///
+/// Tasks take their estimate, or `default_estimate` when they have none.
+/// Epics aren't scheduled; they finish as soon as their last task does.
+pub fn plan(graph: &TaskGraph, workers: usize, default_estimate: Effort) -> Plan {
+    let duration = |task: &Task| task.estimate.unwrap_or(default_estimate);
+    let ranks = ranks(graph, &duration);
+
+    let mut sim = graph.clone();
+    let mut plan = Plan::default();
+    let mut started: HashSet<String> = HashSet::new();
+    let mut running: Vec<(Effort, String)> = Vec::new();
+    let mut now = Effort::default();
+
+    loop {
+        let mut ready: Vec<&Task> = available_tasks(&sim)
+            .into_iter()
+            .filter(|t| !started.contains(&t.id))
+            .collect();
+        ready.sort_by_key(|t| (!t.is_in_progress(), Reverse(ranks.get(t.id.as_str()).copied()), t.id.clone()));
+
+        let free = workers.saturating_sub(running.len());
+        let mut wave = Vec::new();
+        for task in ready.into_iter().take(free) {
+            let length = duration(task);
+            plan.work = plan.work + length;
+            if task.estimate.is_none() {
+                plan.unestimated += 1;
+            }
+            started.insert(task.id.clone());
+            running.push((now + length, task.id.clone()));
+            wave.push(task.id.clone());
+        }
+        if !wave.is_empty() {
+            plan.waves.push(Wave { start: now, tasks: wave });
+        }
+        plan.peak = plan.peak.max(running.len());
+
+        // Move on to the next time a task finishes
+        let Some(next) = running.iter().map(|(end, _)| *end).min() else {
+            break;
+        };
+        now = next;
+        let (finished, still_running): (Vec<_>, Vec<_>) = running.into_iter().partition(|(end, _)| *end == now);
+        running = still_running;
+        for (_, id) in finished {
+            complete(&mut sim, &id);
+        }
+        loop {
+            let epics = finished_epics(&sim);
+            if epics.is_empty() {
+                break;
+            }
+            for id in epics {
+                complete(&mut sim, &id);
+            }
+        }
+    }
+
+    plan.finish = now;
+    plan.unschedulable = sim
+        .values()
+        .filter(|t| !t.is_deleted() && !t.is_closed() && !t.is_gate() && !t.is_epic())
+        .map(|t| t.id.clone())
+        .collect();
+    plan.unschedulable.sort();
+    plan
+}
+
+fn complete(graph: &mut TaskGraph, id: &str) {
+    if let Some(task) = graph.get(id) {
+        let mut task = task.clone();
+        task.status = Some(Status::Complete);
+        graph.insert(task);
+    }
+}
+
+/// For every open task, its own duration plus the longest chain of open work
+/// waiting on it.
+fn ranks<'a>(graph: &'a TaskGraph, duration: &dyn Fn(&Task) -> Effort) -> HashMap<&'a str, Effort> {
+    // The work waiting on a task comes after it in topological order, so
+    // walking the order backwards ranks it first
+    let mut ranks: HashMap<&str, Effort> = HashMap::new();
+    for id in graph.topological_order().into_iter().rev() {
+        let Some(task) = graph.get(id).filter(|t| !t.is_deleted() && !t.is_closed()) else {
+            continue;
+        };
+        let below = graph
+            .dependents(id)
+            .chain(task.before.iter().filter_map(|id| graph.get(id)))
+            .filter_map(|t| ranks.get(t.id.as_str()).copied())
+            .max()
+            .unwrap_or_default();
+        let own = if task.is_epic() { Effort::default() } else { duration(task) };
+        ranks.insert(task.id.as_str(), own + below);
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::task::parse;
+
+    fn graph(tasks: &[&str]) -> TaskGraph {
+        tasks.iter().map(|content| parse(content).unwrap()).collect()
+    }
+
+    fn hours(h: u64) -> Effort {
+        Effort::from_minutes(h * 60)
+    }
+
+    #[test]
+    fn test_plan_waves_and_finish() {
+        // a (2h) -> c (1h); b (1h) and d (1h) are independent; e is after a
+        // blocked task and never starts
+        let graph = graph(&[
+            "---\nid: a\nestimate: 2h\n---\n",
+            "---\nid: b\nestimate: 1h\n---\n",
+            "---\nid: c\nestimate: 1h\nafter: [a]\n---\n",
+            "---\nid: d\n---\n",
+            "---\nid: stuck\nstatus: blocked\nblocker:\n  reason: waiting\n---\n",
+            "---\nid: e\nafter: [stuck]\n---\n",
+        ]);
+
+        // Two workers: the long chain starts first, the rest fill in
+        let plan = plan(&graph, 2, hours(1));
+        let waves: Vec<(u64, Vec<&str>)> = plan
+            .waves
+            .iter()
+            .map(|w| (w.start.minutes() / 60, w.tasks.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(waves, vec![(0, vec!["a", "b"]), (1, vec!["d"]), (2, vec!["c"])]);
+        assert_eq!(plan.finish, hours(3));
+        assert_eq!(plan.work, hours(5));
+        assert_eq!(plan.peak, 2);
+        assert_eq!(plan.unestimated, 1);
+        assert_eq!(plan.unschedulable, vec!["e", "stuck"]);
+
+        // Enough workers to run everything that's ready at once
+        let plan = super::plan(&graph, 10, hours(1));
+        assert_eq!(plan.peak, 3);
+        assert_eq!(plan.finish, hours(3));
+
+        // One worker does everything in sequence
+        assert_eq!(super::plan(&graph, 1, hours(1)).finish, hours(5));
+    }
+
+    #[test]
+    fn test_plan_completes_epics() {
+        let graph = graph(&[
+            "---\nid: epic\ntype: epic\n---\n",
+            "---\nid: part\nbefore: [epic]\n---\n",
+            "---\nid: next\nafter: [epic]\n---\n",
+        ]);
+        let plan = plan(&graph, 2, hours(1));
+        assert_eq!(plan.waves.len(), 2);
+        assert_eq!(plan.finish, hours(2));
+        assert!(plan.unschedulable.is_empty());
+    }
+}
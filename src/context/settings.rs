@@ -5,8 +5,10 @@
 
 use std::path::Path;
 
+use chrono::NaiveDate;
 use serde::Deserialize;
 
+use super::lint::LintConfig;
 use super::TaskGraph;
 
 /// Configuration for jj (Jujutsu) VCS integration.
@@ -24,10 +26,24 @@ impl Default for JjConfig {
     }
 }
 
+/// A named release target that tasks can belong to via `milestone:`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Milestone {
+    pub name: String,
+    /// Target date (YYYY-MM-DD)
+    pub date: NaiveDate,
+}
+
 /// Global configuration loaded from `.tasks/config.yml`.
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct GlobalConfig {
+    /// On-disk format version of the `.tasks` directory (see `mont migrate`).
+    /// Missing in directories created before versioning.
+    #[serde(default)]
+    pub version: Option<u32>,
+
     /// Gate IDs that must pass for all tasks.
     #[serde(default)]
     pub default_gates: Vec<String>,
@@ -35,6 +51,24 @@ pub struct GlobalConfig {
     /// Configuration for jj VCS integration.
     #[serde(default)]
     pub jj: JjConfig,
+
+    /// When true, a cancelled task keeps blocking tasks that depend on it.
+    /// By default a cancelled dependency counts as satisfied.
+    #[serde(default)]
+    pub cancelled_blocks_dependents: bool,
+
+    /// Identity used for assignment (`mont mine`, `mont start --claim`).
+    /// Falls back to `$USER` when unset.
+    #[serde(default)]
+    pub user: Option<String>,
+
+    /// Milestones tasks can be assigned to, in planning order.
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+
+    /// Lint rules run by `mont check`.
+    #[serde(default)]
+    pub lint: LintConfig,
 }
 
 /// Errors that can occur when loading or validating settings.
@@ -51,6 +85,12 @@ pub enum SettingsError {
 
     #[error("default gate '{gate_id}' is not a gate (type: {actual_type})")]
     NotAGate { gate_id: String, actual_type: String },
+
+    #[error("task '{task_id}' belongs to milestone '{milestone}', which is not defined in config.yml")]
+    MilestoneNotFound { task_id: String, milestone: String },
+
+    #[error("milestone '{0}' is defined more than once")]
+    DuplicateMilestone(String),
 }
 
 impl GlobalConfig {
@@ -68,10 +108,42 @@ impl GlobalConfig {
         }
     }
 
+    /// Look up a milestone by name.
+    pub fn milestone(&self, name: &str) -> Option<&Milestone> {
+        self.milestones.iter().find(|m| m.name == name)
+    }
+
+    /// The current user: `user` from config, otherwise `$USER`.
+    pub fn current_user(&self) -> Option<String> {
+        self.user
+            .clone()
+            .or_else(|| std::env::var("USER").ok())
+            .filter(|u| !u.trim().is_empty())
+    }
+
     /// Validate the config against a task graph.
     ///
-    /// Ensures all default gates exist and are actually gates.
+    /// Ensures all default gates exist and are actually gates, and that every
+    /// milestone referenced by a task is defined exactly once.
     pub fn validate(&self, graph: &TaskGraph) -> Result<(), SettingsError> {
+        for (i, milestone) in self.milestones.iter().enumerate() {
+            if self.milestones[..i].iter().any(|m| m.name == milestone.name) {
+                return Err(SettingsError::DuplicateMilestone(milestone.name.clone()));
+            }
+        }
+        let mut tasks: Vec<_> = graph.values().collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        for task in tasks {
+            if let Some(milestone) = &task.milestone
+                && self.milestone(milestone).is_none()
+            {
+                return Err(SettingsError::MilestoneNotFound {
+                    task_id: task.id.clone(),
+                    milestone: milestone.clone(),
+                });
+            }
+        }
+
         for gate_id in &self.default_gates {
             match graph.get(gate_id) {
                 Some(task) => {
@@ -102,30 +174,15 @@ mod tests {
     fn make_gate(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
-            status: None,
             task_type: TaskType::Gate,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
     fn make_task(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
@@ -149,6 +206,12 @@ mod tests {
         assert_eq!(config.default_gates, vec!["test-gate", "lint-gate"]);
     }
 
+    #[test]
+    fn test_current_user_prefers_config() {
+        let config: GlobalConfig = serde_yaml::from_str("user: alice\n").unwrap();
+        assert_eq!(config.current_user().as_deref(), Some("alice"));
+    }
+
     #[test]
     fn test_load_empty_config() {
         let temp_dir = TempDir::new().unwrap();
@@ -219,4 +282,23 @@ mod tests {
         let err = GlobalConfig::load(&path).unwrap_err();
         assert!(matches!(err, SettingsError::Parse(_)));
     }
+
+    #[test]
+    fn test_validate_milestones() {
+        let config: GlobalConfig =
+            serde_yaml::from_str("milestones:\n  - name: v1\n    date: 2025-06-01\n").unwrap();
+        assert_eq!(config.milestone("v1").unwrap().date.to_string(), "2025-06-01");
+
+        let mut graph = TaskGraph::new();
+        let mut task = make_task("in-v1");
+        task.milestone = Some("v1".to_string());
+        graph.insert(task);
+        assert!(config.validate(&graph).is_ok());
+
+        let mut stray = make_task("in-v2");
+        stray.milestone = Some("v2".to_string());
+        graph.insert(stray);
+        let err = config.validate(&graph).unwrap_err();
+        assert!(matches!(err, SettingsError::MilestoneNotFound { milestone, .. } if milestone == "v2"));
+    }
 }
@@ -0,0 +1,137 @@
+//! Snapshots: the tasks in `.tasks` as they were at another revision.
+//!
+//! A snapshot is read from file contents handed in by the caller (e.g. from
+//! `jj file show`) rather than from disk. Files are parsed and namespaced the
+//! same way `MontContext::load` does it, but the result isn't validated: an
+//! old revision may well have been invalid, and it should still be comparable.
+//! Files in an older format are upgraded in memory first, going by the
+//! revision's own `config.yml`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::archive;
+use super::graph::GraphReadError;
+use super::migrate::upgrade_contents;
+use super::namespace;
+use super::task::{parse, ParseError, Task};
+use super::LoadError;
+
+/// The tasks in `.tasks` at some revision.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    /// Loaded tasks, with namespaced ids and references, in path order
+    pub tasks: Vec<Task>,
+    /// Ids of the tasks in `.tasks/archive`
+    pub archived: HashSet<String>,
+}
+
+impl Snapshot {
+    /// Build a snapshot from `(path, content)` pairs of the files under
+    /// `tasks_dir`, including `config.yml` if the revision has one. Other
+    /// files that aren't task files are skipped, and the contents of archived
+    /// tasks aren't looked at.
+    pub fn read(tasks_dir: &Path, files: Vec<(PathBuf, String)>) -> Result<Self, LoadError> {
+        let mut errors = GraphReadError::new();
+        let mut snapshot = Snapshot::default();
+        let archive_dir = archive::archive_dir(tasks_dir);
+        let config_path = tasks_dir.join("config.yml");
+
+        let mut config = None;
+        let mut tasks = BTreeMap::new();
+        for (path, content) in files {
+            if path == config_path {
+                config = Some(content);
+            } else if is_task_file(tasks_dir, &path) {
+                tasks.insert(path, content);
+            }
+        }
+        upgrade_contents(config.as_deref(), &mut tasks).map_err(LoadError::Format)?;
+
+        for (path, content) in tasks {
+            if path.starts_with(&archive_dir) {
+                snapshot.archived.extend(archive::archived_id(&archive_dir, &path));
+                continue;
+            }
+            let mut task = match parse(&content) {
+                Ok(task) if task.id.is_empty() => {
+                    errors.add_parse_error(path, ParseError::EmptyId);
+                    continue;
+                }
+                Ok(task) => task,
+                Err(e) => {
+                    errors.add_parse_error(path, e);
+                    continue;
+                }
+            };
+            if let Some(ns) = namespace::namespace_of(tasks_dir, &path) {
+                namespace::qualify_id(&mut task, &ns);
+            }
+            snapshot.tasks.push(task);
+        }
+
+        if !errors.is_empty() {
+            return Err(LoadError::Graph(errors));
+        }
+
+        let ids: HashSet<String> = snapshot.tasks.iter().map(|t| t.id.clone()).collect();
+        for task in &mut snapshot.tasks {
+            namespace::qualify_references(task, |key| ids.contains(key) || snapshot.archived.contains(key));
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Whether `path` is a task file under `tasks_dir`, by the same rules as
+/// `namespace::task_files`: a `.md` file with no hidden component.
+fn is_task_file(tasks_dir: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(tasks_dir) else {
+        return false;
+    };
+    path.extension().is_some_and(|ext| ext == "md")
+        && relative.components().all(|c| !c.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_from_files() {
+        let file = |path: &str, content: &str| (PathBuf::from(path), content.to_string());
+        let snapshot = Snapshot::read(
+            Path::new(".tasks"),
+            vec![
+                file(".tasks/config.yml", "version: 3\n"),
+                file(".tasks/.cache/tasks.json", "{}"),
+                file(".tasks/db.md", "---\nid: db\n---\n"),
+                file(".tasks/backend/api.md", "---\nid: api\nafter: [db, auth]\n---\n"),
+                file(".tasks/backend/auth.md", "---\nid: auth\n---\n"),
+                file(".tasks/archive/old.md", "---\nid: old\n---\n"),
+            ],
+        )
+        .unwrap();
+
+        let ids: Vec<&str> = snapshot.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["backend/api", "backend/auth", "db"]);
+        assert_eq!(snapshot.tasks[0].after, vec!["db", "backend/auth"]);
+        assert!(snapshot.archived.contains("old"));
+
+        let broken = Snapshot::read(Path::new(".tasks"), vec![file(".tasks/x.md", "no frontmatter")]);
+        assert!(broken.is_err());
+    }
+
+    #[test]
+    fn test_snapshot_upgrades_older_formats() {
+        let file = |path: &str, content: &str| (PathBuf::from(path), content.to_string());
+        let task = file(".tasks/old.md", "---\nid: old\nvalidations:\n  - review\n---\n");
+
+        // Format 1 called gates validations
+        let snapshot = Snapshot::read(Path::new(".tasks"), vec![file(".tasks/config.yml", "default_gates: []\n"), task.clone()]).unwrap();
+        assert_eq!(snapshot.tasks[0].gates.len(), 1);
+        assert_eq!(snapshot.tasks[0].uid, None);
+
+        let newer = Snapshot::read(Path::new(".tasks"), vec![file(".tasks/config.yml", "version: 99\n"), task]);
+        assert!(matches!(newer, Err(LoadError::Format(_))));
+    }
+}
@@ -1,62 +1,41 @@
-use serde::Deserialize;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize, Serializer};
 use thiserror::Error;
 
-/// Escape a string for safe YAML output.
-///
-/// Wraps the string in double quotes if it contains characters that could
-/// break YAML parsing (colons, quotes, brackets, etc.). Internal double
-/// quotes and backslashes are escaped.
-fn yaml_escape(s: &str) -> String {
-    // Characters that require quoting in YAML
-    let needs_quoting = s.contains(':')
-        || s.contains('#')
-        || s.contains('"')
-        || s.contains('\'')
-        || s.contains('[')
-        || s.contains(']')
-        || s.contains('{')
-        || s.contains('}')
-        || s.contains('&')
-        || s.contains('*')
-        || s.contains('!')
-        || s.contains('|')
-        || s.contains('>')
-        || s.contains('%')
-        || s.contains('@')
-        || s.contains('`')
-        || s.starts_with(' ')
-        || s.ends_with(' ')
-        || s.starts_with('-')
-        || s.starts_with('?');
-
-    if needs_quoting {
-        // Escape backslashes and double quotes, then wrap in double quotes
-        let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
-        format!("\"{}\"", escaped)
-    } else {
-        s.to_string()
-    }
-}
+use super::effort::{serialize_optional_timestamp, serialize_timestamp, Effort, Session};
+use super::frontmatter::{self, FrontmatterFormat, SourceText};
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskType {
     Jot,
     #[default]
     Task,
     Gate,
+    /// Groups the tasks that list it in `before`; completes when they do
+    Epic,
+}
+
+impl TaskType {
+    fn is_task(&self) -> bool {
+        *self == TaskType::Task
+    }
 }
 
 /// Task status - only stored statuses. "Ready" is computed from the graph.
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Status {
     InProgress,
     Stopped,
+    /// Cannot proceed until something outside the task changes (see `Task::blocker`)
+    Blocked,
     Complete,
+    /// Won't be done; kept as a record (see `Task::cancelled`)
+    Cancelled,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum GateStatus {
     #[default]
@@ -66,6 +45,32 @@ pub enum GateStatus {
     Skipped,
 }
 
+/// Why a blocked task can't proceed.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Blocker {
+    pub reason: String,
+    /// External reference such as an issue URL or ticket number
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+}
+
+/// When and why a task was cancelled.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Cancellation {
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// A record of a closed task being reopened.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Reopening {
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub at: DateTime<Utc>,
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateItem {
     pub id: String,
@@ -113,12 +118,64 @@ impl<'de> Deserialize<'de> for GateItem {
     }
 }
 
+impl Serialize for GateItem {
+    /// Pending gates are written as a bare id, others as `{id: status}`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        if self.status == GateStatus::Pending {
+            return serializer.serialize_str(&self.id);
+        }
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.id, &self.status)?;
+        map.end()
+    }
+}
+
+/// Format used for `due:` dates in frontmatter.
+pub const DUE_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parse a `due:` date, rejecting anything but YYYY-MM-DD.
+pub fn parse_due_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s.trim(), DUE_DATE_FORMAT)
+        .map_err(|_| format!("invalid due date '{}', expected YYYY-MM-DD", s))
+}
+
+fn deserialize_due<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(s) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    parse_due_date(&s).map(Some).map_err(serde::de::Error::custom)
+}
+
+fn serialize_due<S: Serializer>(due: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error> {
+    match due {
+        Some(due) => serializer.serialize_str(&due.format(DUE_DATE_FORMAT).to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ParseError {
     #[error("missing frontmatter delimiters")]
     MissingFrontmatter,
-    #[error("invalid yaml: {0}")]
-    InvalidYaml(#[from] serde_yaml::Error),
+    #[error("frontmatter opened on line {line} is never closed")]
+    UnclosedFrontmatter { line: usize },
+    #[error("invalid yaml{}: {message}", format_location(.line, .column))]
+    InvalidYaml {
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    #[error("invalid toml{}: {message}", format_location(.line, .column))]
+    InvalidToml {
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
     #[error("task id cannot be empty")]
     EmptyId,
     #[error("task id '{0}' is reserved")]
@@ -131,27 +188,102 @@ pub enum ParseError {
     JotWithGates(String),
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+/// Format " at line L column C" for parse errors, or nothing if unknown.
+fn format_location(line: &Option<usize>, column: &Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!(" at line {} column {}", line, column),
+        (Some(line), None) => format!(" at line {}", line),
+        _ => String::new(),
+    }
+}
+
+/// A task file's contents.
+///
+/// Fields serialize in the order they are written to frontmatter; empty
+/// fields are omitted.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub struct Task {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub id: String,
+    /// Immutable ULID assigned when the task is created. Stays the same
+    /// across renames, so external tools can link to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    /// Previous ids of a renamed task; references using them still resolve
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// When the task was created, recorded by mont when it first writes it.
+    /// Tasks from before creation times were recorded don't have one.
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_timestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub created: Option<DateTime<Utc>>,
     /// New ID for renaming. Only used in multieditor, not persisted.
-    #[serde(default)]
+    #[serde(default, skip_serializing)]
     pub new_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "TaskType::is_task")]
+    pub task_type: TaskType,
+    /// Task status: None means pending (ready if no blockers), Some(status) for explicit state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// Reason the task is blocked, set alongside `status: blocked`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocker: Option<Blocker>,
+    /// Record of the cancellation, set alongside `status: cancelled`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancelled: Option<Cancellation>,
+    /// When the task was marked complete, set alongside `status: complete`
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_timestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub completed: Option<DateTime<Utc>>,
+    /// Person or agent who owns this task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Milestone (defined in config.yml) this task is planned for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// Free-form labels, e.g. the area of the code a task touches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Estimated effort, e.g. `2h` or `1h30m`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<Effort>,
+    /// Date this task must be complete by (YYYY-MM-DD)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_due",
+        serialize_with = "serialize_due",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub due: Option<NaiveDate>,
     /// This task must complete before these referenced tasks
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub before: Vec<String>,
     /// This task can only start after these tasks complete
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub after: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub gates: Vec<GateItem>,
-    pub title: Option<String>,
-    /// Task status: None means pending (ready if no blockers), Some(status) for explicit state
-    #[serde(default)]
-    pub status: Option<Status>,
-    #[serde(default, rename = "type")]
-    pub task_type: TaskType,
+    /// History of reopen events, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reopened: Vec<Reopening>,
+    /// Work sessions recorded by start/stop/done transitions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sessions: Vec<Session>,
+    /// Frontmatter syntax the task was written in, kept when saving
+    #[serde(skip)]
+    pub frontmatter: FrontmatterFormat,
+    /// Text the task was parsed from, so saving an unchanged task is a no-op.
+    /// Ignored when comparing tasks.
+    #[serde(skip)]
+    pub source: SourceText,
     #[serde(skip)]
     pub description: String,
     /// Internal flag for soft-deletion. Not persisted to markdown.
@@ -169,6 +301,16 @@ impl Task {
         self.task_type == TaskType::Gate
     }
 
+    /// Whether `key` names this task: its id, its uid or one of its aliases.
+    pub fn has_key(&self, key: &str) -> bool {
+        self.id == key || self.uid.as_deref() == Some(key) || self.aliases.iter().any(|a| a == key)
+    }
+
+    /// Whether this task lists `id` under `before`, `after` or `gates`.
+    pub fn references(&self, id: &str) -> bool {
+        self.before.iter().any(|b| b == id) || self.after.iter().any(|a| a == id) || self.gate_ids().any(|g| g == id)
+    }
+
     /// Returns true if this task is a jot
     pub fn is_jot(&self) -> bool {
         self.task_type == TaskType::Jot
@@ -189,82 +331,108 @@ impl Task {
         self.status == Some(Status::Stopped)
     }
 
-    /// Returns true if this task is marked for deletion
-    pub fn is_deleted(&self) -> bool {
-        self.deleted
+    /// Returns true if this task is an epic
+    pub fn is_epic(&self) -> bool {
+        self.task_type == TaskType::Epic
     }
 
-    /// Serialize this task to markdown format.
+    /// Returns true if this task is marked cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.status == Some(Status::Cancelled)
+    }
+
+    /// Returns true if no more work will happen on this task (complete or cancelled)
+    pub fn is_closed(&self) -> bool {
+        self.is_complete() || self.is_cancelled()
+    }
+
+    /// Move a closed task back to pending, recording why.
     ///
-    /// Produces the frontmatter YAML block followed by the description.
-    pub fn to_markdown(&self) -> String {
-        let mut content = String::new();
-        content.push_str("---\n");
-        if !self.id.is_empty() {
-            content.push_str(&format!("id: {}\n", self.id));
+    /// Clears the cancellation record and resets all gates to pending so the
+    /// work is verified again.
+    pub fn reopen(&mut self, reason: &str, now: DateTime<Utc>) {
+        self.status = None;
+        self.cancelled = None;
+        self.completed = None;
+        for gate in &mut self.gates {
+            gate.status = GateStatus::Pending;
         }
+        self.reopened.push(Reopening { at: now, reason: reason.to_string() });
+    }
 
-        if let Some(t) = &self.title {
-            content.push_str(&format!("title: {}\n", yaml_escape(t)));
-        }
+    /// Returns true if this task is marked blocked
+    pub fn is_blocked(&self) -> bool {
+        self.status == Some(Status::Blocked)
+    }
 
-        match self.task_type {
-            TaskType::Task => {} // default, don't write
-            TaskType::Jot => content.push_str("type: jot\n"),
-            TaskType::Gate => content.push_str("type: gate\n"),
-        }
+    /// Returns true if this task is assigned to `user`
+    pub fn is_assigned_to(&self, user: &str) -> bool {
+        self.assignee.as_deref() == Some(user)
+    }
 
-        if let Some(status) = &self.status {
-            let status_str = match status {
-                Status::InProgress => "inprogress",
-                Status::Stopped => "stopped",
-                Status::Complete => "complete",
-            };
-            content.push_str(&format!("status: {}\n", status_str));
-        }
+    /// The namespace this task lives in: its id up to the last `/`, which is
+    /// also its directory under `.tasks`. Top-level tasks have none.
+    pub fn namespace(&self) -> Option<&str> {
+        self.id.rsplit_once('/').map(|(ns, _)| ns)
+    }
 
-        if !self.before.is_empty() {
-            content.push_str("before:\n");
-            for target in &self.before {
-                content.push_str(&format!("  - {}\n", target));
-            }
-        }
+    /// Returns true if this task is in namespace `ns` or one nested inside it
+    pub fn in_namespace(&self, ns: &str) -> bool {
+        let ns = ns.trim_end_matches('/');
+        self.id.strip_prefix(ns).is_some_and(|rest| rest.starts_with('/'))
+    }
 
-        if !self.after.is_empty() {
-            content.push_str("after:\n");
-            for dep in &self.after {
-                content.push_str(&format!("  - {}\n", dep));
-            }
-        }
+    /// Returns true if this task is marked for deletion
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
 
-        if !self.gates.is_empty() {
-            content.push_str("gates:\n");
-            for val in &self.gates {
-                match val.status {
-                    GateStatus::Pending => {
-                        content.push_str(&format!("  - {}\n", val.id));
-                    }
-                    GateStatus::Passed => {
-                        content.push_str(&format!("  - {}: passed\n", val.id));
-                    }
-                    GateStatus::Failed => {
-                        content.push_str(&format!("  - {}: failed\n", val.id));
-                    }
-                    GateStatus::Skipped => {
-                        content.push_str(&format!("  - {}: skipped\n", val.id));
-                    }
-                }
-            }
+    /// Open a new work session at `now`, unless one is already open.
+    pub fn start_session(&mut self, now: DateTime<Utc>) {
+        if !self.sessions.iter().any(Session::is_open) {
+            self.sessions.push(Session { start: now, end: None });
         }
+    }
 
-        content.push_str("---\n\n");
+    /// Mark the task complete at `now`, closing any open work session.
+    pub fn complete(&mut self, now: DateTime<Utc>) {
+        self.status = Some(Status::Complete);
+        self.completed = Some(now);
+        self.end_session(now);
+    }
 
-        if !self.description.is_empty() {
-            content.push_str(&self.description);
-            content.push('\n');
+    /// When the task was completed or cancelled, as recorded on the task.
+    /// Tasks completed before completion times were recorded fall back to the
+    /// end of their last work session. `None` for open tasks, and for closed
+    /// ones with nothing recorded.
+    pub fn closed_at(&self) -> Option<DateTime<Utc>> {
+        match self.status {
+            Some(Status::Complete) => self.completed.or_else(|| self.sessions.iter().filter_map(|s| s.end).max()),
+            Some(Status::Cancelled) => self.cancelled.as_ref().map(|c| c.at),
+            _ => None,
         }
+    }
 
-        content
+    /// Close any open work session at `now`.
+    pub fn end_session(&mut self, now: DateTime<Utc>) {
+        for session in self.sessions.iter_mut().filter(|s| s.is_open()) {
+            session.end = Some(now);
+        }
+    }
+
+    /// Total active time across all sessions, counting an open session up to `now`.
+    pub fn actual_effort(&self, now: DateTime<Utc>) -> Effort {
+        self.sessions.iter().map(|s| s.elapsed(now)).sum()
+    }
+
+    /// Serialize this task to markdown format.
+    ///
+    /// A task parsed from a file and left unchanged is written back
+    /// byte-for-byte. Otherwise only the frontmatter keys whose values changed
+    /// are rewritten, so comments, key order and formatting elsewhere in the
+    /// file survive. Tasks without a source are rendered in canonical form.
+    pub fn to_markdown(&self) -> String {
+        frontmatter::render(self)
     }
 }
 
@@ -364,18 +532,22 @@ impl Task {
 /// assert!(matches!(result, Err(ParseError::GateWithAfter(_))));
 /// ```
 pub fn parse(content: &str) -> Result<Task, ParseError> {
-    let Some(start) = content.find("---") else {
-        return Err(ParseError::MissingFrontmatter);
-    };
-    let after_first = start + 3;
-    let Some(end) = content[after_first..].find("---") else {
-        return Err(ParseError::MissingFrontmatter);
+    let split = frontmatter::split(content)?;
+
+    let mut task: Task = match split.format {
+        FrontmatterFormat::Yaml => serde_yaml::from_str(&split.frontmatter)
+            .map_err(|e| frontmatter::yaml_error(e, split.first_line))?,
+        FrontmatterFormat::Toml => {
+            let value = frontmatter::toml_to_yaml(&split.frontmatter, split.first_line)?;
+            serde_yaml::from_value(value).map_err(|e| ParseError::InvalidToml {
+                message: e.to_string(),
+                line: None,
+                column: None,
+            })?
+        }
     };
-    let yaml = &content[after_first..after_first + end];
-    let description = content[after_first + end + 3..].trim().to_string();
-
-    let mut task: Task = serde_yaml::from_str(yaml)?;
-    task.description = description;
+    task.frontmatter = split.format;
+    task.description = split.body.clone();
 
     // Validate reserved IDs
     if task.id == "?" {
@@ -394,6 +566,7 @@ pub fn parse(content: &str) -> Result<Task, ParseError> {
         return Err(ParseError::JotWithGates(task.id));
     }
 
+    task.source = SourceText::record(content, &split, &task);
     Ok(task)
 }
 
@@ -509,7 +682,7 @@ Jots cannot have gates.
     fn test_parse_missing_closing_delimiter() {
         let content = "---\nid: test\nNo closing delimiter";
         let result = parse(content);
-        assert!(matches!(result, Err(ParseError::MissingFrontmatter)));
+        assert!(matches!(result, Err(ParseError::UnclosedFrontmatter { line: 1 })));
     }
 
     #[test]
@@ -762,15 +935,7 @@ Task description.
     fn test_to_markdown_minimal() {
         let task = Task {
             id: "minimal".to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         };
         let markdown = task.to_markdown();
         let parsed = parse(&markdown).unwrap();
@@ -784,7 +949,6 @@ Task description.
         // Use Task type since jots cannot have gates
         let task = Task {
             id: "full-task".to_string(),
-            new_id: None,
             before: vec!["parent1".to_string(), "parent2".to_string()],
             after: vec!["dep1".to_string()],
             gates: vec![
@@ -803,9 +967,8 @@ Task description.
             ],
             title: Some("Full Task Title".to_string()),
             status: Some(Status::InProgress),
-            task_type: TaskType::Task,
             description: "This is the description.".to_string(),
-            deleted: false,
+            ..Default::default()
         };
         let markdown = task.to_markdown();
         let parsed = parse(&markdown).unwrap();
@@ -827,15 +990,11 @@ Task description.
     fn test_to_markdown_gate() {
         let task = Task {
             id: "my-gate".to_string(),
-            new_id: None,
             before: vec!["consumer".to_string()],
-            after: vec![],
-            gates: vec![],
             title: Some("Gate Title".to_string()),
-            status: None,
             task_type: TaskType::Gate,
             description: "Gate description.".to_string(),
-            deleted: false,
+            ..Default::default()
         };
         let markdown = task.to_markdown();
         let parsed = parse(&markdown).unwrap();
@@ -847,15 +1006,9 @@ Task description.
     fn test_to_markdown_complete_status() {
         let task = Task {
             id: "done-task".to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
             title: Some("Completed Task".to_string()),
             status: Some(Status::Complete),
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         };
         let markdown = task.to_markdown();
         let parsed = parse(&markdown).unwrap();
@@ -866,15 +1019,8 @@ Task description.
     fn test_to_markdown_stopped_status() {
         let task = Task {
             id: "stopped-task".to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
             status: Some(Status::Stopped),
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         };
         let markdown = task.to_markdown();
         let parsed = parse(&markdown).unwrap();
@@ -885,15 +1031,8 @@ Task description.
     fn test_to_markdown_title_with_colon() {
         let task = Task {
             id: "colon-task".to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
             title: Some("Fix: something broken".to_string()),
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         };
         let markdown = task.to_markdown();
         // Should contain quoted title
@@ -907,15 +1046,8 @@ Task description.
     fn test_to_markdown_title_with_quotes() {
         let task = Task {
             id: "quote-task".to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
             title: Some("Task with \"quotes\" inside".to_string()),
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         };
         let markdown = task.to_markdown();
         // Should escape the quotes
@@ -924,4 +1056,320 @@ Task description.
         let parsed = parse(&markdown).unwrap();
         assert_eq!(parsed.title, Some("Task with \"quotes\" inside".to_string()));
     }
+
+    #[test]
+    fn test_parse_estimate_and_sessions() {
+        let content = r#"---
+id: timed
+estimate: 1h30m
+sessions:
+  - start: 2025-01-01T09:00:00Z
+    end: 2025-01-01T10:00:00Z
+  - start: 2025-01-02T09:00:00Z
+---
+"#;
+        let task = parse(content).unwrap();
+        assert_eq!(task.estimate.map(|e| e.minutes()), Some(90));
+        assert_eq!(task.sessions.len(), 2);
+        assert!(task.sessions[1].is_open());
+    }
+
+    #[test]
+    fn test_parse_invalid_estimate_fails() {
+        let content = "---\nid: bad\nestimate: soon\n---\n";
+        assert!(matches!(parse(content), Err(ParseError::InvalidYaml { .. })));
+    }
+
+    #[test]
+    fn test_sessions_accumulate_and_roundtrip() {
+        use chrono::TimeZone;
+        let at = |h| Utc.with_ymd_and_hms(2025, 1, 1, h, 0, 0).unwrap();
+
+        let mut task = parse("---\nid: timed\nestimate: 2h\n---\n").unwrap();
+        task.start_session(at(9));
+        task.start_session(at(9)); // already open, no second session
+        task.end_session(at(10));
+        task.start_session(at(13));
+        assert_eq!(task.sessions.len(), 2);
+        assert_eq!(task.actual_effort(at(14)).minutes(), 120);
+
+        task.end_session(at(15));
+        let parsed = parse(&task.to_markdown()).unwrap();
+        assert_eq!(parsed.estimate, task.estimate);
+        assert_eq!(parsed.sessions, task.sessions);
+        assert_eq!(parsed.actual_effort(at(23)).minutes(), 180);
+    }
+
+    #[test]
+    fn test_closed_at() {
+        use chrono::TimeZone;
+        let at = |h| Utc.with_ymd_and_hms(2025, 1, 1, h, 0, 0).unwrap();
+
+        // Tasks completed before completion times were recorded use their
+        // last session
+        let mut task = parse("---\nid: timed\nstatus: complete\n---\n").unwrap();
+        assert_eq!(task.closed_at(), None);
+        task.sessions = vec![Session { start: at(9), end: Some(at(10)) }];
+        assert_eq!(task.closed_at(), Some(at(10)));
+
+        task.start_session(at(11));
+        task.complete(at(12));
+        let parsed = parse(&task.to_markdown()).unwrap();
+        assert_eq!(parsed.completed, Some(at(12)));
+        assert_eq!(parsed.closed_at(), Some(at(12)));
+
+        task.reopen("not done", at(13));
+        assert_eq!((task.completed, task.closed_at()), (None, None));
+    }
+
+    #[test]
+    fn test_parse_due_date() {
+        let task = parse("---\nid: dated\ndue: 2025-03-01\n---\n").unwrap();
+        assert_eq!(task.due, NaiveDate::from_ymd_opt(2025, 3, 1));
+
+        let reparsed = parse(&task.to_markdown()).unwrap();
+        assert_eq!(reparsed.due, task.due);
+    }
+
+    #[test]
+    fn test_parse_invalid_due_date_fails() {
+        for due in ["tomorrow", "2025-13-01", "01/03/2025"] {
+            let content = format!("---\nid: bad\ndue: {}\n---\n", due);
+            let err = parse(&content).unwrap_err();
+            assert!(err.to_string().contains("expected YYYY-MM-DD"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_parse_assignee_roundtrip() {
+        let task = parse("---\nid: owned\nassignee: agent@ci\n---\n").unwrap();
+        assert!(task.is_assigned_to("agent@ci"));
+        assert!(!task.is_assigned_to("someone-else"));
+
+        let reparsed = parse(&task.to_markdown()).unwrap();
+        assert_eq!(reparsed.assignee.as_deref(), Some("agent@ci"));
+    }
+
+    #[test]
+    fn test_parse_blocked_with_blocker_roundtrip() {
+        let content = r#"---
+id: stuck
+status: blocked
+blocker:
+  reason: "Waiting on API keys: ops team"
+  ref: https://example.com/issues/42
+---
+"#;
+        let task = parse(content).unwrap();
+        assert!(task.is_blocked());
+        let blocker = task.blocker.clone().unwrap();
+        assert_eq!(blocker.reason, "Waiting on API keys: ops team");
+        assert_eq!(blocker.reference.as_deref(), Some("https://example.com/issues/42"));
+
+        let reparsed = parse(&task.to_markdown()).unwrap();
+        assert_eq!(reparsed.status, Some(Status::Blocked));
+        assert_eq!(reparsed.blocker, task.blocker);
+    }
+
+    #[test]
+    fn test_parse_cancelled_roundtrip() {
+        let content = r#"---
+id: dropped
+status: cancelled
+cancelled:
+  at: 2025-02-01T12:00:00Z
+  reason: Superseded by the new importer
+---
+"#;
+        let task = parse(content).unwrap();
+        assert!(task.is_cancelled());
+        assert!(task.is_closed());
+        assert!(!task.is_complete());
+
+        let reparsed = parse(&task.to_markdown()).unwrap();
+        assert_eq!(reparsed.status, Some(Status::Cancelled));
+        assert_eq!(reparsed.cancelled, task.cancelled);
+    }
+
+    #[test]
+    fn test_reopen_resets_gates_and_records_reason() {
+        let content = r#"---
+id: shipped
+status: complete
+gates:
+  - review: passed
+  - tests: skipped
+---
+"#;
+        let mut task = parse(content).unwrap();
+        let now = "2025-03-01T09:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        task.reopen("Crashes on empty input", now);
+
+        assert_eq!(task.status, None);
+        assert!(task.gates.iter().all(|g| g.status == GateStatus::Pending));
+
+        let reparsed = parse(&task.to_markdown()).unwrap();
+        assert_eq!(reparsed.reopened.len(), 1);
+        assert_eq!(reparsed.reopened[0].reason, "Crashes on empty input");
+        assert_eq!(reparsed.reopened[0].at, now);
+    }
+
+    #[test]
+    fn test_parse_keeps_horizontal_rule_in_body() {
+        let content = "---\nid: rule\ntitle: A --- B\n---\n\nFirst part\n\n---\n\nSecond part\n";
+        let task = parse(content).unwrap();
+        assert_eq!(task.title.as_deref(), Some("A --- B"));
+        assert_eq!(task.description, "First part\n\n---\n\nSecond part");
+    }
+
+    #[test]
+    fn test_parse_yaml_error_has_file_line() {
+        let content = "Leading notes\n---\nid: bad\nafter: [\n---\n";
+        let err = parse(content).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidYaml { line: Some(5), .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn test_parse_toml_frontmatter_roundtrip() {
+        let content = r#"+++
+id = "toml-task"
+title = "Written in TOML"
+after = ["dep"]
+gates = ["review", { tests = "passed" }]
+due = 2025-06-01
+
+[[sessions]]
+start = 2025-05-01T09:00:00Z
+end = 2025-05-01T10:00:00Z
++++
+
+Body text.
+"#;
+        let task = parse(content).unwrap();
+        assert_eq!(task.frontmatter, FrontmatterFormat::Toml);
+        assert_eq!(task.after, vec!["dep"]);
+        assert_eq!(task.gates[1].status, GateStatus::Passed);
+        assert_eq!(task.due, NaiveDate::from_ymd_opt(2025, 6, 1));
+        assert_eq!(task.sessions.len(), 1);
+        assert_eq!(task.description, "Body text.");
+
+        let markdown = task.to_markdown();
+        assert!(markdown.starts_with("+++\n"));
+        assert_eq!(parse(&markdown).unwrap(), task);
+    }
+
+    mod roundtrip {
+        use super::*;
+        use chrono::TimeZone;
+        use proptest::prelude::*;
+
+        fn id() -> impl Strategy<Value = String> {
+            prop_oneof![
+                "[a-z0-9][a-z0-9-]{0,12}",
+                Just("true".to_string()),
+                Just("007".to_string()),
+                Just("a: b".to_string()),
+                Just("- dash".to_string()),
+            ]
+        }
+
+        fn timestamp() -> impl Strategy<Value = DateTime<Utc>> {
+            (0i64..4_000_000_000).prop_map(|s| Utc.timestamp_opt(s, 0).unwrap())
+        }
+
+        fn gate() -> impl Strategy<Value = GateItem> {
+            let status = prop_oneof![
+                Just(GateStatus::Pending),
+                Just(GateStatus::Passed),
+                Just(GateStatus::Failed),
+                Just(GateStatus::Skipped),
+            ];
+            (id(), status).prop_map(|(id, status)| GateItem { id, status })
+        }
+
+        fn task() -> impl Strategy<Value = Task> {
+            let status = prop::option::of(prop_oneof![
+                Just(Status::InProgress),
+                Just(Status::Stopped),
+                Just(Status::Blocked),
+                Just(Status::Complete),
+                Just(Status::Cancelled),
+            ]);
+            let header = (
+                id(),
+                prop::option::of(any::<String>()),
+                prop_oneof![Just(TaskType::Task), Just(TaskType::Epic)],
+                status,
+                prop::option::of((any::<String>(), prop::option::of(any::<String>())))
+                    .prop_map(|b| b.map(|(reason, reference)| Blocker { reason, reference })),
+                prop::option::of((timestamp(), prop::option::of(any::<String>())))
+                    .prop_map(|c| c.map(|(at, reason)| Cancellation { at, reason })),
+                prop::option::of(any::<String>()),
+                prop::option::of(id()),
+            );
+            let plan = (
+                prop::option::of((1u64..10_000).prop_map(Effort::from_minutes)),
+                prop::option::of((0i64..40_000).prop_map(|d| {
+                    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + chrono::Duration::days(d)
+                })),
+                prop::collection::vec(id(), 0..3),
+                prop::collection::vec(id(), 0..3),
+                prop::collection::vec(gate(), 0..3),
+                prop::collection::vec((timestamp(), any::<String>()), 0..2)
+                    .prop_map(|r| r.into_iter().map(|(at, reason)| Reopening { at, reason }).collect()),
+                prop::collection::vec((timestamp(), prop::option::of(timestamp())), 0..3)
+                    .prop_map(|s| s.into_iter().map(|(start, end)| Session { start, end }).collect()),
+                "[a-zA-Z0-9 #*.,:-]{0,20}(\n[a-zA-Z0-9 #*.,:-]{0,20}){0,3}".prop_map(|d| d.trim().to_string()),
+                prop::collection::vec(id(), 0..3),
+            );
+            (header, plan).prop_map(
+                |(
+                    (id, title, task_type, status, blocker, cancelled, assignee, milestone),
+                    (estimate, due, before, after, gates, reopened, sessions, description, tags),
+                )| Task {
+                    id,
+                    before,
+                    after,
+                    gates,
+                    title,
+                    status,
+                    task_type,
+                    estimate,
+                    sessions,
+                    due,
+                    assignee,
+                    blocker,
+                    cancelled,
+                    reopened,
+                    milestone,
+                    tags,
+                    description,
+                    ..Default::default()
+                },
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn prop_to_markdown_roundtrips(task in task()) {
+                let markdown = task.to_markdown();
+                let parsed = parse(&markdown).unwrap();
+                prop_assert_eq!(&parsed, &task);
+
+                // Unmodified tasks write back byte-identically, with or
+                // without their recorded source
+                prop_assert_eq!(parsed.to_markdown(), markdown.clone());
+                let mut detached = parsed.clone();
+                detached.source = Default::default();
+                prop_assert_eq!(detached.to_markdown(), markdown);
+            }
+
+            #[test]
+            fn prop_edited_task_roundtrips(original in task(), edited in task()) {
+                let parsed = parse(&original.to_markdown()).unwrap();
+                let updated = Task { source: parsed.source.clone(), ..edited.clone() };
+                prop_assert_eq!(parse(&updated.to_markdown()).unwrap(), edited);
+            }
+        }
+    }
 }
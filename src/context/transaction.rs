@@ -75,50 +75,44 @@ impl Transaction {
     /// If `new_id` is `None`, removes all references entirely.
     ///
     /// This adds Update operations for all tasks in `graph` that reference `old_id`
-    /// in their `before`, `after`, or `validations` fields.
+    /// in their `before`, `after`, or `gates` fields.
     pub fn rewrite_references(
         &mut self,
         graph: &impl GraphView,
         old_id: &str,
         new_id: Option<&str>,
     ) {
-        for task in graph.values() {
+        for task in graph.referencing(old_id) {
             if task.id == old_id {
                 continue;
             }
 
-            let has_ref = task.before.iter().any(|b| b == old_id)
-                || task.after.iter().any(|a| a == old_id)
-                || task.gates.iter().any(|v| v.id == old_id);
-
-            if has_ref {
-                let mut updated = task.clone();
-                match new_id {
-                    Some(new) => {
-                        for before in &mut updated.before {
-                            if before == old_id {
-                                *before = new.to_string();
-                            }
-                        }
-                        for after in &mut updated.after {
-                            if after == old_id {
-                                *after = new.to_string();
-                            }
+            let mut updated = task.clone();
+            match new_id {
+                Some(new) => {
+                    for before in &mut updated.before {
+                        if before == old_id {
+                            *before = new.to_string();
                         }
-                        for validation in &mut updated.gates {
-                            if validation.id == old_id {
-                                validation.id = new.to_string();
-                            }
+                    }
+                    for after in &mut updated.after {
+                        if after == old_id {
+                            *after = new.to_string();
                         }
                     }
-                    None => {
-                        updated.before.retain(|b| b != old_id);
-                        updated.after.retain(|a| a != old_id);
-                        updated.gates.retain(|v| v.id != old_id);
+                    for validation in &mut updated.gates {
+                        if validation.id == old_id {
+                            validation.id = new.to_string();
+                        }
                     }
                 }
-                self.update(&task.id, updated);
+                None => {
+                    updated.before.retain(|b| b != old_id);
+                    updated.after.retain(|a| a != old_id);
+                    updated.gates.retain(|v| v.id != old_id);
+                }
             }
+            self.update(&task.id, updated);
         }
     }
 }
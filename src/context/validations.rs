@@ -13,17 +13,17 @@ pub enum ValidationError {
         task_id: String,
         after_id: String,
     },
-    #[error("task '{task_id}' has gate '{after_id}' as after dependency (use validations instead)")]
+    #[error("task '{task_id}' has gate '{after_id}' as after dependency (list it under gates instead)")]
     AfterIsGate {
         task_id: String,
         after_id: String,
     },
-    #[error("task '{task_id}' references non-existent validation '{validation_id}'")]
+    #[error("task '{task_id}' references non-existent gate '{validation_id}'")]
     ValidationNotFound {
         task_id: String,
         validation_id: String,
     },
-    #[error("task '{task_id}' references validation '{validation_id}' which is not a gate")]
+    #[error("task '{task_id}' references '{validation_id}' in gates, which is not a gate")]
     InvalidValidation {
         task_id: String,
         validation_id: String,
@@ -33,43 +33,146 @@ pub enum ValidationError {
         task_id: String,
         validation_id: String,
     },
-    #[error("cycle detected in task graph")]
-    CycleDetected,
+    #[error("cycle detected in task graph: {}", cycle_path(cycle))]
+    CycleDetected { cycle: Vec<CycleEdge> },
     #[error("duplicate task id '{0}'")]
     DuplicateTaskId(String),
+    #[error("task '{task_id}' has a different uid than when it was loaded; uids cannot change")]
+    UidChanged { task_id: String },
+    #[error("'{key}' names both '{task_id}' and '{other_id}' (as an id, uid or alias)")]
+    KeyConflict {
+        key: String,
+        task_id: String,
+        other_id: String,
+    },
+}
+
+impl ValidationError {
+    /// The task whose file the problem should be fixed in. A cycle is
+    /// reported against the task that lists its first edge.
+    pub fn task_id(&self) -> &str {
+        match self {
+            ValidationError::InvalidBefore { task_id, .. }
+            | ValidationError::InvalidAfter { task_id, .. }
+            | ValidationError::AfterIsGate { task_id, .. }
+            | ValidationError::ValidationNotFound { task_id, .. }
+            | ValidationError::InvalidValidation { task_id, .. }
+            | ValidationError::ValidationNotRootGate { task_id, .. }
+            | ValidationError::UidChanged { task_id }
+            | ValidationError::KeyConflict { task_id, .. }
+            | ValidationError::DuplicateTaskId(task_id) => task_id,
+            ValidationError::CycleDetected { cycle } => cycle.first().map_or("", |e| e.from.as_str()),
+        }
+    }
+}
+
+/// Which field of a task a dependency edge was declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    Before,
+    After,
+}
+
+impl EdgeKind {
+    /// The frontmatter key the edge is listed under.
+    pub fn field(self) -> &'static str {
+        match self {
+            EdgeKind::Before => "before",
+            EdgeKind::After => "after",
+        }
+    }
+}
+
+/// One edge of a dependency cycle: `from` lists `to` under `kind`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// Render a cycle as `a -> b -> c -> a`.
+pub fn cycle_path(cycle: &[CycleEdge]) -> String {
+    let mut ids: Vec<&str> = cycle.iter().map(|e| e.from.as_str()).collect();
+    if let Some(last) = cycle.last() {
+        ids.push(&last.to);
+    }
+    ids.join(" -> ")
 }
 
 /// Validates a GraphView (TaskGraph, ValidationView, or any other implementation).
 ///
 /// Checks that:
-/// - All task references (before, after, validations) point to existing tasks
+/// - Ids, uids and aliases are unique across tasks
+/// - All task references (before, after, gates) point to existing tasks,
+///   by id, uid or alias, or to archived tasks
 /// - Non-gate tasks cannot have gates as after dependencies
 /// - Validation references point to root gates (gates without before targets)
 /// - The graph forms a DAG (no cycles)
 ///
-/// Deleted tasks are skipped and not validated.
+/// Deleted tasks are skipped and not validated. Returns the first problem
+/// found; use `validate_all` to collect every one.
 pub fn validate_view<V: GraphView>(view: &V) -> Result<(), ValidationError> {
-    for task in view.values() {
-        validate_task_in_view(task, view)?;
+    match validate_all(view).into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(()),
     }
+}
 
-    if has_cycle(view) {
-        return Err(ValidationError::CycleDetected);
+/// Validates a GraphView like `validate_view`, collecting every problem
+/// instead of stopping at the first.
+///
+/// Tasks are checked in id order, so the same graph always produces the same
+/// report. Every cycle is reported by the edge that closes it, so dropping the
+/// last edge of each breaks them all.
+pub fn validate_all<V: GraphView>(view: &V) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    validate_keys(view, &mut errors);
+
+    let mut tasks: Vec<&Task> = view.values().collect();
+    tasks.sort_by(|a, b| a.id.cmp(&b.id));
+    for task in tasks {
+        validate_task_in_view(task, view, &mut errors);
     }
 
-    Ok(())
+    for cycle in find_cycles(view) {
+        errors.push(ValidationError::CycleDetected { cycle });
+    }
+
+    errors
+}
+
+/// Ensures every id, uid and alias names exactly one task.
+fn validate_keys<V: GraphView>(view: &V, errors: &mut Vec<ValidationError>) {
+    let mut owners: HashMap<&str, &str> = view.values().map(|t| (t.id.as_str(), t.id.as_str())).collect();
+
+    for task in view.values() {
+        let keys = task.uid.iter().chain(task.aliases.iter());
+        for key in keys {
+            match owners.insert(key.as_str(), task.id.as_str()) {
+                Some(other) if other != task.id => {
+                    errors.push(ValidationError::KeyConflict {
+                        key: key.clone(),
+                        task_id: task.id.clone(),
+                        other_id: other.to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
 }
 
 /// Validates a single task's references against a GraphView.
-fn validate_task_in_view<V: GraphView>(task: &Task, view: &V) -> Result<(), ValidationError> {
+fn validate_task_in_view<V: GraphView>(task: &Task, view: &V, errors: &mut Vec<ValidationError>) {
     // Skip validation of deleted tasks
     if task.is_deleted() {
-        return Ok(());
+        return;
     }
 
     for before_id in &task.before {
-        if view.get(before_id).is_none() {
-            return Err(ValidationError::InvalidBefore {
+        if view.resolve(before_id).is_none() && !view.is_archived(before_id) {
+            errors.push(ValidationError::InvalidBefore {
                 task_id: task.id.clone(),
                 before_id: before_id.clone(),
             });
@@ -77,15 +180,19 @@ fn validate_task_in_view<V: GraphView>(task: &Task, view: &V) -> Result<(), Vali
     }
 
     for after_id in &task.after {
-        let Some(after_task) = view.get(after_id) else {
-            return Err(ValidationError::InvalidAfter {
+        if view.is_archived(after_id) && view.resolve(after_id).is_none() {
+            continue;
+        }
+        let Some(after_task) = view.resolve(after_id) else {
+            errors.push(ValidationError::InvalidAfter {
                 task_id: task.id.clone(),
                 after_id: after_id.clone(),
             });
+            continue;
         };
 
         if !task.is_gate() && after_task.is_gate() {
-            return Err(ValidationError::AfterIsGate {
+            errors.push(ValidationError::AfterIsGate {
                 task_id: task.id.clone(),
                 after_id: after_id.clone(),
             });
@@ -93,29 +200,29 @@ fn validate_task_in_view<V: GraphView>(task: &Task, view: &V) -> Result<(), Vali
     }
 
     for validation in &task.gates {
-        let Some(gate) = view.get(&validation.id) else {
-            return Err(ValidationError::ValidationNotFound {
+        if view.is_archived(&validation.id) && view.resolve(&validation.id).is_none() {
+            continue;
+        }
+        let Some(gate) = view.resolve(&validation.id) else {
+            errors.push(ValidationError::ValidationNotFound {
                 task_id: task.id.clone(),
                 validation_id: validation.id.clone(),
             });
+            continue;
         };
 
         if !gate.is_gate() {
-            return Err(ValidationError::InvalidValidation {
+            errors.push(ValidationError::InvalidValidation {
                 task_id: task.id.clone(),
                 validation_id: validation.id.clone(),
             });
-        }
-
-        if !gate.before.is_empty() {
-            return Err(ValidationError::ValidationNotRootGate {
+        } else if !gate.before.is_empty() {
+            errors.push(ValidationError::ValidationNotRootGate {
                 task_id: task.id.clone(),
                 validation_id: validation.id.clone(),
             });
         }
     }
-
-    Ok(())
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -125,45 +232,76 @@ enum Color {
     Black,
 }
 
-fn has_cycle<V: GraphView>(view: &V) -> bool {
+/// Find cycles of `before`/`after` edges, each returned in order and ending
+/// with the edge that closes it.
+fn find_cycles<V: GraphView>(view: &V) -> Vec<Vec<CycleEdge>> {
     let mut colors: HashMap<String, Color> = HashMap::new();
     for id in view.keys() {
         colors.insert(id.to_string(), Color::White);
     }
 
-    for id in view.keys() {
-        if colors[id] == Color::White && dfs_cycle(view, id, &mut colors) {
-            return true;
+    // Visit in a fixed order so the same graph always reports the same cycles
+    let mut ids: Vec<&str> = view.keys().collect();
+    ids.sort();
+
+    let mut cycles = Vec::new();
+    let mut path = Vec::new();
+    for id in ids {
+        if colors[id] == Color::White {
+            dfs_cycle(view, id, &mut colors, &mut path, &mut cycles);
         }
     }
 
-    false
+    cycles
 }
 
-fn dfs_cycle<V: GraphView>(view: &V, task_id: &str, colors: &mut HashMap<String, Color>) -> bool {
+/// Depth-first search from `task_id`. `path` holds the edges from the root of
+/// the search down to `task_id`.
+fn dfs_cycle<V: GraphView>(
+    view: &V,
+    task_id: &str,
+    colors: &mut HashMap<String, Color>,
+    path: &mut Vec<CycleEdge>,
+    cycles: &mut Vec<Vec<CycleEdge>>,
+) {
     colors.insert(task_id.to_string(), Color::Gray);
 
     let Some(task) = view.get(task_id) else {
-        return false;
+        return;
     };
 
-    let neighbors = task.before.iter().chain(task.after.iter());
-    for neighbor_id in neighbors {
-        let neighbor_color = colors.get(neighbor_id.as_str()).copied().unwrap_or(Color::Black);
+    let neighbors = task
+        .before
+        .iter()
+        .map(|id| (id, EdgeKind::Before))
+        .chain(task.after.iter().map(|id| (id, EdgeKind::After)));
+    for (neighbor_id, kind) in neighbors {
+        let neighbor_id = view.resolve(neighbor_id).map_or(neighbor_id.as_str(), |t| t.id.as_str());
+        let neighbor_color = colors.get(neighbor_id).copied().unwrap_or(Color::Black);
+        let edge = CycleEdge {
+            from: task_id.to_string(),
+            to: neighbor_id.to_string(),
+            kind,
+        };
 
         match neighbor_color {
-            Color::Gray => return true,
+            Color::Gray => {
+                // The neighbor is on the current path: the cycle runs from it to here
+                let start = path.iter().position(|e| e.from == neighbor_id).unwrap_or(path.len());
+                let mut cycle = path[start..].to_vec();
+                cycle.push(edge);
+                cycles.push(cycle);
+            }
             Color::White => {
-                if dfs_cycle(view, neighbor_id, colors) {
-                    return true;
-                }
+                path.push(edge);
+                dfs_cycle(view, neighbor_id, colors, path, cycles);
+                path.pop();
             }
             Color::Black => {}
         }
     }
 
     colors.insert(task_id.to_string(), Color::Black);
-    false
 }
 
 #[cfg(test)]
@@ -175,30 +313,15 @@ mod tests {
     fn make_task(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
     fn make_gate(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            title: None,
-            status: None,
             task_type: TaskType::Gate,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
@@ -285,7 +408,70 @@ mod tests {
         graph.insert(a);
         graph.insert(b);
 
-        assert_eq!(validate_view(&graph), Err(ValidationError::CycleDetected));
+        assert_eq!(
+            validate_view(&graph),
+            Err(ValidationError::CycleDetected {
+                cycle: vec![
+                    CycleEdge { from: "a".to_string(), to: "b".to_string(), kind: EdgeKind::Before },
+                    CycleEdge { from: "b".to_string(), to: "a".to_string(), kind: EdgeKind::Before },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_cycle_path_skips_tasks_outside_the_cycle() {
+        let mut entry = make_task("entry");
+        let mut a = make_task("a");
+        let mut b = make_task("b");
+        let mut c = make_task("c");
+        entry.before = vec!["b".to_string()];
+        a.before = vec!["b".to_string()];
+        b.after = vec!["c".to_string()];
+        c.before = vec!["b".to_string()];
+
+        let graph: TaskGraph = vec![entry, a, b, c].into_iter().collect();
+
+        let result = validate_view(&graph);
+        assert_eq!(
+            result,
+            Err(ValidationError::CycleDetected {
+                cycle: vec![
+                    CycleEdge { from: "b".to_string(), to: "c".to_string(), kind: EdgeKind::After },
+                    CycleEdge { from: "c".to_string(), to: "b".to_string(), kind: EdgeKind::Before },
+                ],
+            })
+        );
+        assert_eq!(result.unwrap_err().to_string(), "cycle detected in task graph: b -> c -> b");
+    }
+
+    #[test]
+    fn test_validate_all_collects_every_error() {
+        let mut a = make_task("a");
+        let mut b = make_task("b");
+        let mut c = make_task("c");
+        let mut d = make_task("d");
+        a.after = vec!["missing".to_string(), "b".to_string()];
+        b.after = vec!["a".to_string()];
+        c.before = vec!["gone".to_string()];
+        c.after = vec!["d".to_string()];
+        d.after = vec!["c".to_string()];
+
+        let graph: TaskGraph = vec![a, b, c, d].into_iter().collect();
+        let errors = validate_all(&graph);
+
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "task 'a' references invalid after dependency 'missing'",
+                "task 'c' references invalid before target 'gone'",
+                "cycle detected in task graph: a -> b -> a",
+                "cycle detected in task graph: c -> d -> c",
+            ]
+        );
+        assert_eq!(errors[3].task_id(), "c");
+        assert_eq!(validate_view(&graph).err().as_ref(), errors.first());
     }
 
     #[test]
@@ -343,6 +529,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_view_archived_references_are_satisfied() {
+        let mut task = make_task("task");
+        task.before = vec!["old-epic".to_string()];
+        task.after = vec!["old-dep".to_string()];
+
+        let mut graph = TaskGraph::new();
+        graph.insert(task);
+        graph.set_archived(["old-epic", "old-dep"].map(String::from).into());
+
+        assert!(validate_view(&graph).is_ok());
+    }
+
     #[test]
     fn test_validate_view_deleted_task_is_skipped() {
         // A deleted task should pass validation even with invalid refs
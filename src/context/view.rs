@@ -17,6 +17,29 @@ pub trait GraphView {
         self.get(id).is_some()
     }
 
+    /// Find the task a reference names: by id, then by uid or alias.
+    fn resolve(&self, key: &str) -> Option<&Task> {
+        self.get(key).or_else(|| self.values().find(|t| t.has_key(key)))
+    }
+
+    /// Whether `id` names a task that has been archived. Archived tasks are
+    /// out of the graph, but references to them are still valid.
+    fn is_archived(&self, _id: &str) -> bool {
+        false
+    }
+
+    /// Whether `task` no longer holds up the tasks that depend on it.
+    fn satisfies_dependents(&self, task: &Task) -> bool {
+        task.is_complete() || task.is_cancelled()
+    }
+
+    /// Tasks that reference `id` under `before`, `after` or `gates`, in id order.
+    fn referencing(&self, id: &str) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.values().filter(|t| t.references(id)).collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        tasks
+    }
+
     /// Iterate over all tasks.
     fn values(&self) -> Box<dyn Iterator<Item = &Task> + '_>;
 
@@ -54,6 +77,24 @@ impl GraphView for TaskGraph {
     fn len(&self) -> usize {
         TaskGraph::values(self).filter(|t| !t.is_deleted()).count()
     }
+
+    fn resolve(&self, key: &str) -> Option<&Task> {
+        GraphView::get(self, key).or_else(|| self.known_by(key).find(|t| !t.is_deleted()))
+    }
+
+    fn is_archived(&self, id: &str) -> bool {
+        TaskGraph::is_archived(self, id)
+    }
+
+    fn satisfies_dependents(&self, task: &Task) -> bool {
+        TaskGraph::satisfies_dependents(self, task)
+    }
+
+    fn referencing(&self, id: &str) -> Vec<&Task> {
+        let mut tasks = TaskGraph::referencing(self, id);
+        tasks.retain(|t| !t.is_deleted());
+        tasks
+    }
 }
 
 /// A lightweight view of the task graph with proposed changes overlaid.
@@ -117,6 +158,40 @@ impl GraphView for ValidationView<'_> {
         self.base.get(id).filter(|t| !t.is_deleted())
     }
 
+    fn resolve(&self, key: &str) -> Option<&Task> {
+        // The transaction's own tasks, then indexed base tasks it leaves alone
+        self.get(key)
+            .or_else(|| self.inserts.values().copied().find(|t| t.has_key(key)))
+            .or_else(|| {
+                self.base.known_by(key).find(|t| {
+                    !t.is_deleted() && !self.deletes.contains(t.id.as_str()) && !self.inserts.contains_key(&t.id)
+                })
+            })
+    }
+
+    fn is_archived(&self, id: &str) -> bool {
+        self.base.is_archived(id)
+    }
+
+    fn satisfies_dependents(&self, task: &Task) -> bool {
+        self.base.satisfies_dependents(task)
+    }
+
+    fn referencing(&self, id: &str) -> Vec<&Task> {
+        // Indexed base tasks the transaction leaves alone, plus a scan of its own
+        let mut tasks: Vec<&Task> = self
+            .base
+            .referencing(id)
+            .into_iter()
+            .filter(|t| !t.is_deleted())
+            .filter(|t| !self.deletes.contains(t.id.as_str()))
+            .filter(|t| !self.inserts.contains_key(&t.id))
+            .chain(self.inserts.values().copied().filter(|t| t.references(id)))
+            .collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        tasks
+    }
+
     fn values(&self) -> Box<dyn Iterator<Item = &Task> + '_> {
         // Base tasks: not soft-deleted, not txn-deleted, not overridden by upsert
         let base_tasks = self
@@ -152,20 +227,12 @@ impl GraphView for ValidationView<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::context::task::TaskType;
 
     fn make_task(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
             title: Some(format!("{} title", id)),
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
@@ -3,7 +3,7 @@ use std::io;
 
 use owo_colors::OwoColorize;
 
-use crate::context::{GraphReadError, LoadError, SettingsError};
+use crate::context::{GraphReadError, LoadError, MigrateError, QueryError, SettingsError};
 use crate::{ParseError, TransactionError, ValidationError};
 use crate::EditorError;
 
@@ -88,10 +88,44 @@ pub enum AppError {
     MultiEditRequiresEditor,
     /// Invalid command arguments
     InvalidArgs(String),
+    /// Task is assigned to someone other than the current user
+    TaskAssignedToOther { task_id: String, assignee: String },
+    /// Current user could not be determined (no config user or $USER)
+    UnknownUser,
+    /// Task is blocked (with the recorded reason, if any)
+    TaskBlocked { task_id: String, reason: Option<String> },
+    /// Task is not blocked (for unblock command)
+    TaskNotBlocked(String),
+    /// Task was cancelled and cannot be worked on
+    TaskCancelled(String),
+    /// Task is still open, so there is nothing to reopen
+    TaskNotClosed(String),
+    /// Epics are containers and cannot be worked on directly
+    CannotStartEpic(String),
+    /// Milestone is not defined in config.yml
+    UnknownMilestone(String),
+    /// .tasks format version check or migration failed
+    Migrate(MigrateError),
+    /// `mont check` found problems; `report` is already formatted
+    CheckFailed { report: String, code: i32 },
+    /// Task query could not be parsed
+    InvalidQuery(QueryError),
+    /// Task query matched no tasks
+    NoMatches(String),
     /// ID required (use ? for picker)
     IdRequired(String),
 }
 
+impl AppError {
+    /// Process exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::CheckFailed { code, .. } => *code,
+            _ => 1,
+        }
+    }
+}
+
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -189,6 +223,40 @@ impl fmt::Display for AppError {
             AppError::InvalidArgs(msg) => {
                 write!(f, "{}", format_cli_error(msg))
             }
+            AppError::TaskAssignedToOther { task_id, assignee } => {
+                write!(f, "{}", format_task_assigned_to_other(task_id, assignee))
+            }
+            AppError::UnknownUser => {
+                write!(f, "{}", format_unknown_user())
+            }
+            AppError::TaskBlocked { task_id, reason } => {
+                write!(f, "{}", format_task_blocked(task_id, reason.as_deref()))
+            }
+            AppError::TaskNotBlocked(id) => {
+                write!(f, "{}", format_task_not_blocked(id))
+            }
+            AppError::TaskCancelled(id) => {
+                write!(f, "{}", format_task_cancelled(id))
+            }
+            AppError::TaskNotClosed(id) => {
+                write!(f, "{}", format_task_not_closed(id))
+            }
+            AppError::CannotStartEpic(id) => {
+                write!(f, "{}", format_cannot_start_epic(id))
+            }
+            AppError::UnknownMilestone(name) => {
+                write!(f, "{}", format_unknown_milestone(name))
+            }
+            AppError::Migrate(e) => {
+                write!(f, "{}", format_migrate_error(e))
+            }
+            AppError::CheckFailed { report, .. } => write!(f, "{}", report),
+            AppError::InvalidQuery(e) => {
+                write!(f, "{}", format_invalid_query(e))
+            }
+            AppError::NoMatches(query) => {
+                write!(f, "{}", format_no_matches(query))
+            }
             AppError::IdRequired(cmd) => {
                 write!(
                     f,
@@ -254,6 +322,15 @@ impl<T> IoResultExt<T> for Result<T, io::Error> {
 // Formatting functions (internal implementation)
 // ============================================================================
 
+/// Format a file position as "path:line:column", omitting unknown parts.
+fn format_file_position(file_path: &str, line: Option<usize>, column: Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!("{}:{}:{}", file_path, line, column),
+        (Some(line), None) => format!("{}:{}", file_path, line),
+        _ => file_path.to_string(),
+    }
+}
+
 fn format_parse_error(error: &ParseError, file_path: &str) -> String {
     let mut out = String::new();
 
@@ -266,7 +343,7 @@ fn format_parse_error(error: &ParseError, file_path: &str) -> String {
                 file_path.cyan()
             ));
             out.push('\n');
-            out.push_str(&format!("  {}\n", "Task files require YAML frontmatter between --- delimiters.".dimmed()));
+            out.push_str(&format!("  {}\n", "Task files require YAML frontmatter between --- lines (or TOML between +++ lines).".dimmed()));
             out.push('\n');
             out.push_str(&format!("  {}:\n", "To fix this".bold()));
             out.push_str(&format!("    Add frontmatter to the top of {}:\n", file_path.cyan()));
@@ -276,10 +353,32 @@ fn format_parse_error(error: &ParseError, file_path: &str) -> String {
             out.push_str(&format!("      {}\n", "title: Your task title".dimmed()));
             out.push_str(&format!("      {}\n", "---".dimmed()));
         }
-        ParseError::InvalidYaml(yaml_err) => {
-            out.push_str(&format!("invalid YAML in {}\n", file_path.cyan()));
+        ParseError::UnclosedFrontmatter { line } => {
+            out.push_str(&format!(
+                "frontmatter in {} is never closed\n",
+                format_file_position(file_path, Some(*line), None).cyan()
+            ));
+            out.push('\n');
+            out.push_str(&format!(
+                "  {}\n",
+                "The closing delimiter must be on a line of its own, matching the opening --- or +++.".dimmed()
+            ));
+        }
+        ParseError::InvalidYaml { message, line, column } => {
+            out.push_str(&format!(
+                "invalid YAML in {}\n",
+                format_file_position(file_path, *line, *column).cyan()
+            ));
+            out.push('\n');
+            out.push_str(&format!("  {}\n", message.dimmed()));
+        }
+        ParseError::InvalidToml { message, line, column } => {
+            out.push_str(&format!(
+                "invalid TOML in {}\n",
+                format_file_position(file_path, *line, *column).cyan()
+            ));
             out.push('\n');
-            out.push_str(&format!("  {}\n", yaml_err.to_string().dimmed()));
+            out.push_str(&format!("  {}\n", message.dimmed()));
         }
         ParseError::EmptyId => {
             out.push_str(&format!("task id cannot be empty in {}\n", file_path.cyan()));
@@ -546,19 +645,37 @@ fn format_validation_error(error: &ValidationError, tasks_dir: &str) -> String {
                 task_id.cyan()
             ));
         }
-        ValidationError::CycleDetected => {
+        ValidationError::CycleDetected { cycle } => {
             out.push_str("cycle detected in task graph\n");
             out.push('\n');
-            out.push_str(&format!("  {}\n", "The task graph contains a circular dependency.".dimmed()));
-            out.push_str(&format!("  {}\n", "Tasks cannot depend on themselves directly or indirectly.".dimmed()));
+            let chain: Vec<String> = cycle
+                .iter()
+                .map(|e| e.from.yellow().to_string())
+                .chain(cycle.last().map(|e| e.to.yellow().to_string()))
+                .collect();
+            out.push_str(&format!("  {}\n", chain.join(&" → ".dimmed().to_string())));
+            out.push('\n');
+            for edge in cycle {
+                out.push_str(&format!(
+                    "    {}/{}.md lists '{}' under {}\n",
+                    tasks_dir.cyan(),
+                    edge.from.cyan(),
+                    edge.to,
+                    edge.kind.field()
+                ));
+            }
             out.push('\n');
             out.push_str(&format!("  {}:\n", "To fix this".bold()));
-            out.push_str("    1. Review before and after relationships\n");
-            out.push_str("    2. Look for chains like: A → B → C → A\n");
-            out.push_str(&format!(
-                "    3. Run {} to see task relationships\n",
-                "mont list".cyan()
-            ));
+            if let Some(edge) = cycle.last() {
+                out.push_str(&format!(
+                    "    1. Remove '{}' from {} in {}/{}.md\n",
+                    edge.to.cyan(),
+                    edge.kind.field().cyan(),
+                    tasks_dir.cyan(),
+                    edge.from.cyan()
+                ));
+                out.push_str("    2. Or remove any other edge listed above; one is enough to break the cycle\n");
+            }
         }
         ValidationError::DuplicateTaskId(task_id) => {
             out.push_str(&format!("duplicate task id '{}'\n", task_id.yellow()));
@@ -577,6 +694,41 @@ fn format_validation_error(error: &ValidationError, tasks_dir: &str) -> String {
             out.push_str("    2. Rename one of the tasks to have a unique id\n");
             out.push_str("    3. Delete the duplicate file if unintended\n");
         }
+        ValidationError::UidChanged { task_id } => {
+            out.push_str(&format!("task '{}' uid cannot change\n", task_id.yellow()));
+            out.push('\n');
+            out.push_str(&format!(
+                "  {}\n",
+                "The uid is a permanent identity that external links rely on.".dimmed()
+            ));
+            out.push('\n');
+            out.push_str(&format!("  {}:\n", "To fix this".bold()));
+            out.push_str("    Restore the original uid line, or remove it to keep the current one\n");
+        }
+        ValidationError::KeyConflict { key, task_id, other_id } => {
+            out.push_str(&format!(
+                "'{}' names both '{}' and '{}'\n",
+                key.yellow(),
+                task_id.yellow(),
+                other_id.yellow()
+            ));
+            out.push('\n');
+            out.push_str(&format!(
+                "  {}\n",
+                "References may use a task's id, uid or aliases, so each must be unique.".dimmed()
+            ));
+            out.push('\n');
+            out.push_str(&format!("  {}:\n", "To fix this".bold()));
+            out.push_str(&format!(
+                "    1. Remove '{}' from the aliases in {}/{}.md or {}/{}.md\n",
+                key.cyan(),
+                tasks_dir.cyan(),
+                task_id.cyan(),
+                tasks_dir.cyan(),
+                other_id.cyan()
+            ));
+            out.push_str("    2. If two tasks share a uid, one was copied; delete its uid line\n");
+        }
     }
 
     out
@@ -807,10 +959,21 @@ fn format_load_error(error: &LoadError) -> String {
     match error {
         LoadError::Graph(e) => format_graph_read_error(e),
         LoadError::Settings(e) => format_settings_error(e),
+        LoadError::Format(e) => format_migrate_error(e),
     }
 }
 
 fn format_graph_read_error(error: &GraphReadError) -> String {
+    // A graph that parsed but failed validation gets the full diagnostic
+    if error.io_errors.is_empty() && error.parse_errors.is_empty() {
+        return error
+            .validation_errors
+            .iter()
+            .map(|e| format_validation_error(e, ".tasks"))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
     let mut out = String::new();
 
     out.push_str(&format!("{}: ", "error".red().bold()));
@@ -827,7 +990,7 @@ fn format_graph_read_error(error: &GraphReadError) -> String {
 
     for (path, parse_err) in &error.parse_errors {
         out.push_str(&format!(
-            "  {} {}: {:?}\n",
+            "  {} {}: {}\n",
             "•".red(),
             path.display().to_string().cyan(),
             parse_err
@@ -835,7 +998,7 @@ fn format_graph_read_error(error: &GraphReadError) -> String {
     }
 
     for val_err in &error.validation_errors {
-        out.push_str(&format!("  {} {:?}\n", "•".red(), val_err));
+        out.push_str(&format!("  {} {}\n", "•".red(), val_err));
     }
 
     out
@@ -937,8 +1100,8 @@ fn format_task_already_complete(id: &str) -> String {
         "mont ready".cyan()
     ));
     out.push_str(&format!(
-        "    2. Edit the task to remove complete status: {}\n",
-        format!("mont {}", id).cyan()
+        "    2. Reopen it if more work is needed: {}\n",
+        format!("mont reopen {} -r <reason>", id).cyan()
     ));
 
     out
@@ -1144,6 +1307,242 @@ fn format_multi_edit_requires_editor() -> String {
     out
 }
 
+fn format_task_assigned_to_other(task_id: &str, assignee: &str) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!(
+        "task '{}' is assigned to '{}'\n",
+        task_id.yellow(),
+        assignee.cyan()
+    ));
+    out.push('\n');
+    out.push_str(&format!("  {}\n", "Someone else owns this task, so starting it could duplicate their work.".dimmed()));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    1. Choose one of your own tasks: {}\n",
+        "mont mine".cyan()
+    ));
+    out.push_str(&format!(
+        "    2. Take over the task: {}\n",
+        format!("mont start {} --claim", task_id).cyan()
+    ));
+
+    out
+}
+
+fn format_unknown_user() -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str("could not determine the current user\n");
+    out.push('\n');
+    out.push_str(&format!("  {}\n", "Neither 'user' in .tasks/config.yml nor $USER is set.".dimmed()));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    1. Set the user in config.yml: {}\n",
+        "user: your-name".cyan()
+    ));
+    out.push_str(&format!(
+        "    2. Or export it for this shell: {}\n",
+        "export USER=your-name".cyan()
+    ));
+
+    out
+}
+
+fn format_task_blocked(id: &str, reason: Option<&str>) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("task '{}' is blocked\n", id.yellow()));
+    out.push('\n');
+    match reason {
+        Some(reason) => out.push_str(&format!("  {} {}\n", "Reason:".dimmed(), reason)),
+        None => out.push_str(&format!("  {}\n", "This task cannot proceed until its blocker is resolved.".dimmed())),
+    }
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    1. Once the blocker is resolved: {}\n",
+        format!("mont unblock {}", id).cyan()
+    ));
+    out.push_str(&format!(
+        "    2. Choose a different task: {}\n",
+        "mont ready".cyan()
+    ));
+
+    out
+}
+
+fn format_task_not_blocked(id: &str) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("task '{}' is not blocked\n", id.yellow()));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    View the task's status: {}\n",
+        format!("mont show {}", id).cyan()
+    ));
+
+    out
+}
+
+fn format_task_cancelled(id: &str) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("task '{}' was cancelled\n", id.yellow()));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    See why it was cancelled: {}\n",
+        format!("mont show {}", id).cyan()
+    ));
+    out.push_str(&format!(
+        "    Revive it: {}\n",
+        format!("mont reopen {} -r <reason>", id).cyan()
+    ));
+
+    out
+}
+
+fn format_task_not_closed(id: &str) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("task '{}' is not complete or cancelled\n", id.yellow()));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    Only finished tasks can be reopened. View its status: {}\n",
+        format!("mont show {}", id).cyan()
+    ));
+
+    out
+}
+
+fn format_cannot_start_epic(id: &str) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("cannot start epic '{}'\n", id.yellow()));
+    out.push('\n');
+    out.push_str(&format!("  {}\n", "Epics complete automatically once all their children are done.".dimmed()));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    See the epic's tasks: {}\n",
+        format!("mont show {} --group", id).cyan()
+    ));
+    out.push_str(&format!(
+        "    Pick one that's ready: {}\n",
+        "mont ready".cyan()
+    ));
+
+    out
+}
+
+fn format_unknown_milestone(name: &str) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("milestone '{}' is not defined\n", name.yellow()));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "To fix this".bold()));
+    out.push_str(&format!(
+        "    1. List defined milestones: {}\n",
+        "mont milestone".cyan()
+    ));
+    out.push_str(&format!(
+        "    2. Add it to .tasks/config.yml: {}\n",
+        format!("milestones: [{{ name: {}, date: YYYY-MM-DD }}]", name).cyan()
+    ));
+
+    out
+}
+
+fn format_migrate_error(error: &MigrateError) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    match error {
+        MigrateError::Newer { found, supported } => {
+            out.push_str(".tasks was written by a newer version of mont\n");
+            out.push('\n');
+            out.push_str(&format!(
+                "  {}\n",
+                format!("Its format version is {}; this mont supports up to {}.", found, supported).dimmed()
+            ));
+            out.push('\n');
+            out.push_str(&format!("  {}:\n", "To fix this".bold()));
+            out.push_str(&format!("    Upgrade mont: {}\n", "cargo install mont".cyan()));
+        }
+        MigrateError::Outdated { found, current } => {
+            out.push_str(&format!(".tasks uses an older format (version {})\n", found));
+            out.push('\n');
+            out.push_str(&format!(
+                "  {}\n",
+                format!("This mont reads format {}. Nothing has been changed.", current).dimmed()
+            ));
+            out.push('\n');
+            out.push_str(&format!("  {}:\n", "To fix this".bold()));
+            out.push_str(&format!("    1. Preview the upgrade: {}\n", "mont migrate --dry-run".cyan()));
+            out.push_str(&format!("    2. Upgrade the directory: {}\n", "mont migrate".cyan()));
+        }
+        MigrateError::Io { .. } | MigrateError::Config(_) => {
+            out.push_str(&format!("{}\n", error));
+        }
+    }
+
+    out
+}
+
+
+
+fn format_invalid_query(error: &QueryError) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("invalid query: {}\n", error));
+    out.push('\n');
+    out.push_str(&format!("  {}:\n", "Query syntax".bold()));
+    out.push_str(&format!(
+        "    {}\n",
+        "status:ready type:task tag:api after:auth-backend title~\"login\"".cyan()
+    ));
+    out.push_str(&format!(
+        "    {}\n",
+        "Terms are ANDed; prefix one with '-' to negate it".dimmed()
+    ));
+
+    out
+}
+
+fn format_no_matches(query: &str) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}: ", "error".red().bold()));
+    out.push_str(&format!("no tasks match '{}'\n", query.yellow()));
+    out.push('\n');
+    out.push_str(&format!(
+        "  {}\n",
+        format!("Preview matches with: mont list --query '{}'", query).dimmed()
+    ));
+
+    out
+}
+
+impl From<QueryError> for AppError {
+    fn from(e: QueryError) -> Self {
+        AppError::InvalidQuery(e)
+    }
+}
+
 impl From<EditorError> for AppError {
     fn from(e: EditorError) -> Self {
         AppError::Editor(e)
@@ -1156,6 +1555,12 @@ impl From<LoadError> for AppError {
     }
 }
 
+impl From<MigrateError> for AppError {
+    fn from(e: MigrateError) -> Self {
+        AppError::Migrate(e)
+    }
+}
+
 impl From<TransactionError> for AppError {
     fn from(e: TransactionError) -> Self {
         match e {
@@ -1189,6 +1594,7 @@ impl From<TransactionError> for AppError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::context::{CycleEdge, EdgeKind};
 
     fn strip_ansi(s: &str) -> String {
         let re = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
@@ -1235,14 +1641,21 @@ mod tests {
     fn test_format_cycle_detected() {
         let err = AppError::Validation {
             tasks_dir: ".tasks".to_string(),
-            source: ValidationError::CycleDetected,
+            source: ValidationError::CycleDetected {
+                cycle: vec![
+                    CycleEdge { from: "a".to_string(), to: "b".to_string(), kind: EdgeKind::Before },
+                    CycleEdge { from: "b".to_string(), to: "a".to_string(), kind: EdgeKind::After },
+                ],
+            },
         };
         let output = err.to_string();
         let stripped = strip_ansi(&output);
 
         assert!(stripped.contains("error:"));
         assert!(stripped.contains("cycle detected"));
-        assert!(stripped.contains("circular dependency"));
+        assert!(stripped.contains("a → b → a"));
+        assert!(stripped.contains(".tasks/a.md lists 'b' under before"));
+        assert!(stripped.contains("Remove 'a' from after in .tasks/b.md"));
         assert!(stripped.contains("To fix this"));
     }
 
@@ -1303,7 +1716,7 @@ mod tests {
 
     #[test]
     fn test_extension_trait_validation() {
-        let result: Result<(), ValidationError> = Err(ValidationError::CycleDetected);
+        let result: Result<(), ValidationError> = Err(ValidationError::DuplicateTaskId("a".to_string()));
         let app_result = result.with_tasks_dir(".tasks");
         assert!(app_result.is_err());
 
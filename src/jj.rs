@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use thiserror::Error;
@@ -211,6 +211,61 @@ impl RevisionDiff {
     }
 }
 
+/// Lists the files under `path` at revision `rev`, relative to the
+/// current directory.
+pub fn file_list(rev: &str, path: &Path) -> Result<Vec<PathBuf>, JJError> {
+    let output = Command::new("jj")
+        .args(["file", "list", "-r", rev])
+        .arg(path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(JJError::CommandFailed(stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Lists the files under `path` that differ between revisions `from` and
+/// `to`, relative to the current directory.
+pub fn changed_files(from: &str, to: &str, path: &Path) -> Result<Vec<PathBuf>, JJError> {
+    let output = Command::new("jj")
+        .args(["diff", "--name-only", "--from", from, "--to", to])
+        .arg(path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(JJError::CommandFailed(stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Reads a file as it was at revision `rev`.
+pub fn file_show(rev: &str, path: &Path) -> Result<String, JJError> {
+    let output = Command::new("jj")
+        .args(["file", "show", "-r", rev])
+        .arg(path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(JJError::CommandFailed(stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 /// Gets the change history for a file, including diffs.
 ///
 /// Returns a list of revisions that modified the file, along with their diffs.
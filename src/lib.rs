@@ -7,9 +7,9 @@ pub mod render;
 
 // Re-export commonly used types from context module for convenience
 pub use context::{
-    parse, GlobalConfig, GraphReadError, LoadError, MontContext, Op, ParseError, SettingsError,
-    Status, Task, TaskGraph, TaskType, Transaction, TransactionError, ValidationError,
-    GateItem, GateStatus,
+    parse, parse_age, parse_due_date, Blocker, Cancellation, Effort, EffortParseError, FrontmatterFormat, GlobalConfig, GraphReadError, LoadError, MigrateError, MigrationReport, Milestone, MontContext, Op,
+    ParseError, Query, QueryError, Reopening, Session, SettingsError, Status, Task, TaskGraph, TaskType, Transaction,
+    TransactionError, ValidationError, CycleEdge, EdgeKind, GateItem, GateStatus, FORMAT_VERSION,
 };
 
 // Re-export graph functions for binary
@@ -1,16 +1,17 @@
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use mont::commands;
-use mont::commands::shared::{pick_task, TaskFilter};
+use mont::commands::shared::{canonical_id, resolve_id, TaskFilter};
 use mont::error_fmt::AppError;
-use mont::TaskType;
+use mont::{Query, TaskType};
 
 #[derive(Parser)]
 #[command(name = "mont")]
 #[command(about = "Task management and agent coordination")]
 struct Cli {
-    /// Task ID(s) to edit (shortcut for 'mont task <ids>')
+    /// Task ID(s) or queries to edit (shortcut for 'mont task <ids>')
     #[arg(value_delimiter = ',')]
     ids: Vec<String>,
 
@@ -22,23 +23,100 @@ struct Cli {
 enum Commands {
     /// Show status of in-progress tasks
     #[command(alias = "st")]
-    Status,
+    Status {
+        /// Only show tasks assigned to this user
+        #[arg(long)]
+        assignee: Option<String>,
+    },
     /// List all tasks in the task graph
     List {
         /// Show completed tasks (hidden by default)
         #[arg(long)]
         show_completed: bool,
+        /// Show cancelled tasks (hidden by default)
+        #[arg(long)]
+        show_cancelled: bool,
+        /// Also show tasks moved to .tasks/archive (implies --show-completed)
+        #[arg(long)]
+        include_archived: bool,
+        /// Only show tasks assigned to this user
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Only show tasks in this namespace (subdirectory of .tasks)
+        #[arg(long)]
+        ns: Option<String>,
+        /// Highlight the tasks on the critical path
+        #[arg(long)]
+        critical_path: bool,
+        /// Only show tasks matching a query, e.g. "status:ready tag:api" (closed matches included)
+        #[arg(long, short)]
+        query: Option<Query>,
+    },
+    /// Show the longest chain of open work, to a target task or in the whole graph
+    CriticalPath {
+        /// Task the chain leads to (defaults to the longest chain anywhere)
+        target: Option<String>,
+    },
+    /// Simulate how the open work would run across parallel workers
+    Plan {
+        /// Number of tasks worked on at once (unlimited if not given)
+        #[arg(long, short, value_parser = clap::value_parser!(u32).range(1..))]
+        workers: Option<u32>,
+        /// Duration assumed for tasks without an estimate (e.g. 30m, 2h, 1d)
+        #[arg(long, default_value = "1h")]
+        default_estimate: mont::Effort,
+    },
+    /// Show what completing a task would unblock
+    Impact {
+        /// Task ID. If not provided, opens interactive picker.
+        id: Option<String>,
+    },
+    /// Explain why a task isn't ready to work on
+    Why {
+        /// Task ID. If not provided, opens interactive picker.
+        id: Option<String>,
     },
     /// Show tasks ready to work on
-    Ready,
-    /// Validate the task graph
+    Ready {
+        /// Only show tasks effectively due on or before this date (YYYY-MM-DD)
+        #[arg(long, value_parser = mont::parse_due_date)]
+        due_before: Option<NaiveDate>,
+        /// Only show tasks assigned to this user
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Only show ready tasks matching a query, e.g. "tag:api"
+        #[arg(long, short)]
+        query: Option<Query>,
+    },
+    /// Show open tasks assigned to you
+    Mine,
+    /// Show a milestone's tasks and progress, or list all milestones
+    Milestone {
+        /// Milestone name from config.yml. If not provided, lists all milestones.
+        name: Option<String>,
+    },
+    /// Validate the task graph, reporting every problem.
+    ///
+    /// Exits 1 if the graph is invalid or a lint rule set to `error` fails,
+    /// and 2 if task files can't be read or parsed.
     Check {
         /// Specific task ID to validate (validates entire graph if not provided)
         id: Option<String>,
+        /// Fail on lint warnings too
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Compare the task graph at two jj revisions
+    Diff {
+        /// Revision to compare from (e.g. main, @-)
+        from: String,
+        /// Revision to compare to
+        #[arg(default_value = "@")]
+        to: String,
     },
     /// Create or edit tasks (opens multieditor)
     Task {
-        /// Task ID(s) to edit (comma-separated). If empty, opens empty multieditor.
+        /// Task ID(s) to edit (comma-separated). A query edits every match. If empty, opens empty multieditor.
         #[arg(value_delimiter = ',')]
         ids: Vec<String>,
         /// Task type template: task, jot, gate
@@ -110,9 +188,15 @@ enum Commands {
         #[arg(long, short)]
         force: bool,
     },
+    /// Move finished task groups into .tasks/archive
+    Archive {
+        /// Only archive tasks completed or cancelled at least this long ago (e.g. 30d, 2w, 12h)
+        #[arg(long, value_parser = mont::parse_age)]
+        older_than: Option<std::time::Duration>,
+    },
     /// Show details for a single task
     Show {
-        /// Task ID to show. If not provided, opens interactive picker.
+        /// Task ID to show, or a query to show every match. Use ? for the picker.
         id: Option<String>,
         /// Show shortened version (omit description)
         #[arg(long, short)]
@@ -120,6 +204,9 @@ enum Commands {
         /// Include full subgraph of the ID (all connected tasks via before/after)
         #[arg(long, short)]
         group: bool,
+        /// Also look for the task in .tasks/archive
+        #[arg(long)]
+        include_archived: bool,
     },
     /// Mark gates as passed or skipped
     Unlock {
@@ -144,12 +231,47 @@ enum Commands {
     Start {
         /// Task ID to start. If not provided, opens interactive picker.
         id: Option<String>,
+        /// Assign the task to you (config `user` or $USER), taking it over if needed
+        #[arg(long)]
+        claim: bool,
     },
     /// Stop working on a task (makes it ready again)
     Stop {
         /// Task ID to stop. If not provided, uses the in-progress task.
         id: Option<String>,
     },
+    /// Mark a task as blocked (can't proceed) with a reason
+    Block {
+        /// Task ID to block. If not provided, uses the in-progress task.
+        id: Option<String>,
+        /// Why the task can't proceed
+        #[arg(long, short)]
+        reason: String,
+        /// External reference (issue URL, ticket number)
+        #[arg(long = "ref")]
+        reference: Option<String>,
+    },
+    /// Clear the blocked status from a task
+    Unblock {
+        /// Task ID to unblock. If not provided, opens interactive picker.
+        id: Option<String>,
+    },
+    /// Move a completed or cancelled task back to pending
+    Reopen {
+        /// Task ID to reopen. Use ? for interactive picker.
+        id: Option<String>,
+        /// Why the task needs more work
+        #[arg(long, short)]
+        reason: String,
+    },
+    /// Mark a task as won't-do, keeping it as a record
+    Cancel {
+        /// Task ID to cancel. Use ? for interactive picker.
+        id: Option<String>,
+        /// Why the task is being abandoned
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
     /// Complete a task and commit
     Done {
         /// Task ID to complete. If not provided, detects from current revision.
@@ -158,6 +280,11 @@ enum Commands {
         #[arg(long, short)]
         message: Option<String>,
     },
+    /// Show estimate vs actual effort across tasks
+    Stats {
+        /// Task ID whose group to summarize. If not provided, covers all tasks.
+        id: Option<String>,
+    },
     /// Generate a prompt based on current task state
     Prompt,
     /// Launch Claude Code with generated prompt
@@ -170,6 +297,12 @@ enum Commands {
     },
     /// Initialize mont in the current directory
     Init,
+    /// Upgrade .tasks to the current on-disk format
+    Migrate {
+        /// Show what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 fn parse_task_type(s: &str) -> Result<TaskType, String> {
@@ -177,8 +310,9 @@ fn parse_task_type(s: &str) -> Result<TaskType, String> {
         "task" => Ok(TaskType::Task),
         "jot" => Ok(TaskType::Jot),
         "gate" => Ok(TaskType::Gate),
+        "epic" => Ok(TaskType::Epic),
         _ => Err(format!(
-            "invalid task type '{}', must be one of: task, jot, gate",
+            "invalid task type '{}', must be one of: task, jot, gate, epic",
             s
         )),
     }
@@ -205,7 +339,7 @@ fn main() {
 
     if let Err(e) = run(cli) {
         eprint!("{}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
 
@@ -215,6 +349,21 @@ fn run(cli: Cli) -> Result<(), AppError> {
         return commands::init();
     }
 
+    // Migrate runs on directories the current format can't load
+    if let Some(Commands::Migrate { dry_run }) = cli.command {
+        return commands::migrate(Path::new(".tasks"), dry_run);
+    }
+
+    // Check reports every problem in directories that fail to load
+    if let Some(Commands::Check { id, strict }) = &cli.command {
+        return commands::check(Path::new(".tasks"), id.as_deref(), *strict);
+    }
+
+    // Diff reads both revisions from jj, whatever state the working copy is in
+    if let Some(Commands::Diff { from, to }) = &cli.command {
+        return commands::diff(Path::new(".tasks"), from, to);
+    }
+
     // Load context once for all commands
     let ctx = mont::MontContext::load(PathBuf::from(".tasks"))?;
 
@@ -243,19 +392,56 @@ fn run(cli: Cli) -> Result<(), AppError> {
     };
 
     match command {
-        Commands::Status => {
-            commands::status(&ctx);
+        Commands::Status { assignee } => {
+            commands::status(&ctx, assignee.as_deref());
             Ok(())
         }
-        Commands::List { show_completed } => {
-            commands::list(&ctx, show_completed);
-            Ok(())
+        Commands::List { show_completed, show_cancelled, include_archived, assignee, ns, critical_path, query } => {
+            commands::list(
+                &ctx,
+                commands::ListArgs {
+                    show_completed,
+                    show_cancelled,
+                    include_archived,
+                    assignee,
+                    namespace: ns,
+                    critical_path,
+                    query,
+                },
+            )
+        }
+        Commands::CriticalPath { target } => {
+            let target = match target {
+                Some(id) => Some(resolve_id(&ctx.graph(), id, TaskFilter::Active)?),
+                None => None,
+            };
+            commands::critical_path(&ctx, target.as_deref())
+        }
+        Commands::Plan { workers, default_estimate } => {
+            commands::plan(&ctx, workers.map(|w| w as usize), default_estimate)
+        }
+        Commands::Impact { id } => {
+            let resolved_id = match id {
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Active)?,
+                None => return Err(AppError::IdRequired("impact".to_string())),
+            };
+            commands::impact(&ctx, &resolved_id)
+        }
+        Commands::Why { id } => {
+            let resolved_id = match id {
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Active)?,
+                None => return Err(AppError::IdRequired("why".to_string())),
+            };
+            commands::why(&ctx, &resolved_id)
         }
-        Commands::Ready => {
-            commands::ready(&ctx);
+        Commands::Ready { due_before, assignee, query } => {
+            commands::ready(&ctx, due_before, assignee.as_deref(), query.as_ref());
             Ok(())
         }
-        Commands::Check { id } => commands::check(&ctx, id.as_deref()),
+        Commands::Mine => commands::mine(&ctx),
+        Commands::Milestone { name } => commands::milestone(&ctx, name.as_deref()),
+        Commands::Check { .. } => unreachable!("Check command should be handled before context loading"),
+        Commands::Diff { .. } => unreachable!("Diff command should be handled before context loading"),
         Commands::Task {
             ids,
             r#type,
@@ -307,8 +493,7 @@ fn run(cli: Cli) -> Result<(), AppError> {
             }
 
             let resolved_id = match id {
-                Some(id) if id == "?" => pick_task(&ctx.graph(), TaskFilter::Jots)?,
-                Some(id) => id,
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Jots)?,
                 None => return Err(AppError::IdRequired("distill".to_string())),
             };
             commands::distill(
@@ -324,24 +509,22 @@ fn run(cli: Cli) -> Result<(), AppError> {
         }
         Commands::Delete { id, force } => {
             let resolved_id = match id {
-                Some(id) if id == "?" => pick_task(&ctx.graph(), TaskFilter::Active)?,
-                Some(id) => id,
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Active)?,
                 None => return Err(AppError::IdRequired("delete".to_string())),
             };
             commands::delete(&ctx, &resolved_id, force)
         }
-        Commands::Show { id, short, group } => {
+        Commands::Archive { older_than } => commands::archive(&ctx, older_than),
+        Commands::Show { id, short, group, include_archived } => {
             let resolved_id = match id {
-                Some(id) if id == "?" => pick_task(&ctx.graph(), TaskFilter::All)?,
-                Some(id) => id,
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::All)?,
                 None => return Err(AppError::IdRequired("show".to_string())),
             };
-            commands::show(&ctx, &resolved_id, short, group)
+            commands::show(&ctx, &resolved_id, short, group, include_archived)
         }
         Commands::Unlock { id, passed, skipped } => {
             let resolved_id = match id {
-                Some(id) if id == "?" => pick_task(&ctx.graph(), TaskFilter::InProgress)?,
-                Some(id) => id,
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::InProgress)?,
                 None => return Err(AppError::IdRequired("unlock".to_string())),
             };
             commands::unlock(
@@ -355,8 +538,7 @@ fn run(cli: Cli) -> Result<(), AppError> {
         }
         Commands::Lock { id, gates } => {
             let resolved_id = match id {
-                Some(id) if id == "?" => pick_task(&ctx.graph(), TaskFilter::InProgress)?,
-                Some(id) => id,
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::InProgress)?,
                 None => return Err(AppError::IdRequired("lock".to_string())),
             };
             commands::unlock::lock(
@@ -367,23 +549,59 @@ fn run(cli: Cli) -> Result<(), AppError> {
                 },
             )
         }
-        Commands::Start { id } => {
+        Commands::Start { id, claim } => {
             let resolved_id = match id {
-                Some(id) if id == "?" => pick_task(&ctx.graph(), TaskFilter::Active)?,
-                Some(id) => id,
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Active)?,
                 None => return Err(AppError::IdRequired("start".to_string())),
             };
-            commands::start(&ctx, &resolved_id)
+            commands::start(&ctx, &resolved_id, claim)
         }
         Commands::Stop { id } => {
             let resolved_id = match id {
-                Some(id) if id == "?" => pick_task(&ctx.graph(), TaskFilter::InProgress)?,
-                Some(id) => id,
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::InProgress)?,
                 None => detect_in_progress_task(&ctx)?,
             };
             commands::stop(&ctx, &resolved_id)
         }
-        Commands::Done { id, message } => commands::done(&ctx, id.as_deref(), message.as_deref()),
+        Commands::Block { id, reason, reference } => {
+            let resolved_id = match id {
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Active)?,
+                None => detect_in_progress_task(&ctx)?,
+            };
+            commands::block(&ctx, &resolved_id, &reason, reference.as_deref())
+        }
+        Commands::Unblock { id } => {
+            let resolved_id = match id {
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Blocked)?,
+                None => return Err(AppError::IdRequired("unblock".to_string())),
+            };
+            commands::unblock(&ctx, &resolved_id)
+        }
+        Commands::Reopen { id, reason } => {
+            let resolved_id = match id {
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Closed)?,
+                None => return Err(AppError::IdRequired("reopen".to_string())),
+            };
+            commands::reopen(&ctx, &resolved_id, &reason)
+        }
+        Commands::Cancel { id, reason } => {
+            let resolved_id = match id {
+                Some(id) => resolve_id(&ctx.graph(), id, TaskFilter::Active)?,
+                None => return Err(AppError::IdRequired("cancel".to_string())),
+            };
+            commands::cancel(&ctx, &resolved_id, reason.as_deref())
+        }
+        Commands::Done { id, message } => {
+            let id = id.map(|id| canonical_id(&ctx.graph(), id));
+            commands::done(&ctx, id.as_deref(), message.as_deref())
+        }
+        Commands::Stats { id } => {
+            let resolved_id = match id {
+                Some(id) => Some(resolve_id(&ctx.graph(), id, TaskFilter::All)?),
+                None => None,
+            };
+            commands::stats(&ctx, resolved_id.as_deref())
+        }
         Commands::Prompt => commands::prompt(&ctx),
         Commands::Claude { id, ignore } => {
             if ignore {
@@ -392,12 +610,12 @@ fn run(cli: Cli) -> Result<(), AppError> {
             } else {
                 // Need a task id - from arg or picker
                 let resolved_id = match id {
-                    Some(id) if id == "?" => {
+                    Some(id) if id.starts_with('?') => {
                         // Pre-validate before showing picker to avoid wasting user time
                         commands::claude_pre_validate(&ctx)?;
-                        pick_task(&ctx.graph(), TaskFilter::Ready)?
+                        resolve_id(&ctx.graph(), id, TaskFilter::Ready)?
                     }
-                    Some(id) => id,
+                    Some(id) => canonical_id(&ctx.graph(), id),
                     None => return Err(AppError::IdRequired("claude".to_string())),
                 };
                 commands::claude(&ctx, &resolved_id)
@@ -405,6 +623,7 @@ fn run(cli: Cli) -> Result<(), AppError> {
         }
         // Init is handled early before context loading
         Commands::Init => unreachable!("Init command should be handled before context loading"),
+        Commands::Migrate { .. } => unreachable!("Migrate command should be handled before context loading"),
     }
 }
 
@@ -413,7 +632,7 @@ mod tests {
     use tempfile::TempDir;
 
     use mont::commands;
-    use mont::{MontContext, Task, TaskType};
+    use mont::{MontContext, Task};
 
     fn create_temp_context() -> (TempDir, MontContext) {
         let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
@@ -460,15 +679,8 @@ Description here.
         // Create a task directly with a known ID
         let task = Task {
             id: "edit-test".to_string(),
-            new_id: None,
             title: Some("Original title".to_string()),
-            description: String::new(),
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            task_type: TaskType::Task,
-            status: None,
-            deleted: false,
+            ..Default::default()
         };
         ctx.insert(task).unwrap();
 
@@ -500,36 +712,23 @@ title: Updated title
     }
 
     #[test]
-    fn test_task_rename_propagates_references() {
+    fn test_task_rename_leaves_references_resolvable() {
         let (temp_dir, ctx) = create_temp_context();
 
         // Create parent task directly with a known ID
         let parent = Task {
             id: "parent-task".to_string(),
-            new_id: None,
             title: Some("Parent".to_string()),
-            description: String::new(),
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            task_type: TaskType::Task,
-            status: None,
-            deleted: false,
+            ..Default::default()
         };
         ctx.insert(parent).unwrap();
 
         // Create child task with before reference
         let child = Task {
             id: "child-task".to_string(),
-            new_id: None,
             title: Some("Child".to_string()),
-            description: String::new(),
             before: vec!["parent-task".to_string()],
-            after: vec![],
-            gates: vec![],
-            task_type: TaskType::Task,
-            status: None,
-            deleted: false,
+            ..Default::default()
         };
         ctx.insert(child).unwrap();
 
@@ -561,11 +760,19 @@ title: Parent
         assert!(!temp_dir.path().join("parent-task.md").exists());
         assert!(temp_dir.path().join("renamed-parent.md").exists());
 
-        // Verify child's before reference was updated
+        // The renamed task keeps its uid and remembers its old id
+        let renamed = ctx.graph().get("renamed-parent").cloned().unwrap();
+        assert!(renamed.uid.is_some());
+        assert_eq!(renamed.aliases, vec!["parent-task".to_string()]);
+
+        // The child's file is untouched, but its reference resolves to the new id
         let child_content =
             std::fs::read_to_string(temp_dir.path().join("child-task.md")).unwrap();
-        assert!(child_content.contains("- renamed-parent"));
-        assert!(!child_content.contains("- parent-task"));
+        assert!(child_content.contains("- parent-task"));
+        assert_eq!(ctx.graph().get("child-task").unwrap().before, vec!["renamed-parent"]);
+
+        let reloaded = MontContext::load(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.graph().get("child-task").unwrap().before, vec!["renamed-parent"]);
     }
 
     #[test]
@@ -114,6 +114,15 @@ fn task_content_differs(a: &Task, b: &Task) -> bool {
         || a.gates != b.gates
         || a.task_type != b.task_type
         || a.status != b.status
+        || a.estimate != b.estimate
+        || a.sessions != b.sessions
+        || a.due != b.due
+        || a.assignee != b.assignee
+        || a.blocker != b.blocker
+        || a.cancelled != b.cancelled
+        || a.reopened != b.reopened
+        || a.milestone != b.milestone
+        || a.tags != b.tags
 }
 
 /// Fill in empty IDs in a diff before displaying to the user.
@@ -168,15 +177,10 @@ pub fn apply_diff(ctx: &MontContext, diff: MultiEditDiff) -> Result<ApplyResult,
         result.deleted.push(id.clone());
     }
 
-    // Process updates - handle renames
+    // Process updates - renamed tasks keep their old ID as an alias
     for (original_id, mut task) in diff.updates {
         let id_changed = task.id != original_id;
 
-        // If ID changed, rewrite references to point to new ID
-        if id_changed {
-            txn.rewrite_references(&*graph, &original_id, Some(&task.id));
-        }
-
         // Generate ID if empty
         if task.id.is_empty() {
             task.id = ctx.generate_id(&graph)
@@ -211,20 +215,12 @@ pub fn apply_diff(ctx: &MontContext, diff: MultiEditDiff) -> Result<ApplyResult,
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::TaskType;
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
             title: Some(title.to_string()),
-            description: String::new(),
-            before: vec![],
-            after: vec![],
-            gates: vec![],
-            task_type: TaskType::Task,
-            status: None,
-            deleted: false,
+            ..Default::default()
         }
     }
 
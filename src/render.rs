@@ -1,22 +1,61 @@
 use std::collections::{HashMap, HashSet};
 
+use chrono::NaiveDate;
 use owo_colors::OwoColorize;
 use renderdag::{Ancestor, GraphRowRenderer, Renderer};
 
-use crate::context::graph;
-use crate::{Task, TaskGraph, TaskType, GateStatus};
+use crate::context::graph::{self, EpicProgress};
+use crate::{Blocker, Query, Task, TaskGraph, TaskType, GateStatus};
 
 type BoxRenderer = renderdag::BoxDrawingRenderer<String, GraphRowRenderer<String>>;
 
 pub const MAX_TITLE_LEN: usize = 60;
 
+/// Tasks due within this many days are highlighted as due soon.
+pub const DUE_SOON_DAYS: i64 = 3;
+
+/// How close a task is to its due date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DueState {
+    Overdue,
+    Soon,
+    Later,
+}
+
+impl DueState {
+    pub fn classify(due: NaiveDate, today: NaiveDate) -> Self {
+        let days_left = (due - today).num_days();
+        if days_left < 0 {
+            DueState::Overdue
+        } else if days_left <= DUE_SOON_DAYS {
+            DueState::Soon
+        } else {
+            DueState::Later
+        }
+    }
+}
+
+/// Format a due date relative to today, colored by urgency.
+pub fn format_due(due: NaiveDate, today: NaiveDate) -> String {
+    let days_left = (due - today).num_days();
+    match DueState::classify(due, today) {
+        DueState::Overdue => format!("overdue {}d", -days_left).red().bold().to_string(),
+        DueState::Soon if days_left == 0 => "due today".yellow().to_string(),
+        DueState::Soon => format!("due in {}d", days_left).yellow().to_string(),
+        DueState::Later => format!("due {}", due).bright_black().to_string(),
+    }
+}
+
 /// Display state of a task for rendering purposes.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DisplayState {
     Complete,
+    Cancelled,
     Gate,
+    Epic,
     Jot,
     InProgress,
+    Blocked,
     Available,
     Waiting,
 }
@@ -43,24 +82,36 @@ pub struct TaskDisplayView {
     pub task_type: TaskType,
     pub state: DisplayState,
     pub gate_progress: Option<GateProgress>,
+    /// Rolled-up progress for open epics
+    pub epic_progress: Option<EpicProgress>,
+    /// Effective due date and today's date, set via `with_due`
+    pub due: Option<(NaiveDate, NaiveDate)>,
+    pub assignee: Option<String>,
 }
 
 impl TaskDisplayView {
     /// Create a TaskDisplayView from a Task.
     ///
     /// - `graph` is used to determine if the task is available (dependencies complete)
+    ///   and to roll up progress for epics
     /// - `default_gates` is used to calculate gate progress for in-progress tasks
     pub fn from_task(task: &Task, graph: &TaskGraph, default_gates: &[String]) -> Self {
-        let is_available = !task.is_complete() && !task.is_gate() && graph::is_available(task, graph);
+        let is_available = !task.is_closed() && !task.is_gate() && graph::is_available(task, graph);
 
         let state = if task.is_complete() {
             DisplayState::Complete
+        } else if task.is_cancelled() {
+            DisplayState::Cancelled
         } else if task.is_gate() {
             DisplayState::Gate
+        } else if task.is_epic() {
+            DisplayState::Epic
         } else if task.is_jot() {
             DisplayState::Jot
         } else if task.is_in_progress() {
             DisplayState::InProgress
+        } else if task.is_blocked() {
+            DisplayState::Blocked
         } else if is_available {
             DisplayState::Available
         } else {
@@ -73,22 +124,55 @@ impl TaskDisplayView {
             None
         };
 
+        let epic_progress = if state == DisplayState::Epic {
+            Some(graph::epic_progress(&task.id, graph, default_gates))
+        } else {
+            None
+        };
+
         Self {
             id: task.id.clone(),
             title: task.title.clone().unwrap_or_default(),
             task_type: task.task_type,
             state,
             gate_progress,
+            epic_progress,
+            due: None,
+            assignee: task.assignee.clone(),
         }
     }
 
+    /// Attach an effective due date so lines show overdue/due-soon markers.
+    ///
+    /// Completed and cancelled tasks and gates never show a due date.
+    pub fn with_due(mut self, due: Option<NaiveDate>, today: NaiveDate) -> Self {
+        if !matches!(self.state, DisplayState::Complete | DisplayState::Cancelled | DisplayState::Gate) {
+            self.due = due.map(|d| (d, today));
+        }
+        self
+    }
+
+    /// Get the epic progress indicator if applicable (e.g., "[3/5 60%]").
+    pub fn epic_progress_colored(&self) -> Option<String> {
+        let progress = self.epic_progress?;
+        Some(format_epic_progress(&progress))
+    }
+
+    /// Get the due date marker if applicable (e.g., "overdue 2d").
+    pub fn due_colored(&self) -> Option<String> {
+        self.due.map(|(due, today)| format_due(due, today))
+    }
+
     /// Get the type tag for display (e.g., "[task]", "[jot]", "[gate]").
     pub fn type_tag(&self) -> &'static str {
         match self.state {
             DisplayState::Complete => "[done]",
+            DisplayState::Cancelled => "[cncl]",
             DisplayState::Gate => "[gate]",
+            DisplayState::Epic => "[epic]",
             DisplayState::Jot => "[jot] ",
             DisplayState::InProgress => "[work]",
+            DisplayState::Blocked => "[blkd]",
             DisplayState::Available => "[task]",
             DisplayState::Waiting => "[wait]",
         }
@@ -98,9 +182,12 @@ impl TaskDisplayView {
     pub fn type_tag_colored(&self) -> String {
         match self.state {
             DisplayState::Complete => "[done]".bright_black().to_string(),
+            DisplayState::Cancelled => "[cncl]".bright_black().to_string(),
             DisplayState::Gate => "[gate]".purple().to_string(),
+            DisplayState::Epic => "[epic]".blue().to_string(),
             DisplayState::Jot => "[jot] ".yellow().to_string(),
             DisplayState::InProgress => "[work]".yellow().to_string(),
+            DisplayState::Blocked => "[blkd]".red().to_string(),
             DisplayState::Available => "[task]".bright_green().to_string(),
             DisplayState::Waiting => "[wait]".bright_black().to_string(),
         }
@@ -109,9 +196,11 @@ impl TaskDisplayView {
     /// Get the colored ID for display.
     pub fn id_colored(&self) -> String {
         match self.state {
-            DisplayState::Complete => self.id.bright_black().bold().to_string(),
+            DisplayState::Complete | DisplayState::Cancelled => self.id.bright_black().bold().to_string(),
             DisplayState::Gate => self.id.purple().bold().to_string(),
+            DisplayState::Epic => self.id.blue().bold().to_string(),
             DisplayState::Jot | DisplayState::InProgress => self.id.yellow().bold().to_string(),
+            DisplayState::Blocked => self.id.red().bold().to_string(),
             DisplayState::Available => self.id.bright_green().bold().to_string(),
             DisplayState::Waiting => self.id.bright_black().bold().to_string(),
         }
@@ -121,9 +210,11 @@ impl TaskDisplayView {
     pub fn id_colored_padded(&self, width: usize) -> String {
         let padded = format!("{:width$}", self.id);
         match self.state {
-            DisplayState::Complete => padded.bright_black().bold().to_string(),
+            DisplayState::Complete | DisplayState::Cancelled => padded.bright_black().bold().to_string(),
             DisplayState::Gate => padded.purple().bold().to_string(),
+            DisplayState::Epic => padded.blue().bold().to_string(),
             DisplayState::Jot | DisplayState::InProgress => padded.yellow().bold().to_string(),
+            DisplayState::Blocked => padded.red().bold().to_string(),
             DisplayState::Available => padded.bright_green().bold().to_string(),
             DisplayState::Waiting => padded.bright_black().bold().to_string(),
         }
@@ -134,8 +225,11 @@ impl TaskDisplayView {
         let truncated = truncate_to(&self.title, max_len);
         match self.state {
             DisplayState::Complete => truncated.bright_black().to_string(),
+            DisplayState::Cancelled => truncated.bright_black().strikethrough().to_string(),
             DisplayState::Gate => truncated.purple().to_string(),
+            DisplayState::Epic => truncated.blue().to_string(),
             DisplayState::Jot | DisplayState::InProgress => truncated.yellow().to_string(),
+            DisplayState::Blocked => truncated.red().to_string(),
             DisplayState::Available => truncated.bright_green().to_string(),
             DisplayState::Waiting => truncated.bright_black().to_string(),
         }
@@ -165,9 +259,24 @@ impl TaskDisplayView {
             self.title_colored(max_title_len)
         );
 
-        match self.gate_progress_colored() {
+        let base = match self.epic_progress_colored() {
             Some(progress) => format!("{} {}", base, progress),
             None => base,
+        };
+
+        let base = match self.gate_progress_colored() {
+            Some(progress) => format!("{} {}", base, progress),
+            None => base,
+        };
+
+        let base = match &self.assignee {
+            Some(assignee) => format!("{} {}", base, format!("@{}", assignee).cyan()),
+            None => base,
+        };
+
+        match self.due_colored() {
+            Some(due) => format!("{} {}", base, due),
+            None => base,
         }
     }
 
@@ -180,9 +289,24 @@ impl TaskDisplayView {
             self.title_colored(max_title_len)
         );
 
-        match self.gate_progress_colored() {
+        let base = match self.epic_progress_colored() {
+            Some(progress) => format!("{} {}", base, progress),
+            None => base,
+        };
+
+        let base = match self.gate_progress_colored() {
             Some(progress) => format!("{} {}", base, progress),
             None => base,
+        };
+
+        let base = match &self.assignee {
+            Some(assignee) => format!("{} {}", base, format!("@{}", assignee).cyan()),
+            None => base,
+        };
+
+        match self.due_colored() {
+            Some(due) => format!("{} {}", base, due),
+            None => base,
         }
     }
 
@@ -190,12 +314,45 @@ impl TaskDisplayView {
     pub fn status_colored(&self) -> String {
         match self.state {
             DisplayState::Complete => "complete".bright_black().to_string(),
+            DisplayState::Cancelled => "cancelled".bright_black().to_string(),
             DisplayState::InProgress => "in progress".yellow().to_string(),
+            DisplayState::Blocked => "blocked".red().to_string(),
             _ => "incomplete".white().to_string(),
         }
     }
 }
 
+/// Format epic progress as "[complete/total pct%]", green once finished.
+pub fn format_epic_progress(progress: &EpicProgress) -> String {
+    let text = format!("[{}/{} {}%]", progress.complete, progress.total, progress.percent());
+    if progress.is_finished() {
+        text.bright_green().to_string()
+    } else {
+        text.blue().to_string()
+    }
+}
+
+/// Describe remaining work and gate status of an epic, e.g.
+/// "4h remaining, gates 7/10 passed".
+pub fn format_epic_details(progress: &EpicProgress) -> String {
+    let mut parts = Vec::new();
+    if !progress.remaining_estimate.is_zero() {
+        parts.push(format!("{} remaining", progress.remaining_estimate));
+    }
+    if progress.gates_total > 0 {
+        parts.push(format!("gates {}/{} passed", progress.gates_passed, progress.gates_total));
+    }
+    parts.join(", ")
+}
+
+/// Format a blocker as "reason (ref)".
+pub fn format_blocker(blocker: &Blocker) -> String {
+    match &blocker.reference {
+        Some(reference) => format!("{} ({})", blocker.reason, reference),
+        None => blocker.reason.clone(),
+    }
+}
+
 /// Format a gate status as (icon, colored_gate_id).
 pub fn format_gate_status(gate_id: &str, status: GateStatus) -> (String, String) {
     match status {
@@ -208,9 +365,9 @@ pub fn format_gate_status(gate_id: &str, status: GateStatus) -> (String, String)
 
 /// Print a gates section for a task.
 /// Shows all gates (task gates + default gates) with their status.
-/// Does nothing for gate-type or jot-type tasks.
+/// Does nothing for gate-type, jot-type or epic tasks (epics roll up their children's gates).
 pub fn print_gates_section(task: &Task, all_gate_ids: &[String], indent: &str, label_width: usize) {
-    if task.is_gate() || task.is_jot() {
+    if task.is_gate() || task.is_jot() || task.is_epic() {
         return;
     }
 
@@ -243,9 +400,12 @@ pub fn print_gates_section(task: &Task, all_gate_ids: &[String], indent: &str, l
 pub fn task_marker_for_state(state: DisplayState) -> String {
     match state {
         DisplayState::Complete => "●".bright_black().to_string(),
+        DisplayState::Cancelled => "✕".bright_black().to_string(),
         DisplayState::Gate => "◈".purple().to_string(),
+        DisplayState::Epic => "◆".blue().to_string(),
         DisplayState::Jot => "◇".yellow().to_string(),
         DisplayState::InProgress => "◐".yellow().to_string(),
+        DisplayState::Blocked => "⊘".red().to_string(),
         DisplayState::Available => "◉".bright_green().to_string(),
         DisplayState::Waiting => "○".bright_black().to_string(),
     }
@@ -264,7 +424,7 @@ fn calculate_gate_progress(task: &Task, default_gates: &[String]) -> GateProgres
         return GateProgress { passed: 0, total: 0 };
     }
 
-    // Count passed or skipped from task's validations
+    // Count passed or skipped from task's gates
     let passed = task
         .gates
         .iter()
@@ -277,34 +437,83 @@ fn calculate_gate_progress(task: &Task, default_gates: &[String]) -> GateProgres
     GateProgress { passed, total }
 }
 
-pub fn render_task_graph(graph: &TaskGraph, default_gates: &[String], show_completed: bool) -> String {
+/// Options controlling which tasks `render_task_graph` includes, and which it
+/// highlights.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// Include completed tasks (hidden by default)
+    pub show_completed: bool,
+    pub show_cancelled: bool,
+    /// Only include tasks assigned to this user (gates are hidden)
+    pub assignee: Option<String>,
+    /// Only include tasks in this namespace or those nested inside it
+    pub namespace: Option<String>,
+    /// Tasks to mark as being on the critical path
+    pub critical: HashSet<String>,
+    /// Only include tasks matching this query
+    pub query: Option<Query>,
+}
+
+impl RenderOptions {
+    fn includes(&self, task: &Task, graph: &TaskGraph) -> bool {
+        let assigned = match &self.assignee {
+            Some(assignee) => task.is_assigned_to(assignee),
+            None => true,
+        };
+        assigned && self.in_namespace(task) && self.matches_query(task, graph)
+    }
+
+    fn in_namespace(&self, task: &Task) -> bool {
+        self.namespace.as_deref().is_none_or(|ns| task.in_namespace(ns))
+    }
+
+    fn matches_query(&self, task: &Task, graph: &TaskGraph) -> bool {
+        self.query.as_ref().is_none_or(|q| q.matches(task, graph))
+    }
+}
+
+pub fn render_task_graph(graph: &TaskGraph, default_gates: &[String], options: &RenderOptions) -> String {
     if graph.is_empty() {
         return String::new();
     }
+    let show_completed = options.show_completed;
+    let notes: HashMap<String, String> = options
+        .critical
+        .iter()
+        .map(|id| (id.clone(), "critical".red().bold().to_string()))
+        .collect();
 
-    // Active tasks (not jots, not gates, not complete)
+    // Active tasks (not jots, not gates, not complete or cancelled)
     let mut active: TaskGraph = graph
         .iter()
-        .filter(|(_, t)| !t.is_gate() && !t.is_jot() && !t.is_complete())
+        .filter(|(_, t)| !t.is_gate() && !t.is_jot() && !t.is_closed() && options.includes(t, graph))
         .map(|(k, v)| (k.clone(), v.clone()))
         .collect();
 
     // Standalone jots (jots not connected to other tasks)
     let jots: TaskGraph = graph
         .iter()
-        .filter(|(_, t)| t.is_jot() && !t.is_complete())
+        .filter(|(_, t)| t.is_jot() && !t.is_closed() && options.includes(t, graph))
         .map(|(k, v)| (k.clone(), v.clone()))
         .collect();
 
     let gates: TaskGraph = graph
         .iter()
-        .filter(|(_, t)| t.is_gate())
+        .filter(|(_, t)| {
+            t.is_gate() && options.assignee.is_none() && options.in_namespace(t) && options.matches_query(t, graph)
+        })
         .map(|(k, v)| (k.clone(), v.clone()))
         .collect();
 
     let complete: TaskGraph = graph
         .iter()
-        .filter(|(_, t)| !t.is_gate() && t.is_complete())
+        .filter(|(_, t)| !t.is_gate() && t.is_complete() && options.includes(t, graph))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    let cancelled: TaskGraph = graph
+        .iter()
+        .filter(|(_, t)| t.is_cancelled() && options.includes(t, graph))
         .map(|(k, v)| (k.clone(), v.clone()))
         .collect();
 
@@ -313,34 +522,50 @@ pub fn render_task_graph(graph: &TaskGraph, default_gates: &[String], show_compl
     let mut output = String::new();
 
     if !active.is_empty() {
-        output.push_str(&render_section(&active, default_gates));
+        output.push_str(&render_section(&active, graph, default_gates, &notes));
     }
 
     if !jots.is_empty() {
         if !output.is_empty() {
             output.push('\n');
         }
-        output.push_str(&render_section(&jots, default_gates));
+        output.push_str(&render_section(&jots, graph, default_gates, &notes));
     }
 
     if !gates.is_empty() {
         if !output.is_empty() {
             output.push('\n');
         }
-        output.push_str(&render_section(&gates, default_gates));
+        output.push_str(&render_section(&gates, graph, default_gates, &notes));
     }
 
     if show_completed && !complete.is_empty() {
         if !output.is_empty() {
             output.push('\n');
         }
-        output.push_str(&render_section(&complete, default_gates));
+        output.push_str(&render_section(&complete, graph, default_gates, &notes));
+    }
+
+    if options.show_cancelled && !cancelled.is_empty() {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&render_section(&cancelled, graph, default_gates, &notes));
     }
 
     output
 }
 
-fn render_section(graph: &TaskGraph, default_gates: &[String]) -> String {
+/// Render one section of the list. `graph` holds the tasks to draw and only
+/// decides the layout; each task's state (ready, waiting, epic progress) is
+/// computed against `full_graph`, the unfiltered graph.
+/// `notes` are appended to the lines of the tasks they're keyed by.
+pub fn render_section(
+    graph: &TaskGraph,
+    full_graph: &TaskGraph,
+    default_gates: &[String],
+    notes: &HashMap<String, String>,
+) -> String {
     let components = graph.connected_components();
     let mut output = String::new();
     let mut prev_was_multi = false;
@@ -359,14 +584,14 @@ fn render_section(graph: &TaskGraph, default_gates: &[String]) -> String {
             output.push('\n');
         }
 
-        output.push_str(&render_component(&component, default_gates));
+        output.push_str(&render_component(&component, full_graph, default_gates, notes));
         prev_was_multi = is_multi;
     }
 
     output
 }
 
-fn render_component(graph: &TaskGraph, default_gates: &[String]) -> String {
+fn render_component(graph: &TaskGraph, full_graph: &TaskGraph, default_gates: &[String], notes: &HashMap<String, String>) -> String {
     if graph.is_empty() {
         return String::new();
     }
@@ -387,8 +612,13 @@ fn render_component(graph: &TaskGraph, default_gates: &[String]) -> String {
         };
 
         let ancestors = build_ancestors(task_id, &effective_successors);
-        let marker = task_marker(task, graph);
-        let task_line = format_task_line(task, graph, default_gates);
+        // State comes from the full graph: a filter may have dropped the
+        // tasks this one is waiting on
+        let marker = task_marker(task, full_graph);
+        let mut task_line = TaskDisplayView::from_task(task, full_graph, default_gates).format_line(MAX_TITLE_LEN);
+        if let Some(note) = notes.get(task_id) {
+            task_line = format!("{} {}", task_line, note);
+        }
 
         let row = renderer.next_row(task_id.to_string(), ancestors, marker, task_line);
         output.push_str(&row);
@@ -410,7 +640,7 @@ fn build_ancestors(task_id: &str, effective_successors: &HashMap<&str, Vec<&str>
 }
 
 pub fn task_marker(task: &Task, graph: &TaskGraph) -> String {
-    let is_available = !task.is_complete() && !task.is_gate() && graph::is_available(task, graph);
+    let is_available = !task.is_closed() && !task.is_gate() && graph::is_available(task, graph);
     let is_in_progress = task.is_in_progress();
     let is_jot = task.is_jot();
 
@@ -418,8 +648,14 @@ pub fn task_marker(task: &Task, graph: &TaskGraph) -> String {
         "◈".purple().to_string()
     } else if task.is_complete() {
         "●".bright_black().to_string()
+    } else if task.is_cancelled() {
+        "✕".bright_black().to_string()
+    } else if task.is_epic() {
+        "◆".blue().to_string()
     } else if is_in_progress {
         "◐".yellow().to_string()
+    } else if task.is_blocked() {
+        "⊘".red().to_string()
     } else if is_jot {
         "◇".yellow().to_string()
     } else if is_available {
@@ -464,30 +700,17 @@ mod tests {
     fn make_task(id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
-            before: vec![],
-            after: vec![],
-            gates: vec![],
             title: Some(format!("{} title", id)),
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
     fn make_task_with_before(id: &str, before_id: &str) -> Task {
         Task {
             id: id.to_string(),
-            new_id: None,
             before: vec![before_id.to_string()],
-            after: vec![],
-            gates: vec![],
             title: Some(format!("{} title", id)),
-            status: None,
-            task_type: TaskType::Task,
-            description: String::new(),
-            deleted: false,
+            ..Default::default()
         }
     }
 
@@ -507,7 +730,7 @@ mod tests {
         let a = make_task_with_before("A", "B");
 
         let graph = build_graph(vec![a, b, c]);
-        let output = render_component(&graph, &Vec::<String>::new());
+        let output = render_component(&graph, &graph, &Vec::<String>::new(), &HashMap::new());
         let stripped = strip_ansi(&output);
 
         println!("\n=== Chain ===\n{}", stripped);
@@ -527,7 +750,7 @@ mod tests {
         b.after = vec!["P".to_string()];
 
         let graph = build_graph(vec![r, p, a, b]);
-        let output = render_component(&graph, &Vec::<String>::new());
+        let output = render_component(&graph, &graph, &Vec::<String>::new(), &HashMap::new());
         let stripped = strip_ansi(&output);
 
         println!("\n=== Diamond ===\n{}", stripped);
@@ -561,7 +784,7 @@ mod tests {
         let a = make_task_with_before("A", "Z");
 
         let graph = build_graph(vec![a, b, c, d, e, p, x, z]);
-        let output = render_component(&graph, &Vec::<String>::new());
+        let output = render_component(&graph, &graph, &Vec::<String>::new(), &HashMap::new());
         let stripped = strip_ansi(&output);
 
         println!("\n=== Parallel Diamond ===\n{}", stripped);
@@ -595,11 +818,134 @@ mod tests {
         gate.task_type = TaskType::Gate;
 
         let graph = build_graph(vec![root, jot_task, in_progress, completed, gate]);
-        let output = render_component(&graph, &Vec::<String>::new());
+        let output = render_component(&graph, &graph, &Vec::<String>::new(), &HashMap::new());
         let stripped = strip_ansi(&output);
 
         println!("\n=== Task Types and States ===\n{}", stripped);
 
         assert!(stripped.contains("[jot]"));
     }
+
+    #[test]
+    fn test_due_state_classification() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(DueState::classify(day(9), today), DueState::Overdue);
+        assert_eq!(DueState::classify(day(10), today), DueState::Soon);
+        assert_eq!(DueState::classify(day(13), today), DueState::Soon);
+        assert_eq!(DueState::classify(day(14), today), DueState::Later);
+    }
+
+    #[test]
+    fn test_format_line_shows_due_marker() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let task = make_task("late");
+        let graph: TaskGraph = vec![task.clone()].into_iter().collect();
+        let view = TaskDisplayView::from_task(&task, &graph, &[])
+            .with_due(NaiveDate::from_ymd_opt(2025, 3, 8), today);
+        assert!(strip_ansi(&view.format_line(MAX_TITLE_LEN)).ends_with("overdue 2d"));
+    }
+
+    #[test]
+    fn test_render_filters_by_assignee() {
+        let mut mine = make_task("mine");
+        mine.assignee = Some("alice".to_string());
+        let mut theirs = make_task("theirs");
+        theirs.assignee = Some("bob".to_string());
+        let graph: TaskGraph = vec![mine, theirs, make_task("nobody")].into_iter().collect();
+
+        let options = RenderOptions {
+            assignee: Some("alice".to_string()),
+            ..Default::default()
+        };
+        let output = strip_ansi(&render_task_graph(&graph, &[], &options));
+        assert!(output.contains("mine"));
+        assert!(output.contains("@alice"));
+        assert!(!output.contains("theirs"));
+        assert!(!output.contains("nobody"));
+    }
+
+    #[test]
+    fn test_render_keeps_state_when_blocker_filtered_out() {
+        // b waits on a; filtering a out mustn't make b look ready
+        let mut b = make_task("b");
+        b.after = vec!["a".to_string()];
+        b.assignee = Some("bob".to_string());
+        let graph: TaskGraph = vec![make_task("a"), b].into_iter().collect();
+
+        let options = RenderOptions {
+            assignee: Some("bob".to_string()),
+            ..Default::default()
+        };
+        let output = strip_ansi(&render_task_graph(&graph, &[], &options));
+        assert!(!output.contains("a title"));
+        assert!(output.contains("[wait] b"));
+    }
+
+    #[test]
+    fn test_render_filters_by_query() {
+        let mut api = make_task("api");
+        api.tags = vec!["backend".to_string()];
+        let graph: TaskGraph = vec![api, make_task("ui")].into_iter().collect();
+
+        let options = RenderOptions {
+            query: Some(Query::parse("tag:backend").unwrap()),
+            ..Default::default()
+        };
+        let output = strip_ansi(&render_task_graph(&graph, &[], &options));
+        assert!(output.contains("api"));
+        assert!(!output.contains("ui"));
+    }
+
+    #[test]
+    fn test_render_query_keeps_state_of_unmatched_blocker() {
+        let mut api = make_task("api");
+        api.after = vec!["db".to_string()];
+        api.tags = vec!["api".to_string()];
+        let graph: TaskGraph = vec![make_task("db"), api].into_iter().collect();
+
+        let options = RenderOptions {
+            query: Some(Query::parse("tag:api").unwrap()),
+            ..Default::default()
+        };
+        let output = strip_ansi(&render_task_graph(&graph, &[], &options));
+        assert!(!output.contains("db title"));
+        assert!(output.contains("[wait] api"));
+    }
+
+    #[test]
+    fn test_render_hides_cancelled_by_default() {
+        use crate::Status;
+
+        let mut dropped = make_task("dropped");
+        dropped.status = Some(Status::Cancelled);
+        let graph: TaskGraph = vec![dropped, make_task("kept")].into_iter().collect();
+
+        let output = strip_ansi(&render_task_graph(&graph, &[], &RenderOptions::default()));
+        assert!(output.contains("kept"));
+        assert!(!output.contains("dropped"));
+
+        let options = RenderOptions { show_cancelled: true, ..Default::default() };
+        let output = strip_ansi(&render_task_graph(&graph, &[], &options));
+        assert!(output.contains("[cncl] dropped"));
+    }
+
+    #[test]
+    fn test_render_cancelled_blocks_dependents() {
+        use crate::Status;
+
+        let mut dropped = make_task("dropped");
+        dropped.status = Some(Status::Cancelled);
+        let mut next = make_task("next");
+        next.after = vec!["dropped".to_string()];
+        let mut graph: TaskGraph = vec![dropped, next].into_iter().collect();
+
+        // By default a cancelled task releases its dependents
+        let output = strip_ansi(&render_task_graph(&graph, &[], &RenderOptions::default()));
+        assert!(output.contains("[task] next"));
+
+        graph.set_cancelled_blocks_dependents(true);
+        let output = strip_ansi(&render_task_graph(&graph, &[], &RenderOptions::default()));
+        assert!(output.contains("[wait] next"));
+    }
 }